@@ -15,11 +15,110 @@ export const commands = {
     filename: string | null,
     service: Service,
     apiKey: string,
+    validate: boolean,
   ): Promise<Result<null, Error>> {
     try {
       return {
         status: "ok",
-        data: await TAURI_INVOKE("set_api_key", { filename, service, apiKey }),
+        data: await TAURI_INVOKE("set_api_key", {
+          filename,
+          service,
+          apiKey,
+          validate,
+        }),
+      };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async setApiKeyOptions(
+    service: Service,
+    profile: string | null,
+    options: ApiKeyOptions,
+  ): Promise<Result<null, Error>> {
+    try {
+      return {
+        status: "ok",
+        data: await TAURI_INVOKE("set_api_key_options", {
+          service,
+          profile,
+          options,
+        }),
+      };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async setApiKeyProfile(
+    service: Service,
+    profile: string,
+    apiKey: string,
+  ): Promise<Result<null, Error>> {
+    try {
+      return {
+        status: "ok",
+        data: await TAURI_INVOKE("set_api_key_profile", {
+          service,
+          profile,
+          apiKey,
+        }),
+      };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async setActiveApiKeyProfile(
+    service: Service,
+    profile: string,
+  ): Promise<Result<null, Error>> {
+    try {
+      return {
+        status: "ok",
+        data: await TAURI_INVOKE("set_active_api_key_profile", {
+          service,
+          profile,
+        }),
+      };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async unlockApiKeys(passphrase: string): Promise<Result<null, Error>> {
+    try {
+      return {
+        status: "ok",
+        data: await TAURI_INVOKE("unlock_api_keys", { passphrase }),
+      };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async setApiKeyPassphrase(
+    passphrase: string | null,
+  ): Promise<Result<null, Error>> {
+    try {
+      return {
+        status: "ok",
+        data: await TAURI_INVOKE("set_api_key_passphrase", { passphrase }),
+      };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async testApiKey(
+    service: Service,
+    apiKey: string,
+  ): Promise<Result<null, Error>> {
+    try {
+      return {
+        status: "ok",
+        data: await TAURI_INVOKE("test_api_key", { service, apiKey }),
       };
     } catch (e) {
       if (e instanceof Error) throw e;
@@ -64,11 +163,119 @@ export const commands = {
   },
   async getApiCalls(
     offset: number,
+    filter: ApiCallFilter | null,
   ): Promise<Result<LightweightLlmCall[], Error>> {
     try {
       return {
         status: "ok",
-        data: await TAURI_INVOKE("get_api_calls", { offset }),
+        data: await TAURI_INVOKE("get_api_calls", { offset, filter }),
+      };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async getApiCallsPage(
+    cursor: PageCursor | null,
+    filter: ApiCallFilter | null,
+  ): Promise<Result<Page<LightweightLlmCall>, Error>> {
+    try {
+      return {
+        status: "ok",
+        data: await TAURI_INVOKE("get_api_calls_page", { cursor, filter }),
+      };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async deleteApiCall(id: string): Promise<Result<DeletedApiCalls, Error>> {
+    try {
+      return {
+        status: "ok",
+        data: await TAURI_INVOKE("delete_api_call", { id }),
+      };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async deleteConversation(
+    id: string,
+  ): Promise<Result<DeletedApiCalls, Error>> {
+    try {
+      return {
+        status: "ok",
+        data: await TAURI_INVOKE("delete_conversation", { id }),
+      };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async exportConversation(
+    id: string,
+    path: string,
+    format: TranscriptFormat,
+  ): Promise<Result<null, Error>> {
+    try {
+      return {
+        status: "ok",
+        data: await TAURI_INVOKE("export_conversation", { id, path, format }),
+      };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async restoreApiCalls(trashId: string): Promise<Result<EntityId[], Error>> {
+    try {
+      return {
+        status: "ok",
+        data: await TAURI_INVOKE("restore_api_calls", { trashId }),
+      };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async purgeDeletedApiCalls(
+    trashId: string,
+  ): Promise<Result<EntityId[], Error>> {
+    try {
+      return {
+        status: "ok",
+        data: await TAURI_INVOKE("purge_deleted_api_calls", { trashId }),
+      };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async setApiCallRating(
+    id: string,
+    rating: Rating,
+    idealCompletion: string | null,
+  ): Promise<Result<LlmCallRating, Error>> {
+    try {
+      return {
+        status: "ok",
+        data: await TAURI_INVOKE("set_api_call_rating", {
+          id,
+          rating,
+          idealCompletion,
+        }),
+      };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async clearApiCallRating(id: string): Promise<Result<null, Error>> {
+    try {
+      return {
+        status: "ok",
+        data: await TAURI_INVOKE("clear_api_call_rating", { id }),
       };
     } catch (e) {
       if (e instanceof Error) throw e;
@@ -83,9 +290,55 @@ export const commands = {
       else return { status: "error", error: e as any };
     }
   },
-  async exportDb(path: string): Promise<Result<DatabaseCounts, Error>> {
+  async importChatgpt(
+    path: string,
+  ): Promise<Result<DatabaseImportCounts, Error>> {
     try {
-      return { status: "ok", data: await TAURI_INVOKE("export_db", { path }) };
+      return {
+        status: "ok",
+        data: await TAURI_INVOKE("import_chatgpt", { path }),
+      };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async importJsonl(
+    path: string,
+  ): Promise<Result<DatabaseImportCounts, Error>> {
+    try {
+      return {
+        status: "ok",
+        data: await TAURI_INVOKE("import_jsonl", { path }),
+      };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async exportDb(
+    path: string,
+    includeUnencryptedKeys: boolean,
+  ): Promise<Result<DatabaseCounts, Error>> {
+    try {
+      return {
+        status: "ok",
+        data: await TAURI_INVOKE("export_db", { path, includeUnencryptedKeys }),
+      };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async exportFineTuningData(
+    path: string,
+    options: FineTuningExportOptions,
+  ): Promise<Result<FineTuningExportCounts, Error>> {
+    try {
+      return {
+        status: "ok",
+        data: await TAURI_INVOKE("export_fine_tuning_data", { path, options }),
+      };
     } catch (e) {
       if (e instanceof Error) throw e;
       else return { status: "error", error: e as any };
@@ -93,11 +346,12 @@ export const commands = {
   },
   async runCommand(
     command: string,
+    size: TerminalSize | null,
   ): Promise<Result<TerminalSessionInfo, Error>> {
     try {
       return {
         status: "ok",
-        data: await TAURI_INVOKE("run_command", { command }),
+        data: await TAURI_INVOKE("run_command", { command, size }),
       };
     } catch (e) {
       if (e instanceof Error) throw e;
@@ -118,6 +372,23 @@ export const commands = {
       else return { status: "error", error: e as any };
     }
   },
+  async resizeTerminalSession(
+    sessionId: string,
+    size: TerminalSize,
+  ): Promise<Result<null, Error>> {
+    try {
+      return {
+        status: "ok",
+        data: await TAURI_INVOKE("resize_terminal_session", {
+          sessionId,
+          size,
+        }),
+      };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
   async getTerminalSession(
     id: string,
   ): Promise<Result<TerminalSessionInfo, Error>> {
@@ -144,15 +415,123 @@ export const commands = {
       else return { status: "error", error: e as any };
     }
   },
+  async getTerminalSessionsPage(
+    cursor: PageCursor | null,
+  ): Promise<Result<Page<TerminalSessionReference>, Error>> {
+    try {
+      return {
+        status: "ok",
+        data: await TAURI_INVOKE("get_terminal_sessions_page", { cursor }),
+      };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async search(
+    query: string,
+    offset: number,
+  ): Promise<Result<SearchResult[], Error>> {
+    try {
+      return {
+        status: "ok",
+        data: await TAURI_INVOKE("search", { query, offset }),
+      };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async semanticSearch(
+    query: string,
+  ): Promise<Result<LightweightLlmCall[], Error>> {
+    try {
+      return {
+        status: "ok",
+        data: await TAURI_INVOKE("semantic_search", { query }),
+      };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
+  async getUsageStats(
+    startTime: string | null,
+    endTime: string | null,
+    period: UsagePeriod,
+  ): Promise<Result<UsageStats, Error>> {
+    try {
+      return {
+        status: "ok",
+        data: await TAURI_INVOKE("get_usage_stats", {
+          startTime,
+          endTime,
+          period,
+        }),
+      };
+    } catch (e) {
+      if (e instanceof Error) throw e;
+      else return { status: "error", error: e as any };
+    }
+  },
 };
 
 /** user-defined events **/
 
+export const events = __makeEvents__<{
+  terminalOutput: TerminalOutput;
+}>({
+  terminalOutput: "terminal-output",
+});
+
 /** user-defined constants **/
 
 /** user-defined types **/
 
-export type ApiKeys = { openai: string | null };
+export type ApiCallFilter = {
+  provider: Service | null;
+  model: string | null;
+  start_time: string | null;
+  end_time: string | null;
+  min_total_tokens: number | null;
+  conversation_roots_only: boolean | null;
+  has_variants: boolean | null;
+  rated: boolean | null;
+  rating: Rating | null;
+  sort_by: ApiCallSortKey | null;
+  sort_order: SortOrder | null;
+};
+export type ApiCallSortKey = "Timestamp" | "TotalTokens";
+export type ApiKeyOptions = {
+  organization?: string | null;
+  project?: string | null;
+  extra_headers?: { [key in string]: string };
+  endpoint?: string | null;
+  api_version?: string | null;
+  deployments?: { [key in string]: string };
+};
+export type ApiKeyProfile = {
+  service: Service;
+  name: string;
+  api_key: string;
+  options?: ApiKeyOptions;
+};
+export type ApiKeySource =
+  | "database"
+  | "environment"
+  | { dot_env: { path: string } };
+export type ApiKeys = {
+  openai: string | null;
+  openai_options?: ApiKeyOptions;
+  openai_profile?: string | null;
+  openai_source?: ApiKeySource | null;
+  azure?: string | null;
+  azure_options?: ApiKeyOptions;
+  azure_profile?: string | null;
+  azure_source?: ApiKeySource | null;
+  profiles?: ApiKeyProfile[];
+  locked?: boolean;
+};
 export type ChatArgs = {
   provider: Service;
   llm: string;
@@ -160,12 +539,17 @@ export type ChatArgs = {
   prompt: ChatMessage[];
   previous_call_id?: string | null;
   canonical_id?: string | null;
+  attachments?: string[];
+  key_profile?: string | null;
 };
 export type ChatMessage =
   | { role: "System"; text: string }
   | { role: "Human"; text: string }
   | { role: "AI"; text: string };
-export type ChatPrompt = { messages: ChatMessage[] };
+export type ChatPrompt = {
+  messages: ChatMessage[];
+  attachments?: FileAttachment[];
+};
 export type ConversationMetadata = {
   previous_call?: LlmCallReference | null;
   next_calls?: LlmCallReference[];
@@ -179,10 +563,18 @@ export type DatabaseImportCounts = {
   imported?: DatabaseCounts;
   ignored?: DatabaseCounts;
 };
+export type DeletedApiCalls = {
+  trash_id: EntityId;
+  deleted_at: string;
+  llm_call_ids: EntityId[];
+};
 export type EntityId = string;
 export type Error =
   | { UnexpectedOpenAiResponse: { reason: string } }
   | { MissingApiKey: { service: Service } }
+  | { InvalidApiKey: { service: Service } }
+  | { ApiKeyQuotaExceeded: { service: Service } }
+  | { NoNetwork: { service: Service; reason: string } }
   | { FutureZammImport: { version: string; import_error: ImportError } }
   | { GenericImport: { source: ImportError } }
   | { Poison: Record<string, never> }
@@ -196,13 +588,31 @@ export type Error =
   | { Tauri: string }
   | { Io: string }
   | { Other: string };
+export type FileAttachment = { path: string; sha256: string };
+export type FineTuningExportCounts = {
+  num_examples: number;
+  num_skipped?: number;
+};
+export type FineTuningExportOptions = {
+  ids: EntityId[] | null;
+  filter: ApiCallFilter | null;
+  drop_system_messages: boolean | null;
+  latest_leaves_only: boolean | null;
+};
 export type ImportError = { UnknownPromptType: Record<string, never> };
 export type LightweightLlmCall = {
   id: EntityId;
   timestamp: string;
   response_message: ChatMessage;
+  prompt?: ChatMessage[] | null;
+};
+export type Llm = {
+  name: string;
+  requested: string;
+  provider: Service;
+  deployment?: string | null;
+  key_profile?: string | null;
 };
-export type Llm = { name: string; requested: string; provider: Service };
 export type LlmCall = {
   id: EntityId;
   timestamp: string;
@@ -212,9 +622,31 @@ export type LlmCall = {
   tokens: TokenMetadata;
   conversation?: ConversationMetadata;
   variation?: VariantMetadata;
+  attribution?: LlmCallAttribution | null;
+};
+export type LlmCallAttribution = {
+  organization: string | null;
+  project: string | null;
+  extra_header_names: string | null;
+};
+export type LlmCallRating = {
+  llm_call_id: EntityId;
+  rating: Rating;
+  ideal_completion?: string | null;
 };
 export type LlmCallReference = { id: EntityId; snippet: string };
+export type LlmCallUsage = {
+  period_start: string;
+  provider: Service;
+  llm: string;
+  num_calls: number;
+  prompt_tokens: number;
+  response_tokens: number;
+  average_latency_ms: number | null;
+};
 export type OS = "Mac" | "Linux" | "Windows";
+export type Page<T> = { items: T[]; next_cursor: PageCursor | null };
+export type PageCursor = { timestamp: string; id: EntityId };
 export type Preferences = {
   version?: string | null;
   animations_on?: boolean | null;
@@ -224,17 +656,38 @@ export type Preferences = {
   high_dpi_adjust?: boolean | null;
   sound_on?: boolean | null;
   volume?: number | null;
+  trash_retention_days?: number | null;
+  proxy_url?: string | null;
+  no_proxy?: string | null;
+  ca_bundle_path?: string | null;
+  request_timeout_secs?: number | null;
+  dotenv_files?: string[] | null;
+  background_embeddings?: boolean | null;
 };
 export type Prompt = ({ type: "Chat" } & ChatPrompt) | { type: "Unknown" };
-export type Request = { prompt: Prompt; temperature: number };
+export type Rating = "Good" | "Bad";
+export type Request = { prompt: Prompt; temperature: number | null };
 export type Response = { completion: ChatMessage };
 export type RodioError =
   | { Stream: string }
   | { Decode: string }
   | { Play: string };
+export type SearchEntityType = "LlmCall" | "TerminalSession";
+export type SearchResult = {
+  id: EntityId;
+  entity_type: SearchEntityType;
+  timestamp: string;
+  snippet: string;
+};
 export type SerdeError = { Json: string } | { Yaml: string } | { Toml: string };
-export type Service = "OpenAI" | "Ollama" | { Unknown: string };
-export type Shell = "Bash" | "Zsh" | "PowerShell";
+export type Service =
+  | "OpenAI"
+  | "Ollama"
+  | "Azure"
+  | "Mock"
+  | { Unknown: string };
+export type Shell = "Bash" | "Zsh" | "Fish" | "PowerShell";
+export type SortOrder = "Ascending" | "Descending";
 export type Sound = "Switch" | "Whoosh";
 export type SystemInfo = {
   zamm_version: string;
@@ -242,6 +695,11 @@ export type SystemInfo = {
   shell: Shell | null;
   shell_init_file: string | null;
 };
+export type TerminalOutput = {
+  session_id: EntityId;
+  from_line: number;
+  output: string;
+};
 export type TerminalSessionInfo = {
   id: EntityId;
   timestamp: string;
@@ -256,11 +714,19 @@ export type TerminalSessionReference = {
   command: string;
   last_io: string | null;
 };
+export type TerminalSessionUsage = { date: string; num_sessions: number };
+export type TerminalSize = { rows: number; cols: number };
 export type TokenMetadata = {
   prompt: number | null;
   response: number | null;
   total: number | null;
 };
+export type TranscriptFormat = "Markdown" | "Html";
+export type UsagePeriod = "Day" | "Week";
+export type UsageStats = {
+  llm_calls: LlmCallUsage[];
+  terminal_sessions: TerminalSessionUsage[];
+};
 export type VariantMetadata = {
   canonical?: LlmCallReference | null;
   variants?: LlmCallReference[];
@@ -81,6 +81,17 @@ describe("API Keys Display", () => {
     );
   });
 
+  test("shows where the API key came from", async () => {
+    await checkSampleCall(
+      "../src-tauri/api/sample-calls/get_api_keys-openai.yaml",
+      "Active",
+    );
+
+    const openAiRow = screen.getByRole("row", { name: /OpenAI/ });
+    const openAiKeyCell = within(openAiRow).getAllByRole("cell")[1];
+    expect(openAiKeyCell).toHaveAttribute("title", "Saved in ZAMM");
+  });
+
   test("some API key set", async () => {
     systemInfo.set({
       ...NullSystemInfo,
@@ -323,4 +334,35 @@ describe("API Keys Display", () => {
     expect(alerts).toHaveLength(1);
     expect(alerts[0]).toHaveTextContent("Is a directory (os error 21)");
   });
+
+  test("reports a rejected key when checking before saving", async () => {
+    systemInfo.set({
+      ...NullSystemInfo,
+    });
+    await checkSampleCall(
+      "../src-tauri/api/sample-calls/get_api_keys-empty.yaml",
+      "Inactive",
+    );
+    tauriInvokeMock.mockClear();
+    playback.addSamples(
+      "../src-tauri/api/sample-calls/set_api_key-invalid-key.yaml",
+      "../src-tauri/api/sample-calls/get_api_keys-empty.yaml",
+    );
+
+    await toggleOpenAIForm();
+    await userEvent.click(
+      screen.getByLabelText("Export as environment variable?"),
+    );
+    await userEvent.click(screen.getByLabelText("Check key before saving"));
+    await userEvent.type(screen.getByLabelText("API key:"), "bad-4p1-k3y");
+    await userEvent.click(screen.getByRole("button", { name: "Save" }));
+    await waitFor(() => expect(tauriInvokeMock).toHaveBeenCalledTimes(2));
+    expect(getOpenAiStatus()).toBe("Inactive");
+    expect(tauriInvokeMock).toHaveReturnedTimes(1);
+
+    render(Snackbar, {});
+    const alerts = screen.queryAllByRole("alertdialog");
+    expect(alerts).toHaveLength(1);
+    expect(alerts[0]).toHaveTextContent("open_ai rejected the API key");
+  });
 });
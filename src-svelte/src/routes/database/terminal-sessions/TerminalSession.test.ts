@@ -85,4 +85,56 @@ describe("Terminal session", () => {
       expect(screen.getByText(new RegExp("stderr"))).toBeInTheDocument();
     });
   });
+
+  test("shows output that streams in after the command starts", async () => {
+    const outputListeners: ((event: unknown) => void)[] = [];
+    window.__TAURI_INTERNALS__.transformCallback = (
+      callback: (event: unknown) => void,
+    ) => {
+      outputListeners.push(callback);
+      return outputListeners.length;
+    };
+    tauriInvokeMock.mockImplementation(
+      (...args: (string | Record<string, string>)[]) =>
+        args[0] === "plugin:event|listen"
+          ? Promise.resolve(outputListeners.length)
+          : playback.mockCall(...args),
+    );
+
+    render(TerminalSession, {});
+    playback.addSamples("../src-tauri/api/sample-calls/run_command-bash.yaml");
+    const commandInput = screen.getByLabelText("Enter command to run");
+    await userEvent.type(commandInput, "bash");
+    await userEvent.click(screen.getByRole("button", { name: "Send" }));
+    await waitFor(() => {
+      expect(
+        screen.getByText(
+          new RegExp("The default interactive shell is now zsh"),
+        ),
+      ).toBeInTheDocument();
+    });
+
+    expect(outputListeners).toHaveLength(1);
+    outputListeners[0]({
+      event: "terminal-output",
+      id: 1,
+      payload: {
+        session_id: "3717ed48-ab52-4654-9f33-de5797af5118",
+        from_line: 3,
+        output: "bash-3.2$ echo printed after a pause\nprinted after a pause\n",
+      },
+    });
+    await waitFor(() => {
+      expect(
+        screen.getByText(new RegExp("printed after a pause")),
+      ).toBeInTheDocument();
+    });
+    // lines that have scrolled off stay, and the prompt that got typed into
+    // gets replaced rather than added to
+    const output = screen.getByText(new RegExp("printed after a pause"));
+    expect(output).toHaveTextContent(
+      new RegExp("The default interactive shell is now zsh"),
+    );
+    expect(output.textContent).not.toContain("bash-3.2$ bash-3.2$");
+  });
 });
@@ -64,16 +64,37 @@ describe("Individual API call", () => {
 
   test("can export API keys", async () => {
     mockFilePicker("save", "different.zamm.yaml");
-    playback.addSamples("../src-tauri/api/sample-calls/export_db-api-key.yaml");
+    playback.addSamples(
+      "../src-tauri/api/sample-calls/play_sound-switch.yaml",
+      "../src-tauri/api/sample-calls/export_db-api-key.yaml",
+    );
     render(Database, {});
 
+    const includeKeysSwitch = screen.getByRole("switch");
+    await act(() => userEvent.click(includeKeysSwitch));
+    await waitFor(() => expect(tauriInvokeMock).toHaveReturnedTimes(1));
+
     const exportButton = screen.getByText("Export data");
     await act(() => userEvent.click(exportButton));
-    await waitFor(() => expect(tauriInvokeMock).toHaveReturnedTimes(2));
+    await waitFor(() => expect(tauriInvokeMock).toHaveReturnedTimes(3));
 
     await checkForAlert("Exported 1 API key");
   });
 
+  test("leaves out unencrypted API keys by default", async () => {
+    mockFilePicker("save", "different.zamm.yaml");
+    playback.addSamples(
+      "../src-tauri/api/sample-calls/export_db-api-key-omitted.yaml",
+    );
+    render(Database, {});
+
+    const exportButton = screen.getByText("Export data");
+    await act(() => userEvent.click(exportButton));
+    await waitFor(() => expect(tauriInvokeMock).toHaveReturnedTimes(2));
+
+    await checkForAlert("Exported 0 items");
+  });
+
   test("can export terminal sessions", async () => {
     mockFilePicker("save", "exported-db.yaml");
     playback.addSamples(
@@ -0,0 +1,2 @@
+INSERT INTO api_key_options VALUES('azure','default','{"endpoint":"https://zamm.openai.azure.com","deployments":{"gpt-4":"zamm-gpt-4"}}');
+INSERT INTO api_keys VALUES('azure','default','4zur3-4p1-k3y');
\ No newline at end of file
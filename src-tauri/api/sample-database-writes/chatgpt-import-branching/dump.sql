@@ -0,0 +1,5 @@
+INSERT INTO llm_calls VALUES('0f3a9c1e-2b4d-4e6f-8a1b-3c5d7e9f1a2b','2024-06-10 08:00:05.250','chatgpt','gpt-4o','gpt-4o',NULL,NULL,NULL,NULL,'{"type":"Chat","messages":[{"role":"Human","text":"Write me a haiku about the sea."}]}','{"role":"AI","text":"Waves fold into foam,\nsalt wind carries gull voices,\nthe tide keeps its time."}');
+INSERT INTO llm_calls VALUES('7c2e4a6b-8d0f-4b2d-9e4f-6a8c0e2a4c6e','2024-06-10 08:01:10.750','chatgpt','gpt-4o','gpt-4o',NULL,NULL,NULL,NULL,'{"type":"Chat","messages":[{"role":"Human","text":"Write me a haiku about the sea."}]}','{"role":"AI","text":"Grey swells rise and break,\nmoonlight spills across the deep,\nshells hum the old song."}');
+INSERT INTO llm_calls VALUES('3b5d7f91-a3c5-4e7a-8c1e-5f7a9b1d3f5a','2024-06-10 08:02:10.654321','chatgpt','gpt-4o','gpt-4o',NULL,NULL,NULL,NULL,'{"type":"Chat","messages":[{"role":"Human","text":"Write me a haiku about the sea."},{"role":"AI","text":"Grey swells rise and break,\nmoonlight spills across the deep,\nshells hum the old song."},{"role":"Human","text":"Now give it a title."}]}','{"role":"AI","text":"\"Song of the Deep\""}');
+INSERT INTO llm_call_follow_ups VALUES('7c2e4a6b-8d0f-4b2d-9e4f-6a8c0e2a4c6e','3b5d7f91-a3c5-4e7a-8c1e-5f7a9b1d3f5a');
+INSERT INTO llm_call_variants VALUES('0f3a9c1e-2b4d-4e6f-8a1b-3c5d7e9f1a2b','7c2e4a6b-8d0f-4b2d-9e4f-6a8c0e2a4c6e');
\ No newline at end of file
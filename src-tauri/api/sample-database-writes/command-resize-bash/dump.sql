@@ -0,0 +1 @@
+INSERT INTO asciicasts VALUES('f7c5bb3f-69ec-4687-9d31-1f5765dedb07','2026-10-19 06:30:53','bash --norc --noprofile','Linux',replace('{"version":2,"width":80,"height":24,"timestamp":1792391453,"command":"bash --norc --noprofile"}\012[0.001,"o","\u001b[?2004h"]\012[0.001,"o","bash-5.2# "]\012[1.0,"r","120x40"]','\012',char(10)));
\ No newline at end of file
@@ -0,0 +1 @@
+INSERT INTO asciicasts VALUES('8511d3ee-b4e9-4c25-87e0-bac66dbc96c6','2026-10-19 06:27:28','python api/sample-terminal-sessions/unicode.py','Linux',replace('{"version":2,"width":80,"height":24,"timestamp":1792391248,"command":"python api/sample-terminal-sessions/unicode.py"}\012[0.123,"o","សួស្តី ពិភពលោក"]\012[0.123,"o","\r\n┌────┐\r\n│ ok │\r\n└────┘\r\n"]\012[0.223,"o","👋\r\n"]','\012',char(10)));
\ No newline at end of file
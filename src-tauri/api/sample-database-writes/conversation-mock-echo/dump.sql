@@ -0,0 +1 @@
+INSERT INTO llm_calls VALUES('3a0c1d7e-52b4-4f8a-9e16-b8d2f4a7c905','2024-07-03 09:15:42.108357200','mock','echo','echo',1.0,14,4,18,'{"type":"Chat","messages":[{"role":"System","text":"You are ZAMM, a chat program. Respond in first person."},{"role":"Human","text":"Hello, does this work?"}]}','{"role":"AI","text":"Hello, does this work?"}');
\ No newline at end of file
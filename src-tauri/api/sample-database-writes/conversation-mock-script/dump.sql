@@ -0,0 +1 @@
+INSERT INTO llm_calls VALUES('b61e8f24-9c3a-4d57-8e0b-2f7a1c6d5e93','2024-07-03 09:16:05.734921800','mock','script:script.yaml','script:script.yaml',1.0,14,10,24,'{"type":"Chat","messages":[{"role":"System","text":"You are ZAMM, a chat program. Respond in first person."},{"role":"Human","text":"Tell me a joke."}]}','{"role":"AI","text":"Why don''t scientists trust atoms? Because they make up everything!"}');
\ No newline at end of file
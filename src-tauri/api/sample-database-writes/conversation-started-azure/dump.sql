@@ -0,0 +1,4 @@
+INSERT INTO llm_calls VALUES('d5ad1e49-f57f-4481-84fb-4d70ba8a7a74','2024-01-16 08:50:19.738093890','azure','gpt-4','gpt-4-0613',1.0,32,12,44,'{"type":"Chat","messages":[{"role":"System","text":"You are ZAMM, a chat program. Respond in first person."},{"role":"Human","text":"Hello, does this work?"}]}','{"role":"AI","text":"Yes, it works. How can I assist you today?"}');
+INSERT INTO api_key_options VALUES('azure','default','{"endpoint":"https://zamm.openai.azure.com","deployments":{"gpt-4":"zamm-gpt-4"}}');
+INSERT INTO llm_call_deployments VALUES('d5ad1e49-f57f-4481-84fb-4d70ba8a7a74','zamm-gpt-4');
+INSERT INTO api_keys VALUES('azure','default','4zur3-4p1-k3y');
\ No newline at end of file
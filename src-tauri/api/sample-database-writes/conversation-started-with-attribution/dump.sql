@@ -0,0 +1,4 @@
+INSERT INTO llm_calls VALUES('d5ad1e49-f57f-4481-84fb-4d70ba8a7a74','2024-01-16 08:50:19.738093890','open_ai','gpt-4','gpt-4-0613',1.0,32,12,44,'{"type":"Chat","messages":[{"role":"System","text":"You are ZAMM, a chat program. Respond in first person."},{"role":"Human","text":"Hello, does this work?"}]}','{"role":"AI","text":"Yes, it works. How can I assist you today?"}');
+INSERT INTO api_key_options VALUES('open_ai','default','{"organization":"org-zamm","project":"proj_chat","extra_headers":{"X-Team":"research"}}');
+INSERT INTO llm_call_attributions VALUES('d5ad1e49-f57f-4481-84fb-4d70ba8a7a74','org-zamm','proj_chat','X-Team');
+INSERT INTO api_keys VALUES('open_ai','default','0p3n41-4p1-k3y');
\ No newline at end of file
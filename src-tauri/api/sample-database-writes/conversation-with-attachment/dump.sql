@@ -0,0 +1 @@
+INSERT INTO llm_calls VALUES('8f1c6d2a-4b7e-4e59-9a3d-2c5f0b6e1d47','2024-07-02 11:00:06.412876300','open_ai','gpt-4','gpt-4-0613',1.0,71,45,116,'{"type":"Chat","messages":[{"role":"System","text":"You are ZAMM, a chat program. Respond in first person."},{"role":"Human","text":"<file path=\"fibonacci.py\">\ndef fibonacci(n):\n    a, b = 0, 1\n    for _ in range(n):\n        a, b = b, a + b\n    return a\n</file>"},{"role":"Human","text":"What does this function do?"}],"attachments":[{"path":"fibonacci.py","sha256":"ceae3621f9d9a6e0c8938e0f5b51d1ee96ff9329a95a85c70cb13b3f4e919915"}]}','{"role":"AI","text":"It returns the nth Fibonacci number. Starting from 0 and 1, I step through the sequence n times, each time replacing the pair with the next two numbers, so fibonacci(10) would give you 55."}');
\ No newline at end of file
@@ -0,0 +1,2 @@
+INSERT INTO api_keys VALUES('open_ai','default','enc:v1:R5E0PwowI/W2wTTvpzh+jYe7SAdcOAtZJzWjOU/wRkzEK7ko6TWUhdWL');
+INSERT INTO api_key_encryption VALUES('oR8rL+ipiavoizE77WWXFQ==','enc:v1:zpKodRNcLo33iPf8eRUzjvQvEaCEqulA0pcP4dCt7yE=');
\ No newline at end of file
@@ -0,0 +1,2 @@
+INSERT INTO llm_calls VALUES('9dd44407-39bb-80b0-96a4-b18dc187c712','2024-07-01 12:00:00','dataset','dataset','dataset',1.0,NULL,NULL,NULL,'{"type":"Chat","messages":[{"role":"System","text":"You are a helpful assistant that answers in one sentence."},{"role":"Human","text":"What is the capital of France?"}]}','{"role":"AI","text":"The capital of France is Paris."}');
+INSERT INTO llm_calls VALUES('fb3f5bc4-0283-89ed-851f-80bb904a24be','2024-07-01 12:00:00','dataset','dataset','dataset',1.0,NULL,NULL,NULL,'{"type":"Chat","messages":[{"role":"Human","text":"Translate \"good morning\" into Spanish."}]}','{"role":"AI","text":"\"Good morning\" in Spanish is \"buenos días\"."}');
\ No newline at end of file
@@ -0,0 +1,2 @@
+INSERT INTO api_key_options VALUES('open_ai','default','{"organization":"org-zamm","project":"proj_chat","extra_headers":{"X-Team":"research"}}');
+INSERT INTO api_keys VALUES('open_ai','default','0p3n41-4p1-k3y');
\ No newline at end of file
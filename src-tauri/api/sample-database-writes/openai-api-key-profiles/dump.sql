@@ -0,0 +1,2 @@
+INSERT INTO api_keys VALUES('open_ai','default','0p3n41-4p1-k3y');
+INSERT INTO api_keys VALUES('open_ai','team','t34m-4p1-k3y');
\ No newline at end of file
@@ -0,0 +1,3 @@
+INSERT INTO api_keys VALUES('open_ai','default','0p3n41-4p1-k3y');
+INSERT INTO api_keys VALUES('open_ai','team','t34m-4p1-k3y');
+INSERT INTO active_api_key_profiles VALUES('open_ai','team');
\ No newline at end of file
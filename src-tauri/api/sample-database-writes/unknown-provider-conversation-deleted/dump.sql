@@ -0,0 +1,2 @@
+INSERT INTO llm_calls VALUES('037b28dd-6f24-4e68-9dfb-3caa1889d886','2024-07-29 17:30:11.073212','Unknown Future Provider','unknown-future-llm','unknown-future-llm',1.0,47,14,61,'{"type":"UnknownFutureType","unknown_field":"Fuck you!"}','{"role":"AI","text":"I''m sorry to hear that. How can I assist you better?"}');
+INSERT INTO llm_call_trash VALUES('5e2fa1c8-9d3b-4b7e-8f64-0c1d2e3f4a5b','2024-08-01 12:00:00','{"llm_calls":{"instances":[{"id":"d5ad1e49-f57f-4481-84fb-4d70ba8a7a74","timestamp":"2024-01-16T08:50:19.738093890","provider":"OpenAI","llm_requested":"gpt-4","llm":"gpt-4-0613","temperature":1.0,"prompt_tokens":32,"response_tokens":12,"total_tokens":44,"prompt":{"type":"Chat","messages":[{"role":"System","text":"You are ZAMM, a chat program. Respond in first person."},{"role":"Human","text":"Hello, does this work?"}]},"completion":{"role":"AI","text":"Yes, it works. How can I assist you today?"}},{"id":"c13c1e67-2de3-48de-a34c-a32079c03316","timestamp":"2024-01-16T09:50:19.738093890","provider":"OpenAI","llm_requested":"gpt-4","llm":"gpt-4-0613","temperature":1.0,"prompt_tokens":57,"response_tokens":22,"total_tokens":79,"prompt":{"type":"Chat","messages":[{"role":"System","text":"You are ZAMM, a chat program. Respond in first person."},{"role":"Human","text":"Hello, does this work?"},{"role":"AI","text":"Yes, it works. How can I assist you today?"},{"role":"Human","text":"Tell me something funny."}]},"completion":{"role":"AI","text":"Sure, here''s a joke for you: Why don''t scientists trust atoms? Because they make up everything!"}}],"follow_ups":[{"previous_call_id":"d5ad1e49-f57f-4481-84fb-4d70ba8a7a74","next_call_id":"c13c1e67-2de3-48de-a34c-a32079c03316"}]}}');
\ No newline at end of file
@@ -0,0 +1 @@
+$env:OPENAI_API_KEY = "0p3n41-4p1-k3y" # zamm
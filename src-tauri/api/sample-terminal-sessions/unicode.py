@@ -0,0 +1,18 @@
+#!/usr/bin/env python3
+
+import sys
+import time
+
+sys.stdout.reconfigure(encoding="utf-8")
+
+print("សួស្តី ពិភពលោក")
+print("┌────┐\n│ ok │\n└────┘")
+sys.stdout.flush()
+
+# split a character across two writes, so that it arrives in separate reads
+wave = "👋\n".encode("utf-8")
+sys.stdout.buffer.write(wave[:2])
+sys.stdout.flush()
+time.sleep(0.1)
+sys.stdout.buffer.write(wave[2:])
+sys.stdout.flush()
@@ -0,0 +1,5 @@
+DROP TRIGGER asciicasts_search_delete;
+DROP TRIGGER llm_calls_search_delete;
+DROP TRIGGER llm_calls_search_update;
+DROP TRIGGER llm_calls_search_insert;
+DROP TABLE search_index;
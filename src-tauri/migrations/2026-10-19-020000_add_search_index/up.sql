@@ -0,0 +1,63 @@
+CREATE VIRTUAL TABLE search_index USING fts5(
+  entity_id UNINDEXED,
+  entity_type UNINDEXED,
+  content,
+  tokenize = 'porter unicode61'
+);
+
+-- LLM calls are indexed entirely within SQLite. Terminal sessions need their
+-- escape sequences cleaned first, so those are indexed by the app on write.
+CREATE TRIGGER llm_calls_search_insert AFTER INSERT ON llm_calls BEGIN
+  INSERT INTO search_index (entity_id, entity_type, content)
+  VALUES (
+    NEW.id,
+    'llm_call',
+    COALESCE(
+      (
+        SELECT group_concat(json_extract(value, '$.text'), char(10))
+        FROM json_each(NEW.prompt, '$.messages')
+      ),
+      ''
+    ) || char(10) || COALESCE(json_extract(NEW.completion, '$.text'), '')
+  );
+END;
+
+CREATE TRIGGER llm_calls_search_update AFTER UPDATE OF prompt, completion ON llm_calls BEGIN
+  DELETE FROM search_index
+    WHERE entity_type = 'llm_call' AND entity_id = OLD.id;
+  INSERT INTO search_index (entity_id, entity_type, content)
+  VALUES (
+    NEW.id,
+    'llm_call',
+    COALESCE(
+      (
+        SELECT group_concat(json_extract(value, '$.text'), char(10))
+        FROM json_each(NEW.prompt, '$.messages')
+      ),
+      ''
+    ) || char(10) || COALESCE(json_extract(NEW.completion, '$.text'), '')
+  );
+END;
+
+CREATE TRIGGER llm_calls_search_delete AFTER DELETE ON llm_calls BEGIN
+  DELETE FROM search_index
+    WHERE entity_type = 'llm_call' AND entity_id = OLD.id;
+END;
+
+CREATE TRIGGER asciicasts_search_delete AFTER DELETE ON asciicasts BEGIN
+  DELETE FROM search_index
+    WHERE entity_type = 'terminal_session' AND entity_id = OLD.id;
+END;
+
+INSERT INTO search_index (entity_id, entity_type, content)
+  SELECT
+    id,
+    'llm_call',
+    COALESCE(
+      (
+        SELECT group_concat(json_extract(value, '$.text'), char(10))
+        FROM json_each(llm_calls.prompt, '$.messages')
+      ),
+      ''
+    ) || char(10) || COALESCE(json_extract(completion, '$.text'), '')
+  FROM llm_calls;
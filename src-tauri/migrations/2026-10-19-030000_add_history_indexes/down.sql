@@ -0,0 +1,2 @@
+DROP INDEX asciicasts_timestamp_id;
+DROP INDEX llm_calls_timestamp_id;
@@ -0,0 +1,2 @@
+CREATE INDEX llm_calls_timestamp_id ON llm_calls (timestamp, id);
+CREATE INDEX asciicasts_timestamp_id ON asciicasts (timestamp, id);
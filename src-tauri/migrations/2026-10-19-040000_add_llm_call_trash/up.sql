@@ -0,0 +1,5 @@
+CREATE TABLE llm_call_trash (
+  id VARCHAR PRIMARY KEY NOT NULL,
+  deleted_at DATETIME NOT NULL,
+  contents TEXT NOT NULL
+);
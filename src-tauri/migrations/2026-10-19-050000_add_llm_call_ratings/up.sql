@@ -0,0 +1,6 @@
+CREATE TABLE llm_call_ratings (
+  llm_call_id VARCHAR PRIMARY KEY NOT NULL,
+  rating VARCHAR NOT NULL,
+  ideal_completion TEXT,
+  FOREIGN KEY (llm_call_id) REFERENCES llm_calls (id) ON DELETE CASCADE
+);
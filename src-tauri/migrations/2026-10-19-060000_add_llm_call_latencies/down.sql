@@ -0,0 +1 @@
+DROP TABLE llm_call_latencies;
@@ -0,0 +1,8 @@
+CREATE TABLE llm_call_embeddings (
+  llm_call_id VARCHAR NOT NULL,
+  model VARCHAR NOT NULL,
+  kind VARCHAR NOT NULL,
+  embedding BLOB NOT NULL,
+  PRIMARY KEY (llm_call_id, model, kind),
+  FOREIGN KEY (llm_call_id) REFERENCES llm_calls (id) ON DELETE CASCADE
+);
@@ -0,0 +1,2 @@
+DROP TABLE llm_call_attributions;
+DROP TABLE api_key_options;
@@ -0,0 +1,12 @@
+CREATE TABLE api_key_options (
+  service VARCHAR PRIMARY KEY NOT NULL,
+  options TEXT NOT NULL
+);
+
+CREATE TABLE llm_call_attributions (
+  llm_call_id VARCHAR PRIMARY KEY NOT NULL,
+  organization VARCHAR,
+  project VARCHAR,
+  extra_header_names VARCHAR,
+  FOREIGN KEY (llm_call_id) REFERENCES llm_calls (id) ON DELETE CASCADE
+);
@@ -0,0 +1,5 @@
+CREATE TABLE llm_call_deployments (
+  llm_call_id VARCHAR PRIMARY KEY NOT NULL,
+  deployment VARCHAR NOT NULL,
+  FOREIGN KEY (llm_call_id) REFERENCES llm_calls (id) ON DELETE CASCADE
+);
@@ -0,0 +1,11 @@
+DROP TABLE llm_call_key_profiles;
+DROP TABLE active_api_key_profiles;
+
+CREATE TABLE api_keys_single (
+  service VARCHAR PRIMARY KEY NOT NULL,
+  api_key VARCHAR NOT NULL
+);
+INSERT INTO api_keys_single (service, api_key)
+  SELECT service, api_key FROM api_keys WHERE profile = 'default';
+DROP TABLE api_keys;
+ALTER TABLE api_keys_single RENAME TO api_keys;
@@ -0,0 +1,21 @@
+CREATE TABLE api_key_profiles (
+  service VARCHAR NOT NULL,
+  profile VARCHAR NOT NULL,
+  api_key VARCHAR NOT NULL,
+  PRIMARY KEY (service, profile)
+);
+INSERT INTO api_key_profiles (service, profile, api_key)
+  SELECT service, 'default', api_key FROM api_keys;
+DROP TABLE api_keys;
+ALTER TABLE api_key_profiles RENAME TO api_keys;
+
+CREATE TABLE active_api_key_profiles (
+  service VARCHAR PRIMARY KEY NOT NULL,
+  profile VARCHAR NOT NULL
+);
+
+CREATE TABLE llm_call_key_profiles (
+  llm_call_id VARCHAR PRIMARY KEY NOT NULL,
+  profile VARCHAR NOT NULL,
+  FOREIGN KEY (llm_call_id) REFERENCES llm_calls (id) ON DELETE CASCADE
+);
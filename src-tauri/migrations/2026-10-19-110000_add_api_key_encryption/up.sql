@@ -0,0 +1,4 @@
+CREATE TABLE api_key_encryption (
+  salt VARCHAR PRIMARY KEY NOT NULL,
+  verifier VARCHAR NOT NULL
+);
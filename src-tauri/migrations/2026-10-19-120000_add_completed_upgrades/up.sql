@@ -0,0 +1,3 @@
+CREATE TABLE completed_upgrades (
+  name VARCHAR PRIMARY KEY NOT NULL
+);
@@ -0,0 +1,90 @@
+-- SQLite can't drop NOT NULL from a column, so the table gets rebuilt, along
+-- with the views, index and triggers that go away with the old one
+DROP VIEW llm_call_named_follow_ups;
+DROP VIEW llm_call_named_variants;
+
+CREATE TABLE llm_calls_new (
+  id VARCHAR PRIMARY KEY NOT NULL,
+  timestamp DATETIME DEFAULT CURRENT_TIMESTAMP NOT NULL,
+  provider VARCHAR NOT NULL,
+  llm_requested VARCHAR NOT NULL,
+  llm VARCHAR NOT NULL,
+  temperature REAL,
+  prompt_tokens INTEGER,
+  response_tokens INTEGER,
+  total_tokens INTEGER,
+  prompt TEXT NOT NULL,
+  completion TEXT NOT NULL
+);
+
+INSERT INTO llm_calls_new
+  SELECT
+    id, timestamp, provider, llm_requested, llm, temperature, prompt_tokens,
+    response_tokens, total_tokens, prompt, completion
+  FROM llm_calls;
+
+DROP TABLE llm_calls;
+ALTER TABLE llm_calls_new RENAME TO llm_calls;
+
+CREATE INDEX llm_calls_timestamp_id ON llm_calls (timestamp, id);
+
+CREATE VIEW llm_call_named_follow_ups AS
+  SELECT
+    llm_call_follow_ups.previous_call_id AS previous_call_id,
+    previous_call.completion AS previous_call_completion,
+    llm_call_follow_ups.next_call_id AS next_call_id,
+    next_call.completion AS next_call_completion
+  FROM
+    llm_call_follow_ups
+    JOIN llm_calls AS previous_call ON llm_call_follow_ups.previous_call_id = previous_call.id
+    JOIN llm_calls AS next_call ON llm_call_follow_ups.next_call_id = next_call.id
+  ORDER BY next_call.timestamp ASC;
+
+CREATE VIEW llm_call_named_variants AS
+  SELECT
+    llm_call_variants.canonical_id AS canonical_id,
+    canonical.completion AS canonical_completion,
+    llm_call_variants.variant_id AS variant_id,
+    variant.completion AS variant_completion
+  FROM
+    llm_call_variants
+    JOIN llm_calls AS canonical ON llm_call_variants.canonical_id = canonical.id
+    JOIN llm_calls AS variant ON llm_call_variants.variant_id = variant.id
+  ORDER BY variant.timestamp ASC;
+
+CREATE TRIGGER llm_calls_search_insert AFTER INSERT ON llm_calls BEGIN
+  INSERT INTO search_index (entity_id, entity_type, content)
+  VALUES (
+    NEW.id,
+    'llm_call',
+    COALESCE(
+      (
+        SELECT group_concat(json_extract(value, '$.text'), char(10))
+        FROM json_each(NEW.prompt, '$.messages')
+      ),
+      ''
+    ) || char(10) || COALESCE(json_extract(NEW.completion, '$.text'), '')
+  );
+END;
+
+CREATE TRIGGER llm_calls_search_update AFTER UPDATE OF prompt, completion ON llm_calls BEGIN
+  DELETE FROM search_index
+    WHERE entity_type = 'llm_call' AND entity_id = OLD.id;
+  INSERT INTO search_index (entity_id, entity_type, content)
+  VALUES (
+    NEW.id,
+    'llm_call',
+    COALESCE(
+      (
+        SELECT group_concat(json_extract(value, '$.text'), char(10))
+        FROM json_each(NEW.prompt, '$.messages')
+      ),
+      ''
+    ) || char(10) || COALESCE(json_extract(NEW.completion, '$.text'), '')
+  );
+END;
+
+CREATE TRIGGER llm_calls_search_delete AFTER DELETE ON llm_calls BEGIN
+  DELETE FROM search_index
+    WHERE entity_type = 'llm_call' AND entity_id = OLD.id;
+END;
@@ -0,0 +1,15 @@
+CREATE TABLE api_key_service_options (
+  service VARCHAR PRIMARY KEY NOT NULL,
+  options TEXT NOT NULL
+);
+INSERT INTO api_key_service_options (service, options)
+  SELECT
+    api_key_options.service,
+    api_key_options.options
+  FROM api_key_options
+  LEFT JOIN active_api_key_profiles
+    ON active_api_key_profiles.service = api_key_options.service
+  WHERE api_key_options.profile =
+    COALESCE(active_api_key_profiles.profile, 'default');
+DROP TABLE api_key_options;
+ALTER TABLE api_key_service_options RENAME TO api_key_options;
@@ -0,0 +1,17 @@
+CREATE TABLE api_key_profile_options (
+  service VARCHAR NOT NULL,
+  profile VARCHAR NOT NULL,
+  options TEXT NOT NULL,
+  PRIMARY KEY (service, profile)
+);
+-- existing options were only ever applied to whichever profile was active
+INSERT INTO api_key_profile_options (service, profile, options)
+  SELECT
+    api_key_options.service,
+    COALESCE(active_api_key_profiles.profile, 'default'),
+    api_key_options.options
+  FROM api_key_options
+  LEFT JOIN active_api_key_profiles
+    ON active_api_key_profiles.service = api_key_options.service;
+DROP TABLE api_key_options;
+ALTER TABLE api_key_profile_options RENAME TO api_key_options;
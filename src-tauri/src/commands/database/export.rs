@@ -1,11 +1,17 @@
 use crate::commands::database::metadata::DatabaseCounts;
 use crate::commands::errors::ZammResult;
 use crate::models::asciicasts::AsciiCast;
-use crate::models::llm_calls::{LlmCallFollowUp, LlmCallRow, LlmCallVariant};
+use crate::models::llm_calls::{
+    LlmCallAttributionRow, LlmCallDeployment, LlmCallFollowUp, LlmCallKeyProfile,
+    LlmCallLatency, LlmCallRating, LlmCallRow, LlmCallVariant,
+};
 use crate::models::{ApiKey, DatabaseContents, LlmCallData};
 use crate::schema::{
-    api_keys, asciicasts, llm_call_follow_ups, llm_call_variants, llm_calls,
+    api_keys, asciicasts, llm_call_attributions, llm_call_deployments,
+    llm_call_follow_ups, llm_call_key_profiles, llm_call_latencies, llm_call_ratings,
+    llm_call_variants, llm_calls,
 };
+use crate::setup::encryption::{get_encryption, is_encrypted};
 use crate::ZammDatabase;
 use anyhow::anyhow;
 use diesel::prelude::*;
@@ -16,9 +22,12 @@ use std::path::PathBuf;
 use tauri::State;
 use tokio::sync::MutexGuard;
 
+/// Encrypted keys always get exported as they're stored. Unencrypted keys only get
+/// exported if explicitly asked for.
 pub async fn get_database_contents(
     zamm_db: &ZammDatabase,
     save_version: bool,
+    include_unencrypted_keys: bool,
 ) -> ZammResult<DatabaseContents> {
     let db_mutex: &mut MutexGuard<'_, Option<SqliteConnection>> =
         &mut zamm_db.0.lock().await;
@@ -29,19 +38,40 @@ pub async fn get_database_contents(
     } else {
         None
     };
-    let api_keys = api_keys::table.load::<ApiKey>(db)?;
+    let api_keys = api_keys::table
+        .load::<ApiKey>(db)?
+        .into_iter()
+        .filter(|key| include_unencrypted_keys || is_encrypted(&key.api_key))
+        .collect::<Vec<_>>();
+    let api_key_encryption = if api_keys.iter().any(|k| is_encrypted(&k.api_key)) {
+        get_encryption(db)?
+    } else {
+        None
+    };
     let llm_calls_instances = llm_calls::table.load::<LlmCallRow>(db)?;
     let follow_ups = llm_call_follow_ups::table.load::<LlmCallFollowUp>(db)?;
     let variants = llm_call_variants::table.load::<LlmCallVariant>(db)?;
+    let ratings = llm_call_ratings::table.load::<LlmCallRating>(db)?;
+    let latencies = llm_call_latencies::table.load::<LlmCallLatency>(db)?;
+    let attributions =
+        llm_call_attributions::table.load::<LlmCallAttributionRow>(db)?;
+    let key_profiles = llm_call_key_profiles::table.load::<LlmCallKeyProfile>(db)?;
+    let deployments = llm_call_deployments::table.load::<LlmCallDeployment>(db)?;
     let terminal_sessions = asciicasts::table.load::<AsciiCast>(db)?;
 
     Ok(DatabaseContents {
         zamm_version,
         api_keys,
+        api_key_encryption,
         llm_calls: LlmCallData {
             instances: llm_calls_instances,
             follow_ups,
             variants,
+            ratings,
+            latencies,
+            attributions,
+            key_profiles,
+            deployments,
         },
         terminal_sessions,
     })
@@ -51,10 +81,12 @@ pub async fn write_database_contents(
     zamm_db: &ZammDatabase,
     file_path: &str,
     save_version: bool,
+    include_unencrypted_keys: bool,
 ) -> ZammResult<DatabaseCounts> {
     let file_path_buf = PathBuf::from(file_path);
     let file_path_abs = file_path_buf.absolutize()?;
-    let db_contents = get_database_contents(zamm_db, save_version).await?;
+    let db_contents =
+        get_database_contents(zamm_db, save_version, include_unencrypted_keys).await?;
     let serialized = serde_yaml::to_string(&db_contents)?;
     if let Some(parent) = file_path_abs.parent() {
         fs::create_dir_all(parent).map_err(|e| {
@@ -78,8 +110,9 @@ pub async fn write_database_contents(
 async fn export_db_helper(
     zamm_db: &ZammDatabase,
     path: &str,
+    include_unencrypted_keys: bool,
 ) -> ZammResult<DatabaseCounts> {
-    write_database_contents(zamm_db, path, true).await
+    write_database_contents(zamm_db, path, true, include_unencrypted_keys).await
 }
 
 #[tauri::command(async)]
@@ -87,8 +120,9 @@ async fn export_db_helper(
 pub async fn export_db(
     database: State<'_, ZammDatabase>,
     path: String,
+    include_unencrypted_keys: bool,
 ) -> ZammResult<DatabaseCounts> {
-    export_db_helper(&database, &path).await
+    export_db_helper(&database, &path, include_unencrypted_keys).await
 }
 
 #[cfg(test)]
@@ -101,13 +135,19 @@ mod tests {
     #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
     struct ExportDbRequest {
         path: String,
+        include_unencrypted_keys: bool,
     }
 
     async fn make_request_helper(
         args: &ExportDbRequest,
         side_effects: &mut SideEffectsHelpers,
     ) -> ZammResult<DatabaseCounts> {
-        export_db_helper(side_effects.db.as_ref().unwrap(), &args.path).await
+        export_db_helper(
+            side_effects.db.as_ref().unwrap(),
+            &args.path,
+            args.include_unencrypted_keys,
+        )
+        .await
     }
 
     impl_result_test_case!(
@@ -124,6 +164,18 @@ mod tests {
         "./api/sample-calls/export_db-api-key.yaml"
     );
 
+    check_sample!(
+        ExportDbTestCase,
+        test_export_api_key_omitted,
+        "./api/sample-calls/export_db-api-key-omitted.yaml"
+    );
+
+    check_sample!(
+        ExportDbTestCase,
+        test_export_encrypted_api_key,
+        "./api/sample-calls/export_db-encrypted-api-key.yaml"
+    );
+
     check_sample!(
         ExportDbTestCase,
         test_export_llm_calls,
@@ -135,4 +187,22 @@ mod tests {
         test_export_terminal_sessions,
         "./api/sample-calls/export_db-terminal-sessions.yaml"
     );
+
+    check_sample!(
+        ExportDbTestCase,
+        test_export_ratings,
+        "./api/sample-calls/export_db-ratings.yaml"
+    );
+
+    check_sample!(
+        ExportDbTestCase,
+        test_export_call_metadata,
+        "./api/sample-calls/export_db-call-metadata.yaml"
+    );
+
+    check_sample!(
+        ExportDbTestCase,
+        test_export_embeddings_omitted,
+        "./api/sample-calls/export_db-embeddings-omitted.yaml"
+    );
 }
@@ -0,0 +1,248 @@
+use crate::commands::database::metadata::is_zero;
+use crate::commands::errors::ZammResult;
+use crate::commands::llms::get_api_calls::{filtered_api_calls, ApiCallFilter};
+use crate::models::llm_calls::{ChatMessage, EntityId, LlmCallRow, Prompt};
+use crate::schema::{llm_call_follow_ups, llm_call_ratings, llm_calls};
+use crate::ZammDatabase;
+use anyhow::anyhow;
+use diesel::prelude::*;
+use path_absolutize::Absolutize;
+use serde::{Deserialize, Serialize};
+use specta::specta;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::PathBuf;
+use tauri::State;
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize, specta::Type)]
+pub struct FineTuningExportOptions {
+    /// Only export these calls, in addition to whatever the filter requires
+    pub ids: Option<Vec<EntityId>>,
+    pub filter: Option<ApiCallFilter>,
+    pub drop_system_messages: Option<bool>,
+    /// Only export the most recent call at the end of each follow-up chain, since
+    /// its prompt already contains the rest of the conversation
+    pub latest_leaves_only: Option<bool>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize, specta::Type)]
+pub struct FineTuningExportCounts {
+    pub num_examples: i32,
+    /// Calls with prompts that this version of ZAMM can't read
+    #[serde(skip_serializing_if = "is_zero")]
+    #[serde(default)]
+    pub num_skipped: i32,
+}
+
+#[derive(Serialize)]
+struct FineTuningMessage<'a> {
+    role: &'static str,
+    content: &'a str,
+}
+
+#[derive(Serialize)]
+struct FineTuningExample<'a> {
+    messages: Vec<FineTuningMessage<'a>>,
+}
+
+impl<'a> From<&'a ChatMessage> for FineTuningMessage<'a> {
+    fn from(message: &'a ChatMessage) -> Self {
+        match message {
+            ChatMessage::System { text } => FineTuningMessage {
+                role: "system",
+                content: text,
+            },
+            ChatMessage::Human { text } => FineTuningMessage {
+                role: "user",
+                content: text,
+            },
+            ChatMessage::AI { text } => FineTuningMessage {
+                role: "assistant",
+                content: text,
+            },
+        }
+    }
+}
+
+/// Keep only the latest call without any follow-ups for each conversation. Only
+/// follow-ups that are themselves being exported count, so that a filter which
+/// cuts a conversation short still exports the last turn that made it through.
+fn latest_leaves(
+    conn: &mut SqliteConnection,
+    rows: Vec<LlmCallRow>,
+) -> ZammResult<Vec<LlmCallRow>> {
+    let previous_calls: HashMap<EntityId, EntityId> = llm_call_follow_ups::table
+        .select((
+            llm_call_follow_ups::next_call_id,
+            llm_call_follow_ups::previous_call_id,
+        ))
+        .load::<(EntityId, EntityId)>(conn)?
+        .into_iter()
+        .collect();
+    let continued_calls: HashSet<&EntityId> = rows
+        .iter()
+        .filter_map(|row| previous_calls.get(&row.id))
+        .collect();
+
+    let mut latest_by_root: HashMap<&EntityId, &EntityId> = HashMap::new();
+    for row in rows.iter().filter(|row| !continued_calls.contains(&row.id)) {
+        let mut root = &row.id;
+        while let Some(previous) = previous_calls.get(root) {
+            root = previous;
+        }
+        // rows are in ascending order, so later ones win
+        latest_by_root.insert(root, &row.id);
+    }
+    let latest_ids: HashSet<EntityId> = latest_by_root.into_values().cloned().collect();
+    Ok(rows
+        .into_iter()
+        .filter(|row| latest_ids.contains(&row.id))
+        .collect())
+}
+
+pub async fn write_fine_tuning_data(
+    zamm_db: &ZammDatabase,
+    file_path: &str,
+    options: FineTuningExportOptions,
+) -> ZammResult<FineTuningExportCounts> {
+    let file_path_buf = PathBuf::from(file_path);
+    let file_path_abs = file_path_buf.absolutize()?;
+    let filter = options.filter.clone().unwrap_or_default();
+
+    let mut db = zamm_db.0.lock().await;
+    let conn = db.as_mut().ok_or(anyhow!("Failed to lock database"))?;
+    let mut query = filtered_api_calls(&filter);
+    if let Some(ids) = &options.ids {
+        query = query.filter(llm_calls::id.eq_any(ids));
+    }
+    let mut rows = query
+        .order(llm_calls::timestamp.asc())
+        .load::<LlmCallRow>(conn)?;
+    if options.latest_leaves_only.unwrap_or(false) {
+        rows = latest_leaves(conn, rows)?;
+    }
+    let ideal_completions: HashMap<EntityId, String> = llm_call_ratings::table
+        .select((
+            llm_call_ratings::llm_call_id,
+            llm_call_ratings::ideal_completion.assume_not_null(),
+        ))
+        .filter(llm_call_ratings::ideal_completion.is_not_null())
+        .filter(llm_call_ratings::llm_call_id.eq_any(rows.iter().map(|row| &row.id)))
+        .load::<(EntityId, String)>(conn)?
+        .into_iter()
+        .collect();
+
+    let drop_system_messages = options.drop_system_messages.unwrap_or(false);
+    let mut lines: Vec<String> = Vec::new();
+    let mut num_skipped = 0;
+    for row in &rows {
+        let prompt = match &row.prompt {
+            Prompt::Chat(chat_prompt) => chat_prompt,
+            Prompt::Unknown => {
+                num_skipped += 1;
+                continue;
+            }
+        };
+        let mut messages: Vec<FineTuningMessage> = prompt
+            .iter()
+            .filter(|message| {
+                !(drop_system_messages && matches!(message, ChatMessage::System { .. }))
+            })
+            .map(|message| message.into())
+            .collect();
+        // a corrected completion from the user beats whatever the AI said
+        messages.push(match ideal_completions.get(&row.id) {
+            Some(ideal_completion) => FineTuningMessage {
+                role: "assistant",
+                content: ideal_completion,
+            },
+            None => (&row.completion).into(),
+        });
+        lines.push(serde_json::to_string(&FineTuningExample { messages })?);
+    }
+
+    if let Some(parent) = file_path_abs.parent() {
+        fs::create_dir_all(parent).map_err(|e| {
+            anyhow!(
+                "Error creating parent directory {}: {}",
+                parent.display(),
+                e
+            )
+        })?;
+    }
+    let serialized: String = lines.iter().map(|line| format!("{line}\n")).collect();
+    fs::write(&file_path_abs, serialized).map_err(|e| {
+        anyhow!("Error exporting to {}: {}", &file_path_abs.display(), e)
+    })?;
+    Ok(FineTuningExportCounts {
+        num_examples: lines.len() as i32,
+        num_skipped,
+    })
+}
+
+#[tauri::command(async)]
+#[specta]
+pub async fn export_fine_tuning_data(
+    database: State<'_, ZammDatabase>,
+    path: String,
+    options: FineTuningExportOptions,
+) -> ZammResult<FineTuningExportCounts> {
+    write_fine_tuning_data(&database, &path, options).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::SideEffectsHelpers;
+    use crate::{check_sample, impl_result_test_case};
+
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    struct ExportFineTuningDataRequest {
+        path: String,
+        options: FineTuningExportOptions,
+    }
+
+    async fn make_request_helper(
+        args: &ExportFineTuningDataRequest,
+        side_effects: &mut SideEffectsHelpers,
+    ) -> ZammResult<FineTuningExportCounts> {
+        write_fine_tuning_data(
+            side_effects.db.as_ref().unwrap(),
+            &args.path,
+            args.options.clone(),
+        )
+        .await
+    }
+
+    impl_result_test_case!(
+        ExportFineTuningDataTestCase,
+        export_fine_tuning_data,
+        true,
+        ExportFineTuningDataRequest,
+        FineTuningExportCounts
+    );
+
+    check_sample!(
+        ExportFineTuningDataTestCase,
+        test_rated_good,
+        "./api/sample-calls/export_fine_tuning_data-rated-good.yaml"
+    );
+
+    check_sample!(
+        ExportFineTuningDataTestCase,
+        test_latest_leaves_with_ideal,
+        "./api/sample-calls/export_fine_tuning_data-latest-leaves.yaml"
+    );
+
+    check_sample!(
+        ExportFineTuningDataTestCase,
+        test_latest_leaves_filtered,
+        "./api/sample-calls/export_fine_tuning_data-latest-leaves-filtered.yaml"
+    );
+
+    check_sample!(
+        ExportFineTuningDataTestCase,
+        test_skip_unknown_prompt,
+        "./api/sample-calls/export_fine_tuning_data-unknown-prompt.yaml"
+    );
+}
@@ -1,13 +1,19 @@
 use crate::commands::database::metadata::DatabaseCounts;
 use crate::commands::errors::{Error, ImportError, ZammResult};
+use crate::commands::llms::embeddings::spawn_embedding_backfill;
+use crate::commands::search::index_terminal_session;
 use crate::models::asciicasts::NewAsciiCast;
 use crate::models::llm_calls::{
-    NewLlmCallFollowUp, NewLlmCallRow, NewLlmCallVariant, Prompt,
+    LlmCallAttributionRow, LlmCallDeployment, LlmCallKeyProfile, LlmCallLatency,
+    NewLlmCallFollowUp, NewLlmCallRating, NewLlmCallRow, NewLlmCallVariant, Prompt,
 };
 use crate::models::{DatabaseContents, NewApiKey};
 use crate::schema::{
-    api_keys, asciicasts, llm_call_follow_ups, llm_call_variants, llm_calls,
+    api_key_encryption, api_keys, asciicasts, llm_call_attributions,
+    llm_call_deployments, llm_call_follow_ups, llm_call_key_profiles,
+    llm_call_latencies, llm_call_ratings, llm_call_variants, llm_calls,
 };
+use crate::setup::encryption::{get_encryption, is_encrypted};
 use crate::ZammDatabase;
 use anyhow::anyhow;
 use diesel::prelude::*;
@@ -28,27 +34,50 @@ pub struct DatabaseImportCounts {
     pub ignored: DatabaseCounts,
 }
 
+pub fn read_import_file(file_path: &str) -> ZammResult<String> {
+    let file_path_buf = PathBuf::from(file_path);
+    let file_path_abs = file_path_buf.absolutize()?;
+    let serialized = fs::read_to_string(&file_path_abs).map_err(|e| {
+        anyhow!("Error reading file at {}: {}", &file_path_abs.display(), e)
+    })?;
+    Ok(serialized)
+}
+
 pub async fn read_database_contents(
     zamm_db: &ZammDatabase,
     file_path: &str,
+) -> ZammResult<DatabaseImportCounts> {
+    let serialized = read_import_file(file_path)?;
+    let db_contents: DatabaseContents = serde_yaml::from_str(&serialized)?;
+    import_database_contents(zamm_db, db_contents).await
+}
+
+/// Insert everything from the contents that isn't already in the database.
+pub async fn import_database_contents(
+    zamm_db: &ZammDatabase,
+    db_contents: DatabaseContents,
 ) -> ZammResult<DatabaseImportCounts> {
     let db_mutex: &mut MutexGuard<'_, Option<SqliteConnection>> =
         &mut zamm_db.0.lock().await;
     let db = db_mutex.as_mut().ok_or(anyhow!("Error getting db"))?;
 
-    let file_path_buf = PathBuf::from(file_path);
-    let file_path_abs = file_path_buf.absolutize()?;
-    let serialized = fs::read_to_string(&file_path_abs).map_err(|e| {
-        anyhow!("Error reading file at {}: {}", &file_path_abs.display(), e)
-    })?;
-    let db_contents: DatabaseContents = serde_yaml::from_str(&serialized)?;
-
+    // encrypted keys can only be unlocked here if this database uses the same
+    // passphrase for its keys, or doesn't have one yet
+    let existing_encryption = get_encryption(db)?;
+    let can_import_encrypted =
+        match (&existing_encryption, &db_contents.api_key_encryption) {
+            (Some(existing), Some(imported)) => existing.salt == imported.salt,
+            (None, Some(_)) => true,
+            (_, None) => false,
+        };
     let new_api_keys: Vec<NewApiKey> = db_contents
         .insertable_api_keys()
         .into_iter()
+        .filter(|key| can_import_encrypted || !is_encrypted(key.api_key))
         .filter(|key| {
             api_keys::table
                 .filter(api_keys::service.eq(&key.service))
+                .filter(api_keys::profile.eq(key.profile))
                 .count()
                 .get_result::<i64>(db)
                 .unwrap_or(0)
@@ -96,6 +125,35 @@ pub async fn read_database_contents(
                 || new_llm_call_ids.contains(&variant.variant_id)
         })
         .collect();
+    let new_llm_call_ratings: Vec<NewLlmCallRating> = db_contents
+        .insertable_call_ratings()
+        .into_iter()
+        .filter(|rating| new_llm_call_ids.contains(&rating.llm_call_id))
+        .collect();
+    let new_llm_call_latencies: Vec<&LlmCallLatency> = db_contents
+        .llm_calls
+        .latencies
+        .iter()
+        .filter(|latency| new_llm_call_ids.contains(&&latency.llm_call_id))
+        .collect();
+    let new_llm_call_attributions: Vec<&LlmCallAttributionRow> = db_contents
+        .llm_calls
+        .attributions
+        .iter()
+        .filter(|attribution| new_llm_call_ids.contains(&&attribution.llm_call_id))
+        .collect();
+    let new_llm_call_key_profiles: Vec<&LlmCallKeyProfile> = db_contents
+        .llm_calls
+        .key_profiles
+        .iter()
+        .filter(|key_profile| new_llm_call_ids.contains(&&key_profile.llm_call_id))
+        .collect();
+    let new_llm_call_deployments: Vec<&LlmCallDeployment> = db_contents
+        .llm_calls
+        .deployments
+        .iter()
+        .filter(|deployment| new_llm_call_ids.contains(&&deployment.llm_call_id))
+        .collect();
 
     if new_llm_calls
         .iter()
@@ -111,10 +169,22 @@ pub async fn read_database_contents(
         }
     }
 
+    let new_encryption = match existing_encryption {
+        None if new_api_keys.iter().any(|key| is_encrypted(key.api_key)) => {
+            db_contents.api_key_encryption.as_ref()
+        }
+        _ => None,
+    };
+
     db.transaction::<(), diesel::result::Error, _>(|conn| {
         diesel::insert_into(api_keys::table)
             .values(&new_api_keys)
             .execute(conn)?;
+        if let Some(encryption) = new_encryption {
+            diesel::insert_into(api_key_encryption::table)
+                .values(encryption)
+                .execute(conn)?;
+        }
         diesel::insert_into(llm_calls::table)
             .values(&new_llm_calls)
             .execute(conn)?;
@@ -124,9 +194,27 @@ pub async fn read_database_contents(
         diesel::insert_into(llm_call_variants::table)
             .values(&new_llm_call_variants)
             .execute(conn)?;
+        diesel::insert_into(llm_call_ratings::table)
+            .values(&new_llm_call_ratings)
+            .execute(conn)?;
+        diesel::insert_into(llm_call_latencies::table)
+            .values(&new_llm_call_latencies)
+            .execute(conn)?;
+        diesel::insert_into(llm_call_attributions::table)
+            .values(&new_llm_call_attributions)
+            .execute(conn)?;
+        diesel::insert_into(llm_call_key_profiles::table)
+            .values(&new_llm_call_key_profiles)
+            .execute(conn)?;
+        diesel::insert_into(llm_call_deployments::table)
+            .values(&new_llm_call_deployments)
+            .execute(conn)?;
         diesel::insert_into(asciicasts::table)
             .values(&new_terminal_sessions)
             .execute(conn)?;
+        for session in &new_terminal_sessions {
+            index_terminal_session(conn, session.id, session.cast)?;
+        }
         Ok(())
     })?;
     Ok(DatabaseImportCounts {
@@ -156,15 +244,22 @@ async fn import_db_helper(
 #[tauri::command(async)]
 #[specta]
 pub async fn import_db(
+    app_handle: tauri::AppHandle,
     database: State<'_, ZammDatabase>,
     path: &str,
 ) -> ZammResult<DatabaseImportCounts> {
-    import_db_helper(&database, path).await
+    let counts = import_db_helper(&database, path).await?;
+    spawn_embedding_backfill(&app_handle);
+    Ok(counts)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::commands::database::get_database_contents;
+    use crate::models::llm_calls::EntityId;
+    use crate::test_helpers::database::setup_zamm_db;
+    use crate::test_helpers::sqlite::load_sqlite_database;
     use crate::test_helpers::SideEffectsHelpers;
     use crate::{check_sample, impl_result_test_case};
     use serde::{Deserialize, Serialize};
@@ -201,6 +296,12 @@ mod tests {
         "./api/sample-calls/import_db-api-key.yaml"
     );
 
+    check_sample!(
+        ImportDbTestCase,
+        test_encrypted_api_key,
+        "./api/sample-calls/import_db-encrypted-api-key.yaml"
+    );
+
     check_sample!(
         ImportDbTestCase,
         test_conflicting_llm_call,
@@ -230,4 +331,72 @@ mod tests {
         test_unknown_provider_prompt,
         "./api/sample-calls/import_db-unknown-provider-prompt.yaml"
     );
+
+    check_sample!(
+        ImportDbTestCase,
+        test_import_ratings,
+        "./api/sample-calls/import_db-ratings.yaml"
+    );
+
+    check_sample!(
+        ImportDbTestCase,
+        test_import_call_metadata,
+        "./api/sample-calls/import_db-call-metadata.yaml"
+    );
+
+    #[tokio::test]
+    async fn test_latencies_and_deployments_round_trip() {
+        let call_id =
+            EntityId::try_from("d5ad1e49-f57f-4481-84fb-4d70ba8a7a74").unwrap();
+        let latency = LlmCallLatency {
+            llm_call_id: call_id.clone(),
+            latency_ms: 1234,
+        };
+        let exporting_db = setup_zamm_db(None);
+        load_sqlite_database(
+            &exporting_db,
+            &PathBuf::from(
+                "./api/sample-database-writes/conversation-started-azure/dump.sql",
+            ),
+        )
+        .await;
+        {
+            let mut db = exporting_db.0.lock().await;
+            diesel::insert_into(llm_call_latencies::table)
+                .values(&latency)
+                .execute(db.as_mut().unwrap())
+                .unwrap();
+        }
+        let exported = serde_yaml::to_string(
+            &get_database_contents(&exporting_db, true, false)
+                .await
+                .unwrap(),
+        )
+        .unwrap();
+
+        let importing_db = setup_zamm_db(None);
+        import_database_contents(
+            &importing_db,
+            serde_yaml::from_str(&exported).unwrap(),
+        )
+        .await
+        .unwrap();
+        let mut db = importing_db.0.lock().await;
+        let conn = db.as_mut().unwrap();
+        assert_eq!(
+            llm_call_latencies::table
+                .load::<LlmCallLatency>(conn)
+                .unwrap(),
+            vec![latency]
+        );
+        assert_eq!(
+            llm_call_deployments::table
+                .load::<LlmCallDeployment>(conn)
+                .unwrap(),
+            vec![LlmCallDeployment {
+                llm_call_id: call_id,
+                deployment: "zamm-gpt-4".to_string(),
+            }]
+        );
+    }
 }
@@ -0,0 +1,310 @@
+use crate::commands::database::import::{
+    import_database_contents, read_import_file, DatabaseImportCounts,
+};
+use crate::commands::errors::ZammResult;
+use crate::commands::llms::embeddings::spawn_embedding_backfill;
+use crate::models::llm_calls::{
+    ChatMessage, ChatPrompt, EntityId, LlmCallFollowUp, LlmCallRow, LlmCallVariant,
+    Prompt,
+};
+use crate::models::{DatabaseContents, LlmCallData};
+use crate::setup::api_keys::Service;
+use crate::ZammDatabase;
+use chrono::naive::NaiveDateTime;
+use chrono::DateTime;
+use serde::Deserialize;
+use specta::specta;
+use std::collections::HashMap;
+use tauri::State;
+use uuid::Uuid;
+
+/// Marks calls that were made through ChatGPT rather than through the API.
+const CHATGPT_MARKER: &str = "chatgpt";
+
+/// A single conversation from the `conversations.json` file in a ChatGPT data
+/// export. Messages form a tree, because editing or regenerating a message
+/// starts a new branch.
+#[derive(Debug, Deserialize)]
+struct ChatGptConversation {
+    create_time: Option<f64>,
+    mapping: HashMap<String, ChatGptNode>,
+    default_model_slug: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatGptNode {
+    message: Option<ChatGptMessage>,
+    parent: Option<String>,
+    #[serde(default)]
+    children: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatGptMessage {
+    id: String,
+    author: ChatGptAuthor,
+    create_time: Option<f64>,
+    content: ChatGptContent,
+    #[serde(default)]
+    metadata: ChatGptMessageMetadata,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatGptAuthor {
+    role: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatGptContent {
+    content_type: String,
+    #[serde(default)]
+    parts: Vec<serde_json::Value>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct ChatGptMessageMetadata {
+    model_slug: Option<String>,
+    #[serde(default)]
+    is_visually_hidden_from_conversation: bool,
+}
+
+/// ChatGPT timestamps are fractional seconds since the epoch, with microsecond
+/// precision.
+fn to_timestamp(seconds: f64) -> Option<NaiveDateTime> {
+    DateTime::from_timestamp_micros((seconds * 1e6).round() as i64)
+        .map(|datetime| datetime.naive_utc())
+}
+
+impl ChatGptMessage {
+    /// The message as ZAMM would have recorded it, if it is a text message that
+    /// was actually shown to the user.
+    fn to_chat_message(&self) -> Option<ChatMessage> {
+        if self.metadata.is_visually_hidden_from_conversation {
+            return None;
+        }
+        if !matches!(
+            self.content.content_type.as_str(),
+            "text" | "multimodal_text"
+        ) {
+            return None;
+        }
+        let text = self
+            .content
+            .parts
+            .iter()
+            .filter_map(|part| part.as_str())
+            .collect::<Vec<&str>>()
+            .join("\n");
+        if text.trim().is_empty() {
+            return None;
+        }
+        match self.author.role.as_str() {
+            "system" => Some(ChatMessage::System { text }),
+            "user" => Some(ChatMessage::Human { text }),
+            "assistant" => Some(ChatMessage::AI { text }),
+            _ => None,
+        }
+    }
+}
+
+/// Conversation state at a given node of the message tree.
+struct Branch {
+    node_id: String,
+    messages: Vec<ChatMessage>,
+    previous_call_id: Option<EntityId>,
+    timestamp: Option<NaiveDateTime>,
+}
+
+fn convert_conversation(conversation: &ChatGptConversation) -> LlmCallData {
+    let mut calls: Vec<(LlmCallRow, Option<EntityId>)> = Vec::new();
+    let conversation_time = conversation.create_time.and_then(to_timestamp);
+    let mut root_ids: Vec<&String> = conversation
+        .mapping
+        .iter()
+        .filter(|(_, node)| match &node.parent {
+            Some(parent) => !conversation.mapping.contains_key(parent),
+            None => true,
+        })
+        .map(|(id, _)| id)
+        .collect();
+    root_ids.sort();
+
+    let mut branches: Vec<Branch> = root_ids
+        .into_iter()
+        .map(|id| Branch {
+            node_id: id.clone(),
+            messages: Vec::new(),
+            previous_call_id: None,
+            timestamp: conversation_time,
+        })
+        .collect();
+    while let Some(mut branch) = branches.pop() {
+        let Some(node) = conversation.mapping.get(&branch.node_id) else {
+            continue;
+        };
+        if let Some(message) = &node.message {
+            branch.timestamp = message
+                .create_time
+                .and_then(to_timestamp)
+                .or(branch.timestamp);
+            if let Some(chat_message) = message.to_chat_message() {
+                if let (ChatMessage::AI { .. }, Some(timestamp)) =
+                    (&chat_message, branch.timestamp)
+                {
+                    let id = match Uuid::parse_str(&message.id) {
+                        Ok(uuid) => EntityId { uuid },
+                        Err(_) => EntityId::new(),
+                    };
+                    let llm = message
+                        .metadata
+                        .model_slug
+                        .clone()
+                        .or(conversation.default_model_slug.clone())
+                        .unwrap_or("unknown".to_string());
+                    let llm_requested = conversation
+                        .default_model_slug
+                        .clone()
+                        .unwrap_or(llm.clone());
+                    calls.push((
+                        LlmCallRow {
+                            id: id.clone(),
+                            timestamp,
+                            provider: Service::Unknown(CHATGPT_MARKER.to_string()),
+                            llm_requested,
+                            llm,
+                            // ChatGPT doesn't say what temperature it uses
+                            temperature: None,
+                            prompt_tokens: None,
+                            response_tokens: None,
+                            total_tokens: None,
+                            prompt: Prompt::Chat(ChatPrompt {
+                                messages: branch.messages.clone(),
+                                attachments: Vec::new(),
+                            }),
+                            completion: chat_message.clone(),
+                        },
+                        branch.previous_call_id.clone(),
+                    ));
+                    branch.previous_call_id = Some(id);
+                }
+                branch.messages.push(chat_message);
+            }
+        }
+        // reversed so that the first child gets popped off the stack first
+        for child_id in node.children.iter().rev() {
+            branches.push(Branch {
+                node_id: child_id.clone(),
+                messages: branch.messages.clone(),
+                previous_call_id: branch.previous_call_id.clone(),
+                timestamp: branch.timestamp,
+            });
+        }
+    }
+    calls.sort_by_key(|(row, _)| row.timestamp);
+
+    let follow_ups = calls
+        .iter()
+        .filter_map(|(row, previous_call_id)| {
+            previous_call_id
+                .as_ref()
+                .map(|previous_call_id| LlmCallFollowUp {
+                    previous_call_id: previous_call_id.clone(),
+                    next_call_id: row.id.clone(),
+                })
+        })
+        .collect();
+    // responses to the same point in the conversation are alternate branches of
+    // the earliest one
+    let mut canonical_ids: HashMap<Option<EntityId>, EntityId> = HashMap::new();
+    let mut variants = Vec::new();
+    for (row, previous_call_id) in &calls {
+        match canonical_ids.get(previous_call_id) {
+            Some(canonical_id) => variants.push(LlmCallVariant {
+                canonical_id: canonical_id.clone(),
+                variant_id: row.id.clone(),
+            }),
+            None => {
+                canonical_ids.insert(previous_call_id.clone(), row.id.clone());
+            }
+        }
+    }
+
+    LlmCallData {
+        instances: calls.into_iter().map(|(row, _)| row).collect(),
+        follow_ups,
+        variants,
+        ..Default::default()
+    }
+}
+
+async fn import_chatgpt_helper(
+    zamm_db: &ZammDatabase,
+    path: &str,
+) -> ZammResult<DatabaseImportCounts> {
+    let serialized = read_import_file(path)?;
+    let conversations: Vec<ChatGptConversation> = serde_json::from_str(&serialized)?;
+    let mut llm_calls = LlmCallData::default();
+    for conversation in &conversations {
+        let converted = convert_conversation(conversation);
+        llm_calls.instances.extend(converted.instances);
+        llm_calls.follow_ups.extend(converted.follow_ups);
+        llm_calls.variants.extend(converted.variants);
+    }
+    let db_contents = DatabaseContents {
+        llm_calls,
+        ..Default::default()
+    };
+    import_database_contents(zamm_db, db_contents).await
+}
+
+#[tauri::command(async)]
+#[specta]
+pub async fn import_chatgpt(
+    app_handle: tauri::AppHandle,
+    database: State<'_, ZammDatabase>,
+    path: &str,
+) -> ZammResult<DatabaseImportCounts> {
+    let counts = import_chatgpt_helper(&database, path).await?;
+    spawn_embedding_backfill(&app_handle);
+    Ok(counts)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::SideEffectsHelpers;
+    use crate::{check_sample, impl_result_test_case};
+    use serde::Serialize;
+
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    struct ImportChatGptRequest {
+        path: String,
+    }
+
+    async fn make_request_helper(
+        args: &ImportChatGptRequest,
+        side_effects: &mut SideEffectsHelpers,
+    ) -> ZammResult<DatabaseImportCounts> {
+        import_chatgpt_helper(side_effects.db.as_ref().unwrap(), &args.path).await
+    }
+
+    impl_result_test_case!(
+        ImportChatGptTestCase,
+        import_chatgpt,
+        true,
+        ImportChatGptRequest,
+        DatabaseImportCounts
+    );
+
+    check_sample!(
+        ImportChatGptTestCase,
+        test_branching_conversation,
+        "./api/sample-calls/import_chatgpt-branching.yaml"
+    );
+
+    check_sample!(
+        ImportChatGptTestCase,
+        test_already_imported,
+        "./api/sample-calls/import_chatgpt-already-imported.yaml"
+    );
+}
@@ -0,0 +1,208 @@
+use crate::commands::database::import::{
+    import_database_contents, read_import_file, DatabaseImportCounts,
+};
+use crate::commands::errors::ZammResult;
+use crate::commands::llms::embeddings::spawn_embedding_backfill;
+use crate::models::llm_calls::{ChatMessage, ChatPrompt, EntityId, LlmCallRow, Prompt};
+use crate::models::{DatabaseContents, LlmCallData};
+use crate::setup::api_keys::Service;
+use crate::ZammDatabase;
+use anyhow::anyhow;
+use chrono::naive::NaiveDateTime;
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use specta::specta;
+use std::collections::HashSet;
+use tauri::State;
+use uuid::Builder;
+
+/// Marks calls that came from a dataset rather than from an actual LLM.
+const DATASET_MARKER: &str = "dataset";
+const DATASET_TEMPERATURE: f32 = 1.0;
+
+/// A single line of an OpenAI-style chat dataset, as used for fine-tuning and
+/// evals.
+#[derive(Debug, Deserialize)]
+struct DatasetExample {
+    messages: Vec<DatasetMessage>,
+}
+
+#[derive(Debug, Deserialize)]
+struct DatasetMessage {
+    role: String,
+    content: DatasetContent,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum DatasetContent {
+    Text(String),
+    Parts(Vec<DatasetContentPart>),
+}
+
+#[derive(Debug, Deserialize)]
+struct DatasetContentPart {
+    text: Option<String>,
+}
+
+impl DatasetMessage {
+    fn to_chat_message(&self) -> ZammResult<ChatMessage> {
+        let text = match &self.content {
+            DatasetContent::Text(text) => text.clone(),
+            DatasetContent::Parts(parts) => parts
+                .iter()
+                .filter_map(|part| part.text.as_deref())
+                .collect::<Vec<&str>>()
+                .join("\n"),
+        };
+        match self.role.as_str() {
+            "system" | "developer" => Ok(ChatMessage::System { text }),
+            "user" => Ok(ChatMessage::Human { text }),
+            "assistant" => Ok(ChatMessage::AI { text }),
+            other => Err(anyhow!("Unsupported message role \"{}\"", other).into()),
+        }
+    }
+}
+
+/// Identical examples always get the same ID, so that importing a dataset twice
+/// doesn't result in duplicate calls.
+fn content_id(prompt: &Prompt, completion: &ChatMessage) -> ZammResult<EntityId> {
+    let mut hasher = Sha256::new();
+    hasher.update(serde_json::to_string(prompt)?);
+    hasher.update(serde_json::to_string(completion)?);
+    let digest = hasher.finalize();
+    let mut bytes = [0u8; 16];
+    bytes.copy_from_slice(&digest[..16]);
+    Ok(EntityId {
+        uuid: Builder::from_custom_bytes(bytes).into_uuid(),
+    })
+}
+
+fn parse_example(line: &str, imported_at: NaiveDateTime) -> ZammResult<LlmCallRow> {
+    let example: DatasetExample = serde_json::from_str(line)?;
+    let mut messages = example
+        .messages
+        .iter()
+        .map(|message| message.to_chat_message())
+        .collect::<ZammResult<Vec<ChatMessage>>>()?;
+    let completion = match messages.pop() {
+        Some(message @ ChatMessage::AI { .. }) => message,
+        _ => {
+            return Err(anyhow!("Example doesn't end with an assistant message").into())
+        }
+    };
+    let prompt = Prompt::Chat(ChatPrompt {
+        messages,
+        attachments: Vec::new(),
+    });
+    Ok(LlmCallRow {
+        id: content_id(&prompt, &completion)?,
+        timestamp: imported_at,
+        provider: Service::Unknown(DATASET_MARKER.to_string()),
+        llm_requested: DATASET_MARKER.to_string(),
+        llm: DATASET_MARKER.to_string(),
+        temperature: DATASET_TEMPERATURE,
+        prompt_tokens: None,
+        response_tokens: None,
+        total_tokens: None,
+        prompt,
+        completion,
+    })
+}
+
+async fn import_jsonl_helper(
+    zamm_db: &ZammDatabase,
+    path: &str,
+    imported_at: NaiveDateTime,
+) -> ZammResult<DatabaseImportCounts> {
+    let serialized = read_import_file(path)?;
+    let mut seen_ids = HashSet::new();
+    let mut instances = Vec::new();
+    let mut num_duplicates = 0;
+    for (index, line) in serialized.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let row = parse_example(line, imported_at)
+            .map_err(|e| anyhow!("Error reading line {}: {}", index + 1, e))?;
+        if seen_ids.insert(row.id.clone()) {
+            instances.push(row);
+        } else {
+            num_duplicates += 1;
+        }
+    }
+
+    let db_contents = DatabaseContents {
+        llm_calls: LlmCallData {
+            instances,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let mut counts = import_database_contents(zamm_db, db_contents).await?;
+    counts.ignored.num_llm_calls += num_duplicates;
+    Ok(counts)
+}
+
+#[tauri::command(async)]
+#[specta]
+pub async fn import_jsonl(
+    app_handle: tauri::AppHandle,
+    database: State<'_, ZammDatabase>,
+    path: &str,
+) -> ZammResult<DatabaseImportCounts> {
+    let imported_at = chrono::Utc::now().naive_utc();
+    let counts = import_jsonl_helper(&database, path, imported_at).await?;
+    spawn_embedding_backfill(&app_handle);
+    Ok(counts)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::SideEffectsHelpers;
+    use crate::{check_sample, impl_result_test_case};
+    use serde::Serialize;
+
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    struct ImportJsonlRequest {
+        path: String,
+    }
+
+    async fn make_request_helper(
+        args: &ImportJsonlRequest,
+        side_effects: &mut SideEffectsHelpers,
+    ) -> ZammResult<DatabaseImportCounts> {
+        let imported_at =
+            NaiveDateTime::parse_from_str("2024-07-01 12:00:00", "%Y-%m-%d %H:%M:%S")
+                .unwrap();
+        import_jsonl_helper(side_effects.db.as_ref().unwrap(), &args.path, imported_at)
+            .await
+    }
+
+    impl_result_test_case!(
+        ImportJsonlTestCase,
+        import_jsonl,
+        true,
+        ImportJsonlRequest,
+        DatabaseImportCounts
+    );
+
+    check_sample!(
+        ImportJsonlTestCase,
+        test_dataset,
+        "./api/sample-calls/import_jsonl-dataset.yaml"
+    );
+
+    check_sample!(
+        ImportJsonlTestCase,
+        test_already_imported,
+        "./api/sample-calls/import_jsonl-already-imported.yaml"
+    );
+
+    check_sample!(
+        ImportJsonlTestCase,
+        test_missing_completion,
+        "./api/sample-calls/import_jsonl-missing-completion.yaml"
+    );
+}
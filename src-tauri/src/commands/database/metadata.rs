@@ -1,4 +1,4 @@
-fn is_zero(num: &i32) -> bool {
+pub fn is_zero(num: &i32) -> bool {
     *num == 0
 }
 
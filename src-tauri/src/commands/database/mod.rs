@@ -1,11 +1,17 @@
 pub mod export;
+pub mod fine_tuning;
 pub mod import;
+pub mod import_chatgpt;
+pub mod import_jsonl;
 mod metadata;
 
 pub use export::export_db;
+pub use fine_tuning::export_fine_tuning_data;
 pub use import::import_db;
+pub use import_chatgpt::import_chatgpt;
+pub use import_jsonl::import_jsonl;
 
 #[cfg(test)]
-pub use export::write_database_contents;
+pub use export::get_database_contents;
 #[cfg(test)]
 pub use import::read_database_contents;
@@ -10,6 +10,12 @@ pub enum Error {
     UnexpectedOpenAiResponse { reason: String },
     #[error("Missing API key for {service}")]
     MissingApiKey { service: Service },
+    #[error("{service} rejected the API key")]
+    InvalidApiKey { service: Service },
+    #[error("The {service} account for this API key has run out of quota")]
+    ApiKeyQuotaExceeded { service: Service },
+    #[error("Couldn't reach {service}: {reason}")]
+    NoNetwork { service: Service, reason: String },
     #[error("Cannot import from ZAMM version {version}. {import_error}")]
     FutureZammImport {
         version: String,
@@ -0,0 +1,94 @@
+use crate::commands::errors::ZammResult;
+use crate::commands::preferences::{get_preferences_helper, Preferences};
+use crate::setup::api_keys::Service;
+use crate::ZammApiKeys;
+use anyhow::anyhow;
+use reqwest::header::HeaderMap;
+use reqwest::{Certificate, NoProxy, Proxy};
+use reqwest_middleware::ClientWithMiddleware;
+use std::fs;
+use std::time::Duration;
+use tauri::Manager;
+
+/// Every request to an LLM provider should go through a client built here, so
+/// that the user's network settings apply to all of them.
+pub fn build_http_client(
+    preferences: &Preferences,
+    default_headers: HeaderMap,
+) -> ZammResult<ClientWithMiddleware> {
+    let mut builder = reqwest::ClientBuilder::new().default_headers(default_headers);
+    if let Some(proxy_url) = &preferences.proxy_url {
+        let no_proxy = preferences
+            .no_proxy
+            .as_deref()
+            .and_then(NoProxy::from_string);
+        builder = builder.proxy(Proxy::all(proxy_url)?.no_proxy(no_proxy));
+    }
+    if let Some(ca_bundle_path) = &preferences.ca_bundle_path {
+        let pem = fs::read(ca_bundle_path).map_err(|e| {
+            anyhow!("Couldn't read CA bundle {}: {}", ca_bundle_path, e)
+        })?;
+        for certificate in Certificate::from_pem_bundle(&pem)? {
+            builder = builder.add_root_certificate(certificate);
+        }
+    }
+    if let Some(timeout_secs) = preferences.request_timeout_secs {
+        builder = builder.timeout(Duration::from_secs(timeout_secs.into()));
+    }
+    let http_client = builder.build()?;
+    Ok(reqwest_middleware::ClientBuilder::new(http_client).build())
+}
+
+pub fn app_http_client(
+    app_handle: &tauri::AppHandle,
+    default_headers: HeaderMap,
+) -> ZammResult<ClientWithMiddleware> {
+    let app_dir = app_handle.path().app_config_dir().ok();
+    build_http_client(&get_preferences_helper(&app_dir), default_headers)
+}
+
+/// Client for talking to `service`, with the options of the key profile sent
+/// along. The active profile gets used if none is requested.
+pub async fn service_http_client(
+    app_handle: &tauri::AppHandle,
+    zamm_api_keys: &ZammApiKeys,
+    service: &Service,
+    profile: Option<&str>,
+) -> ZammResult<ClientWithMiddleware> {
+    let api_keys = zamm_api_keys.0.lock().await;
+    let default_headers = match service {
+        Service::OpenAI | Service::Azure => {
+            api_keys.options_for(service, profile).openai_headers()?
+        }
+        _ => HeaderMap::new(),
+    };
+    app_http_client(app_handle, default_headers)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_with_network_preferences() {
+        let preferences = Preferences {
+            proxy_url: Some("http://proxy.example.com:8080".to_string()),
+            no_proxy: Some("localhost,127.0.0.1".to_string()),
+            request_timeout_secs: Some(30),
+            ..Default::default()
+        };
+        assert!(build_http_client(&preferences, HeaderMap::new()).is_ok());
+    }
+
+    #[test]
+    fn test_missing_ca_bundle() {
+        let preferences = Preferences {
+            ca_bundle_path: Some("nonexistent/ca.pem".to_string()),
+            ..Default::default()
+        };
+        let error = build_http_client(&preferences, HeaderMap::new()).unwrap_err();
+        assert!(error
+            .to_string()
+            .starts_with("Couldn't read CA bundle nonexistent/ca.pem:"));
+    }
+}
@@ -19,6 +19,7 @@ pub async fn get_api_keys(api_keys: State<'_, ZammApiKeys>) -> ZammResult<ApiKey
 pub mod tests {
     use super::*;
     use crate::sample_call::SampleCall;
+    use crate::setup::api_keys::ApiKeySource;
     use crate::test_helpers::{
         SampleCallTestCase, SideEffectsHelpers, ZammResultReturn,
     };
@@ -85,6 +86,8 @@ pub mod tests {
     async fn test_get_openai_key() {
         let api_keys = ZammApiKeys(Mutex::new(ApiKeys {
             openai: Some("0p3n41-4p1-k3y".to_string()),
+            openai_source: Some(ApiKeySource::Database),
+            ..Default::default()
         }));
 
         check_get_api_keys_sample(
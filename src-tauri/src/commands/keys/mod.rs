@@ -1,8 +1,18 @@
 mod get;
 mod set;
+mod set_active_profile;
+mod set_options;
+mod set_passphrase;
+mod test_key;
+mod unlock;
 
 pub use get::get_api_keys;
-pub use set::set_api_key;
+pub use set::{set_api_key, set_api_key_profile};
+pub use set_active_profile::set_active_api_key_profile;
+pub use set_options::set_api_key_options;
+pub use set_passphrase::set_api_key_passphrase;
+pub use test_key::test_api_key;
+pub use unlock::unlock_api_keys;
 
 #[cfg(test)]
 mod tests {
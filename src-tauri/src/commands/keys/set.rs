@@ -1,50 +1,102 @@
 use crate::commands::errors::ZammResult;
+use crate::commands::http_client::service_http_client;
+use crate::commands::keys::test_key::test_api_key_helper;
+use crate::models::shell::Shell;
 use crate::schema::api_keys;
 use crate::setup::api_keys::Service;
 use crate::{ZammApiKeys, ZammDatabase};
+use anyhow::anyhow;
 use diesel::{ExpressionMethods, RunQueryDsl};
+use reqwest_middleware::ClientWithMiddleware;
 use specta::specta;
 use tauri::State;
 
-use std::fs::OpenOptions;
-use std::io::{Read, Write};
+use std::fs;
 use std::path::Path;
 
+/// Replaces any line that ZAMM would have written for this variable, including
+/// unmarked ones from older versions, with one for the new key, or removes such
+/// lines if the key is empty.
+fn update_init_file(path: &Path, env_var: &str, api_key: &str) -> ZammResult<()> {
+    let shell = Shell::for_init_file(path);
+    let contents = if path.exists() {
+        fs::read_to_string(path)?
+    } else {
+        String::new()
+    };
+    let new_line = if api_key.is_empty() {
+        None
+    } else {
+        Some(shell.export_line(env_var, api_key))
+    };
+
+    let mut lines = Vec::new();
+    let mut found_existing = false;
+    for line in contents.lines() {
+        if !shell.is_export_line(line, env_var) {
+            lines.push(line.to_string());
+        } else if !found_existing {
+            // keep the new key where the old one was, and drop any duplicates
+            found_existing = true;
+            lines.extend(new_line.clone());
+        }
+    }
+    if !found_existing {
+        match new_line {
+            Some(line) => lines.push(line),
+            None => return Ok(()), // nothing to remove, so leave the file alone
+        }
+    }
+
+    // keep Windows files with Windows line endings
+    let line_ending = if contents.contains("\r\n") {
+        "\r\n"
+    } else {
+        "\n"
+    };
+    let mut new_contents = lines.join(line_ending);
+    new_contents.push_str(line_ending);
+    fs::write(path, new_contents)?;
+    Ok(())
+}
+
 async fn set_api_key_helper(
     zamm_api_keys: &ZammApiKeys,
     zamm_db: &ZammDatabase,
     filename: Option<&str>,
     service: &Service,
     api_key: String,
+    profile: Option<&str>,
+    validation_client: Option<ClientWithMiddleware>,
 ) -> ZammResult<()> {
+    // check the key before anything gets saved, so that a typo doesn't replace a
+    // working key
+    if let Some(http_client) = validation_client {
+        if !api_key.is_empty() {
+            test_api_key_helper(zamm_api_keys, service, &api_key, http_client).await?;
+        }
+    }
+
     let api_keys = &mut zamm_api_keys.0.lock().await;
     let db = &mut zamm_db.0.lock().await;
+    let active_profile = api_keys.active_profile(service).to_string();
+    let profile = profile.unwrap_or(&active_profile);
 
     // write new API key to disk before we can no longer borrow it
     let init_update_result = || -> ZammResult<()> {
-        if api_key.is_empty() {
+        // only the active key should end up in the environment
+        if profile != active_profile {
             return Ok(());
         }
 
+        let env_var = match service.key_env_vars().first() {
+            Some(env_var) => env_var,
+            None => return Ok(()),
+        };
         if let Some(untrimmed_filename) = filename {
             let f = untrimmed_filename.trim();
             if !f.is_empty() {
-                let ends_in_newline = {
-                    if Path::new(f).exists() {
-                        let mut file = OpenOptions::new().read(true).open(f)?;
-                        let mut contents = String::new();
-                        file.read_to_string(&mut contents)?;
-                        contents.ends_with('\n')
-                    } else {
-                        true // no need to start the file with a newline later
-                    }
-                };
-
-                let mut file = OpenOptions::new().create(true).append(true).open(f)?;
-                if !ends_in_newline {
-                    writeln!(file)?;
-                }
-                writeln!(file, "export OPENAI_API_KEY=\"{}\"", api_key)?;
+                update_init_file(Path::new(f), env_var, &api_key)?;
             }
         }
         Ok(())
@@ -57,12 +109,25 @@ async fn set_api_key_helper(
                 // delete from db
                 diesel::delete(api_keys::table)
                     .filter(api_keys::service.eq(service))
+                    .filter(api_keys::profile.eq(profile))
                     .execute(conn)?;
             } else {
+                let stored_key = match &api_keys.cipher {
+                    Some(cipher) => cipher.encrypt(&api_key)?,
+                    // never let a key slip into the database unencrypted
+                    None if api_keys.locked => {
+                        return Err(anyhow!(
+                            "Unlock the stored API keys before saving a new one"
+                        )
+                        .into())
+                    }
+                    None => api_key.clone(),
+                };
                 diesel::replace_into(api_keys::table)
                     .values(crate::models::NewApiKey {
                         service: service.clone(),
-                        api_key: &api_key,
+                        profile,
+                        api_key: &stored_key,
                     })
                     .execute(conn)?;
             }
@@ -72,9 +137,9 @@ async fn set_api_key_helper(
 
     // assign ownership of new API key string to in-memory API keys
     if api_key.is_empty() {
-        api_keys.remove(service)?;
+        api_keys.remove_profile(service, profile)?;
     } else {
-        api_keys.update(service, api_key)?;
+        api_keys.save_profile(service, profile, api_key)?;
     }
 
     // if any errors exist, return one of them
@@ -85,24 +150,65 @@ async fn set_api_key_helper(
 #[tauri::command(async)]
 #[specta]
 pub async fn set_api_key(
+    app_handle: tauri::AppHandle,
     api_keys: State<'_, ZammApiKeys>,
     database: State<'_, ZammDatabase>,
     filename: Option<&str>,
     service: Service,
     api_key: String,
+    validate: bool,
 ) -> ZammResult<()> {
-    set_api_key_helper(&api_keys, &database, filename, &service, api_key).await
+    let validation_client = if validate {
+        Some(service_http_client(&app_handle, &api_keys, &service, None).await?)
+    } else {
+        None
+    };
+    set_api_key_helper(
+        &api_keys,
+        &database,
+        filename,
+        &service,
+        api_key,
+        None,
+        validation_client,
+    )
+    .await
+}
+
+/// Saves a key under a named profile. Unlike `set_api_key`, this never writes
+/// to the shell init file, because the profile might not be the active one.
+#[tauri::command(async)]
+#[specta]
+pub async fn set_api_key_profile(
+    api_keys: State<'_, ZammApiKeys>,
+    database: State<'_, ZammDatabase>,
+    service: Service,
+    profile: String,
+    api_key: String,
+) -> ZammResult<()> {
+    set_api_key_helper(
+        &api_keys,
+        &database,
+        None,
+        &service,
+        api_key,
+        Some(&profile),
+        None,
+    )
+    .await
 }
 
 #[cfg(test)]
 pub mod tests {
     use super::*;
     use crate::sample_call::SampleCall;
-    use crate::setup::api_keys::ApiKeys;
+    use crate::setup::api_keys::{setup_api_keys, ApiKeys};
     use crate::test_helpers::api_testing::{check_zamm_result, standard_test_subdir};
+    use crate::test_helpers::temp_files::get_temp_test_dir;
     use crate::test_helpers::{
         SampleCallTestCase, SideEffectsHelpers, ZammResultReturn,
     };
+    use crate::{check_sample, impl_result_test_case};
     use serde::{Deserialize, Serialize};
     use std::collections::HashMap;
     use stdext::function_name;
@@ -113,6 +219,7 @@ pub mod tests {
         filename: Option<String>,
         service: Service,
         api_key: String,
+        validate: bool,
     }
 
     struct SetApiKeyTestCase<'a> {
@@ -136,12 +243,19 @@ pub mod tests {
             args: &SetApiKeyRequest,
             side_effects: &mut SideEffectsHelpers,
         ) -> ZammResult<()> {
+            let validation_client = side_effects
+                .network
+                .as_ref()
+                .filter(|_| args.validate)
+                .map(|network_helper| network_helper.network_client.clone());
             set_api_key_helper(
                 self.api_keys,
                 side_effects.db.as_ref().unwrap(),
                 args.filename.as_deref(),
                 &args.service,
                 args.api_key.clone(),
+                None,
+                validation_client,
             )
             .await
         }
@@ -171,9 +285,9 @@ pub mod tests {
             check_zamm_result(sample, result);
 
             // check that the API call actually modified the in-memory API keys,
-            // regardless of success or failure
+            // regardless of success or failure, unless the key got rejected
             let existing_api_keys = &self.api_keys.0.lock().await;
-            if args.api_key.is_empty() {
+            if args.api_key.is_empty() || (args.validate && result.is_err()) {
                 assert_eq!(existing_api_keys.openai, None);
             } else {
                 let arg_api_key = Some(args.api_key.clone());
@@ -267,10 +381,22 @@ pub mod tests {
         .await;
     }
 
+    #[tokio::test]
+    async fn test_reject_invalid_key() {
+        let api_keys = ZammApiKeys(Mutex::new(ApiKeys::default()));
+        check_set_api_key_sample_unit(
+            function_name!(),
+            &api_keys,
+            "api/sample-calls/set_api_key-invalid-key.yaml",
+        )
+        .await;
+    }
+
     #[tokio::test]
     async fn test_overwrite_different_key() {
         let api_keys = ZammApiKeys(Mutex::new(ApiKeys {
             openai: Some("0p3n41-4p1-k3y".to_string()),
+            ..Default::default()
         }));
         check_set_api_key_sample_unit(
             function_name!(),
@@ -280,10 +406,84 @@ pub mod tests {
         .await;
     }
 
+    #[tokio::test]
+    async fn test_replace_existing_key_in_init_file() {
+        let api_keys = ZammApiKeys(Mutex::new(ApiKeys {
+            openai: Some("0p3n41-4p1-k3y".to_string()),
+            ..Default::default()
+        }));
+        check_set_api_key_sample_unit(
+            function_name!(),
+            &api_keys,
+            "api/sample-calls/set_api_key-replace-existing.yaml",
+        )
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_replace_legacy_key_in_init_file() {
+        let api_keys = ZammApiKeys(Mutex::new(ApiKeys {
+            openai: Some("0p3n41-4p1-k3y".to_string()),
+            ..Default::default()
+        }));
+        check_set_api_key_sample_unit(
+            function_name!(),
+            &api_keys,
+            "api/sample-calls/set_api_key-replace-legacy.yaml",
+        )
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_fish_init_file() {
+        let api_keys = ZammApiKeys(Mutex::new(ApiKeys::default()));
+        check_set_api_key_sample_unit(
+            function_name!(),
+            &api_keys,
+            "api/sample-calls/set_api_key-fish.yaml",
+        )
+        .await;
+    }
+
+    #[tokio::test]
+    async fn test_powershell_init_file() {
+        let api_keys = ZammApiKeys(Mutex::new(ApiKeys::default()));
+        check_set_api_key_sample_unit(
+            function_name!(),
+            &api_keys,
+            "api/sample-calls/set_api_key-powershell.yaml",
+        )
+        .await;
+    }
+
+    #[test]
+    fn test_keep_windows_line_endings() {
+        let init_file =
+            get_temp_test_dir("test_keep_windows_line_endings").join(".bashrc");
+        fs::write(
+            &init_file,
+            concat!(
+                "# dummy initial bashrc file\r\n",
+                "export OPENAI_API_KEY=\"0p3n41-4p1-k3y\" # zamm\r\n",
+            ),
+        )
+        .unwrap();
+
+        update_init_file(&init_file, "OPENAI_API_KEY", "4-d1ff3r3n7-k3y").unwrap();
+        assert_eq!(
+            fs::read_to_string(&init_file).unwrap(),
+            concat!(
+                "# dummy initial bashrc file\r\n",
+                "export OPENAI_API_KEY=\"4-d1ff3r3n7-k3y\" # zamm\r\n",
+            )
+        );
+    }
+
     #[tokio::test]
     async fn test_unset() {
         let api_keys = ZammApiKeys(Mutex::new(ApiKeys {
             openai: Some("0p3n41-4p1-k3y".to_string()),
+            ..Default::default()
         }));
         check_set_api_key_sample_unit(
             function_name!(),
@@ -294,6 +494,21 @@ pub mod tests {
         assert!(api_keys.0.lock().await.openai.is_none());
     }
 
+    #[tokio::test]
+    async fn test_unset_legacy() {
+        let api_keys = ZammApiKeys(Mutex::new(ApiKeys {
+            openai: Some("0p3n41-4p1-k3y".to_string()),
+            ..Default::default()
+        }));
+        check_set_api_key_sample_unit(
+            function_name!(),
+            &api_keys,
+            "api/sample-calls/set_api_key-unset-legacy.yaml",
+        )
+        .await;
+        assert!(api_keys.0.lock().await.openai.is_none());
+    }
+
     #[tokio::test]
     async fn test_empty_filename() {
         let api_keys = ZammApiKeys(Mutex::new(ApiKeys::default()));
@@ -321,4 +536,57 @@ pub mod tests {
         )
         .await;
     }
+
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    struct SetApiKeyProfileRequest {
+        service: Service,
+        profile: String,
+        api_key: String,
+    }
+
+    async fn make_request_helper(
+        args: &SetApiKeyProfileRequest,
+        side_effects: &mut SideEffectsHelpers,
+    ) -> ZammResult<()> {
+        let zamm_db = side_effects.db.as_ref().unwrap();
+        let api_keys = ZammApiKeys(Mutex::new(setup_api_keys(
+            &mut *zamm_db.0.lock().await,
+            None,
+            Vec::new(),
+        )));
+        let active_key = api_keys.0.lock().await.openai.clone();
+        let result = set_api_key_helper(
+            &api_keys,
+            zamm_db,
+            None,
+            &args.service,
+            args.api_key.clone(),
+            Some(&args.profile),
+            None,
+        )
+        .await;
+
+        let api_keys = api_keys.0.lock().await;
+        assert_eq!(
+            api_keys.key_for(&args.service, Some(&args.profile)),
+            Some(args.api_key.as_str())
+        );
+        // saving another profile shouldn't change the key that's in use
+        assert_eq!(api_keys.openai, active_key);
+        result
+    }
+
+    impl_result_test_case!(
+        SetApiKeyProfileTestCase,
+        set_api_key_profile,
+        true,
+        SetApiKeyProfileRequest,
+        ()
+    );
+
+    check_sample!(
+        SetApiKeyProfileTestCase,
+        test_named_profile,
+        "./api/sample-calls/set_api_key_profile-team.yaml"
+    );
 }
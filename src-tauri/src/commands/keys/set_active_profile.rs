@@ -0,0 +1,107 @@
+use crate::commands::errors::ZammResult;
+use crate::models::ActiveApiKeyProfile;
+use crate::schema::active_api_key_profiles;
+use crate::setup::api_keys::{Service, DEFAULT_PROFILE};
+use crate::{ZammApiKeys, ZammDatabase};
+use diesel::{ExpressionMethods, RunQueryDsl};
+use specta::specta;
+use tauri::State;
+
+async fn set_active_api_key_profile_helper(
+    zamm_api_keys: &ZammApiKeys,
+    zamm_db: &ZammDatabase,
+    service: &Service,
+    profile: &str,
+) -> ZammResult<()> {
+    let api_keys = &mut zamm_api_keys.0.lock().await;
+    // fail on unknown profiles before anything gets saved
+    api_keys.activate_profile(service, profile)?;
+
+    let db = &mut zamm_db.0.lock().await;
+    if let Some(conn) = db.as_mut() {
+        if profile == DEFAULT_PROFILE {
+            diesel::delete(active_api_key_profiles::table)
+                .filter(active_api_key_profiles::service.eq(service))
+                .execute(conn)?;
+        } else {
+            diesel::replace_into(active_api_key_profiles::table)
+                .values(ActiveApiKeyProfile {
+                    service: service.clone(),
+                    profile: profile.to_string(),
+                })
+                .execute(conn)?;
+        }
+    }
+    Ok(())
+}
+
+#[tauri::command(async)]
+#[specta]
+pub async fn set_active_api_key_profile(
+    api_keys: State<'_, ZammApiKeys>,
+    database: State<'_, ZammDatabase>,
+    service: Service,
+    profile: String,
+) -> ZammResult<()> {
+    set_active_api_key_profile_helper(&api_keys, &database, &service, &profile).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::setup::api_keys::setup_api_keys;
+    use crate::test_helpers::SideEffectsHelpers;
+    use crate::{check_sample, impl_result_test_case};
+    use serde::{Deserialize, Serialize};
+    use tokio::sync::Mutex;
+
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    struct SetActiveApiKeyProfileRequest {
+        service: Service,
+        profile: String,
+    }
+
+    async fn make_request_helper(
+        args: &SetActiveApiKeyProfileRequest,
+        side_effects: &mut SideEffectsHelpers,
+    ) -> ZammResult<()> {
+        let zamm_db = side_effects.db.as_ref().unwrap();
+        let api_keys = ZammApiKeys(Mutex::new(setup_api_keys(
+            &mut *zamm_db.0.lock().await,
+            None,
+            Vec::new(),
+        )));
+        let result = set_active_api_key_profile_helper(
+            &api_keys,
+            zamm_db,
+            &args.service,
+            &args.profile,
+        )
+        .await;
+        if result.is_ok() {
+            let api_keys = api_keys.0.lock().await;
+            assert_eq!(api_keys.active_profile(&args.service), args.profile);
+        }
+        result
+    }
+
+    impl_result_test_case!(
+        SetActiveApiKeyProfileTestCase,
+        set_active_api_key_profile,
+        true,
+        SetActiveApiKeyProfileRequest,
+        ()
+    );
+
+    check_sample!(
+        SetActiveApiKeyProfileTestCase,
+        test_switch_profile,
+        "./api/sample-calls/set_active_api_key_profile-team.yaml"
+    );
+
+    check_sample!(
+        SetActiveApiKeyProfileTestCase,
+        test_missing_profile,
+        "./api/sample-calls/set_active_api_key_profile-missing.yaml"
+    );
+}
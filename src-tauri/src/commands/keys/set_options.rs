@@ -0,0 +1,178 @@
+use crate::commands::errors::ZammResult;
+use crate::models::NewApiKeyOptions;
+use crate::schema::api_key_options;
+use crate::setup::api_keys::{ApiKeyOptions, Service};
+use crate::{ZammApiKeys, ZammDatabase};
+use anyhow::anyhow;
+use diesel::{ExpressionMethods, RunQueryDsl};
+use specta::specta;
+use tauri::State;
+
+async fn set_api_key_options_helper(
+    zamm_api_keys: &ZammApiKeys,
+    zamm_db: &ZammDatabase,
+    service: &Service,
+    profile: Option<&str>,
+    options: ApiKeyOptions,
+) -> ZammResult<()> {
+    let api_keys = &mut zamm_api_keys.0.lock().await;
+    let profile = profile
+        .unwrap_or(api_keys.active_profile(service))
+        .to_string();
+    // fail on invalid headers before anything gets saved
+    options.openai_headers()?;
+    let stored_options = match &api_keys.cipher {
+        Some(cipher) => options.encrypt_headers(cipher)?,
+        // never let a header slip into the database unencrypted
+        None if api_keys.locked => {
+            return Err(anyhow!(
+                "Unlock the stored API keys before changing key options"
+            )
+            .into())
+        }
+        None => options.clone(),
+    };
+    api_keys.set_options(service, Some(&profile), options)?;
+
+    let db = &mut zamm_db.0.lock().await;
+    if let Some(conn) = db.as_mut() {
+        if stored_options.is_default() {
+            diesel::delete(api_key_options::table)
+                .filter(api_key_options::service.eq(service))
+                .filter(api_key_options::profile.eq(&profile))
+                .execute(conn)?;
+        } else {
+            diesel::replace_into(api_key_options::table)
+                .values(NewApiKeyOptions {
+                    service: service.clone(),
+                    profile: &profile,
+                    options: &stored_options,
+                })
+                .execute(conn)?;
+        }
+    }
+    Ok(())
+}
+
+#[tauri::command(async)]
+#[specta]
+pub async fn set_api_key_options(
+    api_keys: State<'_, ZammApiKeys>,
+    database: State<'_, ZammDatabase>,
+    service: Service,
+    profile: Option<String>,
+    options: ApiKeyOptions,
+) -> ZammResult<()> {
+    set_api_key_options_helper(
+        &api_keys,
+        &database,
+        &service,
+        profile.as_deref(),
+        options,
+    )
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::ApiKeyOptionsRow;
+    use crate::schema::api_key_encryption;
+    use crate::setup::api_keys::{setup_api_keys, ApiKeys};
+    use crate::setup::encryption::new_encryption;
+    use crate::test_helpers::database::setup_database;
+    use crate::test_helpers::SideEffectsHelpers;
+    use crate::{check_sample, impl_result_test_case};
+    use serde::{Deserialize, Serialize};
+    use std::collections::BTreeMap;
+    use tokio::sync::Mutex;
+
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    struct SetApiKeyOptionsRequest {
+        service: Service,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        profile: Option<String>,
+        options: ApiKeyOptions,
+    }
+
+    async fn make_request_helper(
+        args: &SetApiKeyOptionsRequest,
+        side_effects: &mut SideEffectsHelpers,
+    ) -> ZammResult<()> {
+        let api_keys = ZammApiKeys(Mutex::new(ApiKeys::default()));
+        let result = set_api_key_options_helper(
+            &api_keys,
+            side_effects.db.as_ref().unwrap(),
+            &args.service,
+            args.profile.as_deref(),
+            args.options.clone(),
+        )
+        .await;
+        if result.is_ok() {
+            assert_eq!(api_keys.0.lock().await.openai_options, args.options);
+        }
+        result
+    }
+
+    impl_result_test_case!(
+        SetApiKeyOptionsTestCase,
+        set_api_key_options,
+        true,
+        SetApiKeyOptionsRequest,
+        ()
+    );
+
+    check_sample!(
+        SetApiKeyOptionsTestCase,
+        test_organization_and_project,
+        "./api/sample-calls/set_api_key_options-openai.yaml"
+    );
+
+    check_sample!(
+        SetApiKeyOptionsTestCase,
+        test_invalid_header,
+        "./api/sample-calls/set_api_key_options-invalid-header.yaml"
+    );
+
+    #[tokio::test]
+    async fn test_extra_headers_encrypted() {
+        let (cipher, encryption) = new_encryption("correct horse").unwrap();
+        let mut conn = setup_database(None);
+        diesel::insert_into(api_key_encryption::table)
+            .values(&encryption)
+            .execute(&mut conn)
+            .unwrap();
+        let zamm_db = ZammDatabase(Mutex::new(Some(conn)));
+        let api_keys = ZammApiKeys(Mutex::new(ApiKeys {
+            cipher: Some(cipher.clone()),
+            ..Default::default()
+        }));
+        let options = ApiKeyOptions {
+            extra_headers: BTreeMap::from([(
+                "X-Gateway-Token".to_string(),
+                "g4t3w4y-t0k3n".to_string(),
+            )]),
+            ..Default::default()
+        };
+
+        set_api_key_options_helper(
+            &api_keys,
+            &zamm_db,
+            &Service::OpenAI,
+            None,
+            options.clone(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(api_keys.0.lock().await.openai_options, options);
+
+        let db = &mut zamm_db.0.lock().await;
+        let stored_options: Vec<ApiKeyOptionsRow> =
+            api_key_options::table.load(db.as_mut().unwrap()).unwrap();
+        assert_eq!(stored_options.len(), 1);
+        assert!(stored_options[0].options.has_encrypted_headers());
+
+        let reloaded_keys = setup_api_keys(db, Some(cipher), Vec::new());
+        assert_eq!(reloaded_keys.openai_options, options);
+    }
+}
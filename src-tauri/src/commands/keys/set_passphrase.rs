@@ -0,0 +1,178 @@
+use crate::commands::errors::{Error, ZammResult};
+use crate::models::{ApiKey, ApiKeyOptionsRow};
+use crate::schema::{api_key_encryption, api_key_options, api_keys};
+use crate::setup::encryption::{is_encrypted, new_encryption};
+use crate::{ZammApiKeys, ZammDatabase};
+use anyhow::anyhow;
+use diesel::prelude::*;
+use specta::specta;
+use tauri::State;
+
+async fn set_api_key_passphrase_helper(
+    zamm_api_keys: &ZammApiKeys,
+    zamm_db: &ZammDatabase,
+    passphrase: Option<&str>,
+) -> ZammResult<()> {
+    let api_keys = &mut zamm_api_keys.0.lock().await;
+    if api_keys.locked {
+        return Err(anyhow!(
+            "Unlock the stored API keys before changing the passphrase"
+        )
+        .into());
+    }
+    let encryption = passphrase.map(new_encryption).transpose()?;
+
+    let db = &mut zamm_db.0.lock().await;
+    if let Some(conn) = db.as_mut() {
+        // re-encrypt everything at once, so that keys never end up under a mix of
+        // old and new passphrases
+        conn.transaction::<(), Error, _>(|conn| {
+            let stored_keys: Vec<ApiKey> = api_keys::table.load(conn)?;
+            for stored_key in stored_keys {
+                let plaintext = match &api_keys.cipher {
+                    Some(cipher) if is_encrypted(&stored_key.api_key) => {
+                        cipher.decrypt(&stored_key.api_key)?
+                    }
+                    _ => stored_key.api_key.clone(),
+                };
+                let new_stored_key = match &encryption {
+                    Some((cipher, _)) => cipher.encrypt(&plaintext)?,
+                    None => plaintext,
+                };
+                diesel::update(
+                    api_keys::table
+                        .filter(api_keys::service.eq(&stored_key.service))
+                        .filter(api_keys::profile.eq(&stored_key.profile)),
+                )
+                .set(api_keys::api_key.eq(new_stored_key))
+                .execute(conn)?;
+            }
+
+            let stored_options: Vec<ApiKeyOptionsRow> =
+                api_key_options::table.load(conn)?;
+            for stored in stored_options {
+                let plaintext = match &api_keys.cipher {
+                    Some(cipher) => stored.options.decrypt_headers(cipher)?,
+                    None => stored.options.clone(),
+                };
+                let new_stored_options = match &encryption {
+                    Some((cipher, _)) => plaintext.encrypt_headers(cipher)?,
+                    None => plaintext,
+                };
+                diesel::update(
+                    api_key_options::table
+                        .filter(api_key_options::service.eq(&stored.service))
+                        .filter(api_key_options::profile.eq(&stored.profile)),
+                )
+                .set(api_key_options::options.eq(new_stored_options))
+                .execute(conn)?;
+            }
+
+            diesel::delete(api_key_encryption::table).execute(conn)?;
+            if let Some((_, settings)) = &encryption {
+                diesel::insert_into(api_key_encryption::table)
+                    .values(settings)
+                    .execute(conn)?;
+            }
+            Ok(())
+        })?;
+    }
+
+    api_keys.cipher = encryption.map(|(cipher, _)| cipher);
+    Ok(())
+}
+
+/// Encrypts stored keys and extra header values under the passphrase, or decrypts
+/// them if there's no passphrase anymore.
+#[tauri::command(async)]
+#[specta]
+pub async fn set_api_key_passphrase(
+    api_keys: State<'_, ZammApiKeys>,
+    database: State<'_, ZammDatabase>,
+    passphrase: Option<String>,
+) -> ZammResult<()> {
+    set_api_key_passphrase_helper(&api_keys, &database, passphrase.as_deref()).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::keys::unlock::unlock_api_keys_helper;
+    use crate::models::NewApiKey;
+    use crate::setup::api_keys::{Service, DEFAULT_PROFILE};
+    use crate::setup::encryption::{get_encryption, unlock};
+    use crate::test_helpers::database::setup_database;
+    use crate::test_helpers::SideEffectsHelpers;
+    use crate::{check_sample, impl_result_test_case};
+    use serde::{Deserialize, Serialize};
+    use tokio::sync::Mutex;
+
+    /// Passphrase that the encrypted sample database was set up with
+    const SAMPLE_PASSPHRASE: &str = "correct horse battery staple";
+
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    struct SetApiKeyPassphraseRequest {
+        passphrase: Option<String>,
+    }
+
+    async fn make_request_helper(
+        args: &SetApiKeyPassphraseRequest,
+        side_effects: &mut SideEffectsHelpers,
+    ) -> ZammResult<()> {
+        let zamm_db = side_effects.db.as_ref().unwrap();
+        let api_keys = ZammApiKeys(Mutex::new(Default::default()));
+        unlock_api_keys_helper(&api_keys, zamm_db, SAMPLE_PASSPHRASE)
+            .await
+            .unwrap();
+
+        set_api_key_passphrase_helper(&api_keys, zamm_db, args.passphrase.as_deref())
+            .await
+    }
+
+    impl_result_test_case!(
+        SetApiKeyPassphraseTestCase,
+        set_api_key_passphrase,
+        true,
+        SetApiKeyPassphraseRequest,
+        ()
+    );
+
+    check_sample!(
+        SetApiKeyPassphraseTestCase,
+        test_remove_passphrase,
+        "./api/sample-calls/set_api_key_passphrase-remove.yaml"
+    );
+
+    #[tokio::test]
+    async fn test_encrypt_existing_keys() {
+        let mut conn = setup_database(None);
+        diesel::insert_into(api_keys::table)
+            .values(&NewApiKey {
+                service: Service::OpenAI,
+                profile: DEFAULT_PROFILE,
+                api_key: "0p3n41-4p1-k3y",
+            })
+            .execute(&mut conn)
+            .unwrap();
+        let zamm_db = ZammDatabase(Mutex::new(Some(conn)));
+        let api_keys = ZammApiKeys(Mutex::new(Default::default()));
+
+        set_api_key_passphrase_helper(&api_keys, &zamm_db, Some(SAMPLE_PASSPHRASE))
+            .await
+            .unwrap();
+        assert!(api_keys.0.lock().await.cipher.is_some());
+
+        let db = &mut zamm_db.0.lock().await;
+        let conn = db.as_mut().unwrap();
+        let stored_keys: Vec<ApiKey> = api_keys::table.load(conn).unwrap();
+        assert_eq!(stored_keys.len(), 1);
+        assert!(is_encrypted(&stored_keys[0].api_key));
+
+        let settings = get_encryption(conn).unwrap().unwrap();
+        let cipher = unlock(&settings, SAMPLE_PASSPHRASE).unwrap();
+        assert_eq!(
+            cipher.decrypt(&stored_keys[0].api_key).unwrap(),
+            "0p3n41-4p1-k3y"
+        );
+    }
+}
@@ -0,0 +1,196 @@
+use crate::commands::errors::ZammResult;
+use crate::commands::http_client::service_http_client;
+use crate::commands::llms::chat::DEFAULT_AZURE_API_VERSION;
+use crate::commands::Error;
+use crate::setup::api_keys::Service;
+use crate::ZammApiKeys;
+use anyhow::anyhow;
+use reqwest::StatusCode;
+use reqwest_middleware::ClientWithMiddleware;
+use specta::specta;
+use std::error::Error as StdError;
+use tauri::State;
+
+const OPENAI_MODELS_URL: &str = "https://api.openai.com/v1/models";
+
+/// Why the request didn't get through, without the error codes that differ
+/// between operating systems.
+fn connection_problem(err: &reqwest::Error) -> String {
+    let mut source = err.source();
+    while let Some(e) = source {
+        if let Some(io_error) = e.downcast_ref::<std::io::Error>() {
+            return io_error.kind().to_string();
+        }
+        source = e.source();
+    }
+    err.to_string()
+}
+
+fn network_error(service: &Service, err: reqwest_middleware::Error) -> Error {
+    match err {
+        reqwest_middleware::Error::Reqwest(e) if e.is_connect() || e.is_timeout() => {
+            Error::NoNetwork {
+                service: service.clone(),
+                reason: connection_problem(&e),
+            }
+        }
+        reqwest_middleware::Error::Reqwest(e) => e.into(),
+        reqwest_middleware::Error::Middleware(e) => e.into(),
+    }
+}
+
+/// Lists the available models, which is free but still needs a working key.
+pub async fn test_api_key_helper(
+    zamm_api_keys: &ZammApiKeys,
+    service: &Service,
+    api_key: &str,
+    http_client: ClientWithMiddleware,
+) -> ZammResult<()> {
+    let request = match service {
+        Service::OpenAI => http_client.get(OPENAI_MODELS_URL).bearer_auth(api_key),
+        Service::Azure => {
+            let api_keys = zamm_api_keys.0.lock().await;
+            let options = &api_keys.azure_options;
+            let endpoint = options
+                .endpoint
+                .as_ref()
+                .ok_or_else(|| anyhow!("No endpoint set for Azure"))?;
+            let api_version = options
+                .api_version
+                .as_deref()
+                .unwrap_or(DEFAULT_AZURE_API_VERSION);
+            let url = format!(
+                "{}/openai/models?api-version={}",
+                endpoint.trim_end_matches('/'),
+                api_version
+            );
+            http_client.get(url).header("api-key", api_key)
+        }
+        _ => return Err(anyhow!("{:?} doesn't use API keys", service).into()),
+    };
+
+    let response = request
+        .send()
+        .await
+        .map_err(|e| network_error(service, e))?;
+    let status = response.status();
+    if status.is_success() {
+        return Ok(());
+    }
+    match status {
+        StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => Err(Error::InvalidApiKey {
+            service: service.clone(),
+        }),
+        StatusCode::TOO_MANY_REQUESTS => {
+            let body = response.text().await?;
+            if body.contains("insufficient_quota") {
+                Err(Error::ApiKeyQuotaExceeded {
+                    service: service.clone(),
+                })
+            } else {
+                // only rate-limited, so the key itself got accepted
+                Ok(())
+            }
+        }
+        _ => Err(anyhow!(
+            "Unexpected response from {:?} while testing the API key: {}",
+            service,
+            status
+        )
+        .into()),
+    }
+}
+
+#[tauri::command(async)]
+#[specta]
+pub async fn test_api_key(
+    app_handle: tauri::AppHandle,
+    api_keys: State<'_, ZammApiKeys>,
+    service: Service,
+    api_key: String,
+) -> ZammResult<()> {
+    let http_client =
+        service_http_client(&app_handle, &api_keys, &service, None).await?;
+    test_api_key_helper(&api_keys, &service, &api_key, http_client).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::http_client::build_http_client;
+    use crate::commands::preferences::Preferences;
+    use crate::setup::api_keys::ApiKeys;
+    use crate::test_helpers::SideEffectsHelpers;
+    use crate::{check_sample, impl_result_test_case};
+    use rvcr::VCRMode;
+    use serde::{Deserialize, Serialize};
+    use std::env;
+    use tokio::sync::Mutex;
+
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    struct TestApiKeyRequest {
+        service: Service,
+        api_key: String,
+    }
+
+    /// For samples without a network recording, so that there's nothing to reach.
+    fn unreachable_http_client() -> ClientWithMiddleware {
+        // nothing listens on the discard port, so the connection gets refused
+        let preferences = Preferences {
+            proxy_url: Some("http://127.0.0.1:9".to_string()),
+            ..Default::default()
+        };
+        build_http_client(&preferences, Default::default()).unwrap()
+    }
+
+    async fn make_request_helper(
+        args: &TestApiKeyRequest,
+        side_effects: &mut SideEffectsHelpers,
+    ) -> ZammResult<()> {
+        let (api_key, http_client) = match side_effects.network.as_ref() {
+            Some(network_helper) => {
+                let api_key = match (&network_helper.mode, args.api_key.as_str()) {
+                    // "dummy" stands in for a real key that shouldn't be committed
+                    (VCRMode::Record, "dummy") => env::var("OPENAI_API_KEY").unwrap(),
+                    _ => args.api_key.clone(),
+                };
+                (api_key, network_helper.network_client.clone())
+            }
+            None => (args.api_key.clone(), unreachable_http_client()),
+        };
+        let api_keys = ZammApiKeys(Mutex::new(ApiKeys::default()));
+        test_api_key_helper(&api_keys, &args.service, &api_key, http_client).await
+    }
+
+    impl_result_test_case!(
+        TestApiKeyTestCase,
+        test_api_key,
+        true,
+        TestApiKeyRequest,
+        ()
+    );
+
+    check_sample!(
+        TestApiKeyTestCase,
+        test_valid_key,
+        "./api/sample-calls/test_api_key-valid.yaml"
+    );
+
+    check_sample!(
+        TestApiKeyTestCase,
+        test_invalid_key,
+        "./api/sample-calls/test_api_key-invalid.yaml"
+    );
+
+    check_sample!(
+        TestApiKeyTestCase,
+        test_quota_exceeded,
+        "./api/sample-calls/test_api_key-quota-exceeded.yaml"
+    );
+
+    check_sample!(
+        TestApiKeyTestCase,
+        test_no_network,
+        "./api/sample-calls/test_api_key-no-network.yaml"
+    );
+}
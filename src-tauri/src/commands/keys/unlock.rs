@@ -0,0 +1,85 @@
+use crate::commands::errors::ZammResult;
+use crate::setup::api_keys::setup_api_keys;
+use crate::setup::encryption::{get_encryption, unlock};
+use crate::{ZammApiKeys, ZammDatabase};
+use anyhow::anyhow;
+use specta::specta;
+use tauri::State;
+
+pub async fn unlock_api_keys_helper(
+    zamm_api_keys: &ZammApiKeys,
+    zamm_db: &ZammDatabase,
+    passphrase: &str,
+) -> ZammResult<()> {
+    let api_keys = &mut zamm_api_keys.0.lock().await;
+    let db = &mut zamm_db.0.lock().await;
+    let conn = db.as_mut().ok_or(anyhow!("Error getting db"))?;
+    let settings =
+        get_encryption(conn)?.ok_or(anyhow!("Stored API keys aren't encrypted"))?;
+    let cipher = unlock(&settings, passphrase)?;
+
+    let dotenv_files = api_keys.dotenv_files.clone();
+    **api_keys = setup_api_keys(db, Some(cipher), dotenv_files);
+    Ok(())
+}
+
+#[tauri::command(async)]
+#[specta]
+pub async fn unlock_api_keys(
+    api_keys: State<'_, ZammApiKeys>,
+    database: State<'_, ZammDatabase>,
+    passphrase: String,
+) -> ZammResult<()> {
+    unlock_api_keys_helper(&api_keys, &database, &passphrase).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::SideEffectsHelpers;
+    use crate::{check_sample, impl_result_test_case};
+    use serde::{Deserialize, Serialize};
+    use tokio::sync::Mutex;
+
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    struct UnlockApiKeysRequest {
+        passphrase: String,
+    }
+
+    async fn make_request_helper(
+        args: &UnlockApiKeysRequest,
+        side_effects: &mut SideEffectsHelpers,
+    ) -> ZammResult<()> {
+        let zamm_db = side_effects.db.as_ref().unwrap();
+        let api_keys = ZammApiKeys(Mutex::new(setup_api_keys(
+            &mut *zamm_db.0.lock().await,
+            None,
+            Vec::new(),
+        )));
+        assert!(api_keys.0.lock().await.locked);
+
+        let result = unlock_api_keys_helper(&api_keys, zamm_db, &args.passphrase).await;
+        assert_eq!(api_keys.0.lock().await.locked, result.is_err());
+        result
+    }
+
+    impl_result_test_case!(
+        UnlockApiKeysTestCase,
+        unlock_api_keys,
+        true,
+        UnlockApiKeysRequest,
+        ()
+    );
+
+    check_sample!(
+        UnlockApiKeysTestCase,
+        test_correct_passphrase,
+        "./api/sample-calls/unlock_api_keys-correct.yaml"
+    );
+
+    check_sample!(
+        UnlockApiKeysTestCase,
+        test_incorrect_passphrase,
+        "./api/sample-calls/unlock_api_keys-incorrect.yaml"
+    );
+}
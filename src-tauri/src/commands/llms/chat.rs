@@ -1,14 +1,22 @@
 use crate::commands::errors::ZammResult;
+use crate::commands::http_client::service_http_client;
+use crate::commands::llms::embeddings::spawn_embedding_backfill;
+use crate::commands::llms::mock::mock_chat;
 use crate::commands::Error;
 use crate::models::llm_calls::{
-    ChatMessage, ChatPrompt, EntityId, LightweightLlmCall, NewLlmCallFollowUp,
-    NewLlmCallRow, NewLlmCallVariant, Prompt, TokenMetadata,
+    ChatMessage, ChatPrompt, EntityId, FileAttachment, LightweightLlmCall,
+    NewLlmCallAttribution, NewLlmCallDeployment, NewLlmCallFollowUp,
+    NewLlmCallKeyProfile, NewLlmCallLatency, NewLlmCallRow, NewLlmCallVariant, Prompt,
+    TokenMetadata,
 };
-use crate::schema::{llm_call_follow_ups, llm_call_variants, llm_calls};
-use crate::setup::api_keys::Service;
+use crate::schema::{
+    llm_call_attributions, llm_call_deployments, llm_call_follow_ups,
+    llm_call_key_profiles, llm_call_latencies, llm_call_variants, llm_calls,
+};
+use crate::setup::api_keys::{Service, DEFAULT_PROFILE};
 use crate::{ZammApiKeys, ZammDatabase};
 use anyhow::anyhow;
-use async_openai::config::OpenAIConfig;
+use async_openai::config::{AzureConfig, Config, OpenAIConfig};
 use async_openai::types::{
     ChatCompletionRequestMessage, CreateChatCompletionRequestArgs,
 };
@@ -18,7 +26,10 @@ use ollama_rs::generation::chat::request::ChatMessageRequest;
 use ollama_rs::generation::chat::ChatMessage as OllamaChatMessage;
 use ollama_rs::Ollama;
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use specta::specta;
+use std::fs;
+use std::time::Instant;
 use tauri::State;
 use uuid::Uuid;
 
@@ -33,6 +44,100 @@ pub struct ChatArgs {
     previous_call_id: Option<Uuid>,
     #[serde(skip_serializing_if = "Option::is_none")]
     canonical_id: Option<Uuid>,
+    /// Paths to local files whose contents should be included in the prompt
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    attachments: Vec<String>,
+    /// Name of the saved key to use instead of the active one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    key_profile: Option<String>,
+}
+
+/// Keeps a stray attachment from filling up the model's context window.
+const MAX_ATTACHMENT_BYTES: u64 = 100_000;
+
+/// Reads the attached files into a single message with one labelled block per file.
+fn read_attachments(
+    paths: &[String],
+) -> ZammResult<Option<(ChatMessage, Vec<FileAttachment>)>> {
+    if paths.is_empty() {
+        return Ok(None);
+    }
+
+    let mut blocks = Vec::new();
+    let mut attachments = Vec::new();
+    for path in paths {
+        let size = fs::metadata(path)
+            .map_err(|e| anyhow!("Couldn't read attachment {}: {}", path, e))?
+            .len();
+        if size > MAX_ATTACHMENT_BYTES {
+            return Err(anyhow!(
+                "Attachment {} is {} bytes, which is over the limit of {} bytes",
+                path,
+                size,
+                MAX_ATTACHMENT_BYTES
+            )
+            .into());
+        }
+        let contents = fs::read_to_string(path)
+            .map_err(|e| anyhow!("Couldn't read attachment {}: {}", path, e))?;
+        blocks.push(format!(
+            "<file path=\"{}\">\n{}\n</file>",
+            path,
+            contents.trim_end()
+        ));
+        attachments.push(FileAttachment {
+            path: path.clone(),
+            sha256: format!("{:x}", Sha256::digest(contents.as_bytes())),
+        });
+    }
+    let text = blocks.join("\n\n");
+    Ok(Some((ChatMessage::Human { text }, attachments)))
+}
+
+/// Used when the Azure key options don't specify an API version.
+pub const DEFAULT_AZURE_API_VERSION: &str = "2024-02-01";
+
+/// Sends the prompt to any provider that speaks the OpenAI chat API. Returns the
+/// model that actually served the request, along with its response.
+async fn openai_chat<C: Config>(
+    client: &async_openai::Client<C>,
+    model: &str,
+    temperature: f32,
+    prompt_messages: &[ChatMessage],
+) -> ZammResult<(TokenMetadata, ChatMessage, String)> {
+    let messages: Vec<ChatCompletionRequestMessage> =
+        prompt_messages.iter().cloned().map(|m| m.into()).collect();
+    let request = CreateChatCompletionRequestArgs::default()
+        .model(model)
+        .temperature(temperature)
+        .messages(messages)
+        .build()?;
+    let response = client.chat().create(&request).await?;
+    let openai_token_metadata = TokenMetadata {
+        prompt: response
+            .usage
+            .as_ref()
+            .map(|usage| usage.prompt_tokens as i32),
+        response: response
+            .usage
+            .as_ref()
+            .map(|usage| usage.completion_tokens as i32),
+        total: response
+            .usage
+            .as_ref()
+            .map(|usage| usage.total_tokens as i32),
+    };
+    let sole_choice = response
+        .choices
+        .first()
+        .ok_or(Error::UnexpectedOpenAiResponse {
+            reason: "Zero choices".to_owned(),
+        })?
+        .message
+        .to_owned();
+    let openai_completion: ChatMessage = sole_choice.try_into()?;
+
+    Ok((openai_token_metadata, openai_completion, response.model))
 }
 
 async fn chat_helper(
@@ -42,65 +147,98 @@ async fn chat_helper(
     http_client: reqwest_middleware::ClientWithMiddleware,
 ) -> ZammResult<LightweightLlmCall> {
     let api_keys = zamm_api_keys.0.lock().await;
-    if api_keys.openai.is_none() {
-        return Err(Error::MissingApiKey {
-            service: Service::OpenAI,
-        });
+    let key_profile = args.key_profile.as_deref();
+    // the mock provider is meant to work offline without any setup
+    if args.provider != Service::Mock {
+        let key_service = match args.provider {
+            Service::Azure => Service::Azure,
+            _ => Service::OpenAI,
+        };
+        if api_keys.key_for(&key_service, key_profile).is_none() {
+            return Err(Error::MissingApiKey {
+                service: key_service,
+            });
+        }
+    }
+
+    let key_options = api_keys.options_for(&args.provider, key_profile);
+
+    let mut prompt_messages = args.prompt;
+    let mut attachments = Vec::new();
+    if let Some((attachments_message, file_attachments)) =
+        read_attachments(&args.attachments)?
+    {
+        // the files go right before the message that asks about them
+        let insert_index = prompt_messages.len().saturating_sub(1);
+        prompt_messages.insert(insert_index, attachments_message);
+        attachments = file_attachments;
     }
+    // the client only knows about the prompt it sent, so hand back the one with
+    // the files in it for follow-ups to build on
+    let expanded_prompt = (!attachments.is_empty()).then(|| prompt_messages.clone());
 
     let db = &mut zamm_db.0.lock().await;
 
     let requested_model = args.llm;
     let requested_temperature = args.temperature.unwrap_or(1.0);
 
+    let request_start = Instant::now();
     let (token_metadata, completion, retrieved_model) = match &args.provider {
         Service::OpenAI => {
-            let openai_api_key =
-                api_keys.openai.as_ref().ok_or(Error::MissingApiKey {
+            let openai_api_key = api_keys
+                .key_for(&Service::OpenAI, key_profile)
+                .ok_or(Error::MissingApiKey {
                     service: Service::OpenAI,
                 })?;
             let config = OpenAIConfig::new().with_api_key(openai_api_key);
             let openai_client =
                 async_openai::Client::with_config(config).with_http_client(http_client);
 
-            let messages: Vec<ChatCompletionRequestMessage> =
-                args.prompt.clone().into_iter().map(|m| m.into()).collect();
-            let request = CreateChatCompletionRequestArgs::default()
-                .model(&requested_model)
-                .temperature(requested_temperature)
-                .messages(messages)
-                .build()?;
-            let response = openai_client.chat().create(&request).await?;
-            let openai_token_metadata = TokenMetadata {
-                prompt: response
-                    .usage
-                    .as_ref()
-                    .map(|usage| usage.prompt_tokens as i32),
-                response: response
-                    .usage
-                    .as_ref()
-                    .map(|usage| usage.completion_tokens as i32),
-                total: response
-                    .usage
-                    .as_ref()
-                    .map(|usage| usage.total_tokens as i32),
-            };
-            let sole_choice = response
-                .choices
-                .first()
-                .ok_or(Error::UnexpectedOpenAiResponse {
-                    reason: "Zero choices".to_owned(),
-                })?
-                .message
-                .to_owned();
-            let openai_completion: ChatMessage = sole_choice.try_into()?;
-
-            Ok((openai_token_metadata, openai_completion, response.model))
+            openai_chat(
+                &openai_client,
+                &requested_model,
+                requested_temperature,
+                &prompt_messages,
+            )
+            .await
+        }
+        Service::Azure => {
+            let azure_api_key = api_keys.key_for(&Service::Azure, key_profile).ok_or(
+                Error::MissingApiKey {
+                    service: Service::Azure,
+                },
+            )?;
+            let endpoint = key_options
+                .endpoint
+                .as_ref()
+                .ok_or_else(|| anyhow!("No endpoint set for Azure"))?;
+            let config = AzureConfig::new()
+                .with_api_base(endpoint)
+                .with_api_version(
+                    key_options
+                        .api_version
+                        .as_deref()
+                        .unwrap_or(DEFAULT_AZURE_API_VERSION),
+                )
+                .with_deployment_id(key_options.azure_deployment(&requested_model))
+                .with_api_key(azure_api_key);
+            let azure_client =
+                async_openai::Client::with_config(config).with_http_client(http_client);
+            openai_chat(
+                &azure_client,
+                &requested_model,
+                requested_temperature,
+                &prompt_messages,
+            )
+            .await
         }
         Service::Ollama => {
             let ollama = Ollama::default().with_client(http_client);
-            let messages: Vec<OllamaChatMessage> =
-                args.prompt.clone().into_iter().map(|m| m.into()).collect();
+            let messages: Vec<OllamaChatMessage> = prompt_messages
+                .clone()
+                .into_iter()
+                .map(|m| m.into())
+                .collect();
             let response = ollama
                 .send_chat_messages(ChatMessageRequest::new(
                     requested_model.clone(),
@@ -128,8 +266,20 @@ async fn chat_helper(
                 requested_model.clone(),
             ))
         }
-        Service::Unknown(_) => Err(anyhow!("Unknown service provider requested")),
+        Service::Mock => {
+            let (mock_token_metadata, mock_completion) =
+                mock_chat(&requested_model, &prompt_messages).await?;
+            Ok((
+                mock_token_metadata,
+                mock_completion,
+                requested_model.clone(),
+            ))
+        }
+        Service::Unknown(_) => {
+            Err(anyhow!("Unknown service provider requested").into())
+        }
     }?;
+    let latency_ms = request_start.elapsed().as_millis() as i32;
 
     let previous_call_id = args.previous_call_id.map(|id| EntityId { uuid: id });
 
@@ -146,16 +296,56 @@ async fn chat_helper(
                 provider: &args.provider,
                 llm_requested: &requested_model,
                 llm: &retrieved_model,
-                temperature: &requested_temperature,
+                temperature: Some(&requested_temperature),
                 prompt_tokens: token_metadata.prompt.as_ref(),
                 response_tokens: token_metadata.response.as_ref(),
                 total_tokens: token_metadata.total.as_ref(),
                 prompt: &Prompt::Chat(ChatPrompt {
-                    messages: args.prompt,
+                    messages: prompt_messages,
+                    attachments,
                 }),
                 completion: &completion,
             })
             .execute(conn)?;
+        diesel::insert_into(llm_call_latencies::table)
+            .values(NewLlmCallLatency {
+                llm_call_id: &new_id,
+                latency_ms,
+            })
+            .execute(conn)?;
+
+        // calls without a profile row used either the default key or one from the
+        // environment
+        if let Some(used_profile) = api_keys
+            .stored_key_profile(&args.provider, key_profile)
+            .filter(|profile| *profile != DEFAULT_PROFILE)
+        {
+            diesel::insert_into(llm_call_key_profiles::table)
+                .values(NewLlmCallKeyProfile {
+                    llm_call_id: &new_id,
+                    profile: used_profile,
+                })
+                .execute(conn)?;
+        }
+
+        if args.provider == Service::Azure {
+            diesel::insert_into(llm_call_deployments::table)
+                .values(NewLlmCallDeployment {
+                    llm_call_id: &new_id,
+                    deployment: key_options.azure_deployment(&requested_model),
+                })
+                .execute(conn)?;
+        }
+
+        if args.provider == Service::OpenAI {
+            if let Some(attribution) =
+                NewLlmCallAttribution::from_options(&new_id, &key_options)
+            {
+                diesel::insert_into(llm_call_attributions::table)
+                    .values(attribution)
+                    .execute(conn)?;
+            }
+        }
 
         if let Some(previous_id) = previous_call_id {
             diesel::insert_into(llm_call_follow_ups::table)
@@ -189,20 +379,28 @@ async fn chat_helper(
         id: new_id,
         timestamp,
         response_message: completion,
+        prompt: expanded_prompt,
     })
 }
 
 #[tauri::command(async)]
 #[specta]
 pub async fn chat(
+    app_handle: tauri::AppHandle,
     api_keys: State<'_, ZammApiKeys>,
     database: State<'_, ZammDatabase>,
     args: ChatArgs,
 ) -> ZammResult<LightweightLlmCall> {
-    let http_client = reqwest::ClientBuilder::new().build()?;
-    let client_with_middleware =
-        reqwest_middleware::ClientBuilder::new(http_client).build();
-    chat_helper(&api_keys, &database, args, client_with_middleware).await
+    let http_client = service_http_client(
+        &app_handle,
+        &api_keys,
+        &args.provider,
+        args.key_profile.as_deref(),
+    )
+    .await?;
+    let llm_call = chat_helper(&api_keys, &database, args, http_client).await?;
+    spawn_embedding_backfill(&app_handle);
+    Ok(llm_call)
 }
 
 #[cfg(test)]
@@ -211,7 +409,7 @@ mod tests {
 
     use crate::check_sample;
     use crate::sample_call::SampleCall;
-    use crate::setup::api_keys::ApiKeys;
+    use crate::setup::api_keys::setup_api_keys;
     use crate::test_helpers::api_testing::standard_test_subdir;
     use crate::test_helpers::{
         SampleCallTestCase, SideEffectsHelpers, ZammResultReturn,
@@ -251,23 +449,33 @@ mod tests {
             args: &ChatRequest,
             side_effects: &mut SideEffectsHelpers,
         ) -> ZammResult<LightweightLlmCall> {
-            let network_helper = side_effects.network.as_ref().unwrap();
-            let api_keys = match network_helper.mode {
-                VCRMode::Record => ZammApiKeys(Mutex::new(ApiKeys {
-                    openai: env::var("OPENAI_API_KEY").ok(),
-                })),
-                VCRMode::Replay => ZammApiKeys(Mutex::new(ApiKeys {
-                    openai: Some("dummy".to_string()),
-                })),
+            let zamm_db = side_effects.db.as_ref().unwrap();
+            // pick up any key options from the starting database, just like the
+            // app does on startup
+            let mut api_keys =
+                setup_api_keys(&mut *zamm_db.0.lock().await, None, Vec::new());
+            let network_client = match side_effects.network.as_ref() {
+                Some(network_helper) => {
+                    api_keys.openai = match network_helper.mode {
+                        VCRMode::Record => env::var("OPENAI_API_KEY").ok(),
+                        VCRMode::Replay => Some("dummy".to_string()),
+                    };
+                    api_keys.azure = match network_helper.mode {
+                        VCRMode::Record => env::var("AZURE_OPENAI_API_KEY").ok(),
+                        VCRMode::Replay => Some("dummy".to_string()),
+                    };
+                    network_helper.network_client.clone()
+                }
+                // offline providers shouldn't need any keys or network access
+                None => {
+                    api_keys.openai = None;
+                    reqwest_middleware::ClientBuilder::new(reqwest::Client::new())
+                        .build()
+                }
             };
+            let api_keys = ZammApiKeys(Mutex::new(api_keys));
 
-            chat_helper(
-                &api_keys,
-                side_effects.db.as_ref().unwrap(),
-                args.args.clone(),
-                network_helper.network_client.clone(),
-            )
-            .await
+            chat_helper(&api_keys, zamm_db, args.args.clone(), network_client).await
         }
 
         fn output_replacements(
@@ -319,6 +527,24 @@ mod tests {
         "api/sample-calls/chat-start-conversation.yaml"
     );
 
+    check_sample!(
+        ChatTestCase,
+        test_openai_attribution,
+        "api/sample-calls/chat-openai-attribution.yaml"
+    );
+
+    check_sample!(
+        ChatTestCase,
+        test_key_profile,
+        "api/sample-calls/chat-key-profile.yaml"
+    );
+
+    check_sample!(
+        ChatTestCase,
+        test_start_conversation_azure,
+        "api/sample-calls/chat-azure.yaml"
+    );
+
     check_sample!(
         ChatTestCase,
         test_start_conversation_ollama,
@@ -349,6 +575,24 @@ mod tests {
         "api/sample-calls/chat-fork-conversation-rust.yaml"
     );
 
+    check_sample!(
+        ChatTestCase,
+        test_attach_file,
+        "api/sample-calls/chat-attach-file.yaml"
+    );
+
+    check_sample!(
+        ChatTestCase,
+        test_mock_echo,
+        "api/sample-calls/chat-mock-echo.yaml"
+    );
+
+    check_sample!(
+        ChatTestCase,
+        test_mock_script,
+        "api/sample-calls/chat-mock-script.yaml"
+    );
+
     check_sample!(
         ChatTestCase,
         test_edit_conversation,
@@ -0,0 +1,69 @@
+use crate::commands::errors::ZammResult;
+use crate::models::llm_calls::EntityId;
+use crate::schema::{llm_call_ratings, llm_calls};
+use crate::ZammDatabase;
+use anyhow::anyhow;
+use diesel::prelude::*;
+use specta::specta;
+use tauri::State;
+use uuid::Uuid;
+
+async fn clear_api_call_rating_helper(
+    zamm_db: &ZammDatabase,
+    api_call_id: &str,
+) -> ZammResult<()> {
+    let parsed_uuid = EntityId {
+        uuid: Uuid::parse_str(api_call_id)?,
+    };
+    let mut db = zamm_db.0.lock().await;
+    let conn = db.as_mut().ok_or(anyhow!("Failed to lock database"))?;
+    let llm_call_id = llm_calls::table
+        .find(&parsed_uuid)
+        .select(llm_calls::id)
+        .first::<EntityId>(conn)?;
+    diesel::delete(llm_call_ratings::table.find(&llm_call_id)).execute(conn)?;
+    Ok(())
+}
+
+#[tauri::command(async)]
+#[specta]
+pub async fn clear_api_call_rating(
+    database: State<'_, ZammDatabase>,
+    id: &str,
+) -> ZammResult<()> {
+    clear_api_call_rating_helper(&database, id).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::SideEffectsHelpers;
+    use crate::{check_sample, impl_result_test_case};
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    struct ClearApiCallRatingRequest {
+        id: String,
+    }
+
+    async fn make_request_helper(
+        args: &ClearApiCallRatingRequest,
+        side_effects: &mut SideEffectsHelpers,
+    ) -> ZammResult<()> {
+        clear_api_call_rating_helper(side_effects.db.as_ref().unwrap(), &args.id).await
+    }
+
+    impl_result_test_case!(
+        ClearApiCallRatingTestCase,
+        clear_api_call_rating,
+        true,
+        ClearApiCallRatingRequest,
+        ()
+    );
+
+    check_sample!(
+        ClearApiCallRatingTestCase,
+        test_clear_existing,
+        "./api/sample-calls/clear_api_call_rating-existing.yaml"
+    );
+}
@@ -0,0 +1,85 @@
+use crate::commands::errors::ZammResult;
+use crate::commands::llms::trash::{trash_llm_calls, DeletedApiCalls};
+use crate::models::llm_calls::EntityId;
+use crate::schema::llm_calls;
+use crate::ZammDatabase;
+use anyhow::anyhow;
+use chrono::naive::NaiveDateTime;
+use diesel::prelude::*;
+use specta::specta;
+use tauri::State;
+use uuid::Uuid;
+
+async fn delete_api_call_helper(
+    zamm_db: &ZammDatabase,
+    api_call_id: &str,
+    trash_id: EntityId,
+    deleted_at: NaiveDateTime,
+) -> ZammResult<DeletedApiCalls> {
+    let parsed_uuid = EntityId {
+        uuid: Uuid::parse_str(api_call_id)?,
+    };
+    let mut db = zamm_db.0.lock().await;
+    let conn = db.as_mut().ok_or(anyhow!("Failed to lock database"))?;
+    let existing_id = llm_calls::table
+        .find(&parsed_uuid)
+        .select(llm_calls::id)
+        .first::<EntityId>(conn)?;
+    trash_llm_calls(conn, &trash_id, &[existing_id], deleted_at)
+}
+
+#[tauri::command(async)]
+#[specta]
+pub async fn delete_api_call(
+    database: State<'_, ZammDatabase>,
+    id: &str,
+) -> ZammResult<DeletedApiCalls> {
+    let deleted_at = chrono::Utc::now().naive_utc();
+    delete_api_call_helper(&database, id, EntityId::new(), deleted_at).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::SideEffectsHelpers;
+    use crate::{check_sample, impl_result_test_case};
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    struct DeleteApiCallRequest {
+        id: String,
+    }
+
+    async fn make_request_helper(
+        args: &DeleteApiCallRequest,
+        side_effects: &mut SideEffectsHelpers,
+    ) -> ZammResult<DeletedApiCalls> {
+        delete_api_call_helper(
+            side_effects.db.as_ref().unwrap(),
+            &args.id,
+            EntityId::try_from("5e2fa1c8-9d3b-4b7e-8f64-0c1d2e3f4a5b").unwrap(),
+            "2024-07-01T12:00:00".parse().unwrap(),
+        )
+        .await
+    }
+
+    impl_result_test_case!(
+        DeleteApiCallTestCase,
+        delete_api_call,
+        true,
+        DeleteApiCallRequest,
+        DeletedApiCalls
+    );
+
+    check_sample!(
+        DeleteApiCallTestCase,
+        test_delete_canonical,
+        "./api/sample-calls/delete_api_call-canonical.yaml"
+    );
+
+    check_sample!(
+        DeleteApiCallTestCase,
+        test_delete_nonexistent,
+        "./api/sample-calls/delete_api_call-nonexistent.yaml"
+    );
+}
@@ -0,0 +1,372 @@
+use crate::commands::errors::ZammResult;
+use crate::commands::http_client::service_http_client;
+use crate::commands::preferences::get_preferences_file_contents;
+use crate::models::llm_calls::{
+    ChatMessage, EmbeddingKind, EntityId, LlmCallRow, Prompt,
+};
+use crate::schema::{llm_call_embeddings, llm_calls};
+use crate::setup::api_keys::Service;
+use crate::{ZammApiKeys, ZammDatabase};
+use anyhow::anyhow;
+use async_openai::config::OpenAIConfig;
+use async_openai::types::CreateEmbeddingRequestArgs;
+use async_trait::async_trait;
+use chrono::naive::NaiveDateTime;
+use diesel::dsl::{exists, not};
+use diesel::prelude::*;
+use ollama_rs::Ollama;
+use tauri::Manager;
+use tokio::sync::Mutex;
+
+const OPENAI_EMBEDDING_MODEL: &str = "text-embedding-3-small";
+const OLLAMA_EMBEDDING_MODEL: &str = "nomic-embed-text";
+// number of calls to embed before checking the database for more
+const BACKFILL_BATCH_SIZE: i64 = 20;
+
+/// Keeps backfills from running alongside each other and embedding the same calls
+/// twice.
+static BACKFILL_LOCK: Mutex<()> = Mutex::const_new(());
+
+#[derive(Insertable)]
+#[diesel(table_name = llm_call_embeddings)]
+struct NewLlmCallEmbedding<'a> {
+    llm_call_id: &'a EntityId,
+    model: &'a str,
+    kind: EmbeddingKind,
+    embedding: Vec<u8>,
+}
+
+#[async_trait]
+pub trait Embedder: Send + Sync {
+    /// Identifies the vector space, since vectors from different models can't be
+    /// compared against each other.
+    fn model(&self) -> &str;
+    async fn embed(&self, text: &str) -> ZammResult<Vec<f32>>;
+}
+
+pub struct OpenAiEmbedder {
+    api_key: String,
+    http_client: reqwest_middleware::ClientWithMiddleware,
+}
+
+#[async_trait]
+impl Embedder for OpenAiEmbedder {
+    fn model(&self) -> &str {
+        OPENAI_EMBEDDING_MODEL
+    }
+
+    async fn embed(&self, text: &str) -> ZammResult<Vec<f32>> {
+        let config = OpenAIConfig::new().with_api_key(&self.api_key);
+        let openai_client = async_openai::Client::with_config(config)
+            .with_http_client(self.http_client.clone());
+        let request = CreateEmbeddingRequestArgs::default()
+            .model(OPENAI_EMBEDDING_MODEL)
+            .input(text)
+            .build()?;
+        let response = openai_client.embeddings().create(request).await?;
+        let embedding = response
+            .data
+            .into_iter()
+            .next()
+            .ok_or(anyhow!("No embedding in OpenAI response"))?
+            .embedding;
+        Ok(embedding)
+    }
+}
+
+pub struct OllamaEmbedder {
+    http_client: reqwest_middleware::ClientWithMiddleware,
+}
+
+#[async_trait]
+impl Embedder for OllamaEmbedder {
+    fn model(&self) -> &str {
+        OLLAMA_EMBEDDING_MODEL
+    }
+
+    async fn embed(&self, text: &str) -> ZammResult<Vec<f32>> {
+        let ollama = Ollama::default().with_client(self.http_client.clone());
+        let response = ollama
+            .generate_embeddings(
+                OLLAMA_EMBEDDING_MODEL.to_string(),
+                text.to_string(),
+                None,
+            )
+            .await?;
+        Ok(response.embeddings.into_iter().map(|x| x as f32).collect())
+    }
+}
+
+/// Use OpenAI if the user has set up a key for it, and a local Ollama model
+/// otherwise.
+pub async fn default_embedder(
+    zamm_api_keys: &ZammApiKeys,
+    http_client: reqwest_middleware::ClientWithMiddleware,
+) -> Box<dyn Embedder> {
+    let api_keys = zamm_api_keys.0.lock().await;
+    match &api_keys.openai {
+        Some(api_key) => Box::new(OpenAiEmbedder {
+            api_key: api_key.clone(),
+            http_client,
+        }),
+        None => Box::new(OllamaEmbedder { http_client }),
+    }
+}
+
+/// What the model saw and what it said during the call, each embedded separately.
+/// Either one is left out if there's no text to embed.
+fn embedding_texts(call: &LlmCallRow) -> Vec<(EmbeddingKind, String)> {
+    let prompt_text = match &call.prompt {
+        Prompt::Chat(chat_prompt) => chat_prompt
+            .iter()
+            .map(ChatMessage::text)
+            .collect::<Vec<&str>>()
+            .join("\n\n"),
+        Prompt::Unknown => String::new(),
+    };
+    let completion_text = call.completion.text().to_string();
+    [
+        (EmbeddingKind::Prompt, prompt_text),
+        (EmbeddingKind::Completion, completion_text),
+    ]
+    .into_iter()
+    .filter(|(_, text)| !text.trim().is_empty())
+    .collect()
+}
+
+async fn embed_call(
+    embedder: &dyn Embedder,
+    call: &LlmCallRow,
+) -> ZammResult<Vec<(EmbeddingKind, Vec<f32>)>> {
+    let mut embeddings = Vec::new();
+    for (kind, text) in embedding_texts(call) {
+        embeddings.push((kind, embedder.embed(&text).await?));
+    }
+    Ok(embeddings)
+}
+
+fn encode_embedding(embedding: &[f32]) -> Vec<u8> {
+    embedding.iter().flat_map(|x| x.to_le_bytes()).collect()
+}
+
+pub fn decode_embedding(bytes: &[u8]) -> Vec<f32> {
+    bytes
+        .chunks_exact(4)
+        .map(|chunk| f32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]))
+        .collect()
+}
+
+pub fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norm_a = a.iter().map(|x| x * x).sum::<f32>().sqrt();
+    let norm_b = b.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm_a == 0.0 || norm_b == 0.0 {
+        return 0.0;
+    }
+    dot / (norm_a * norm_b)
+}
+
+/// Embed every call that doesn't have any embeddings from this model yet. The
+/// database is only locked while reading and writing, not while waiting on the
+/// embedding model. Calls that can't be embedded are skipped until the next
+/// backfill, so that one bad call doesn't hold up the rest. If none of the calls
+/// in a batch can be embedded, the embedding model is probably unreachable, so the
+/// backfill stops there instead of trying every call in the history.
+pub async fn backfill_embeddings(
+    zamm_db: &ZammDatabase,
+    embedder: &dyn Embedder,
+) -> ZammResult<usize> {
+    let mut num_embedded = 0;
+    // the last call that was looked at, so that skipped calls aren't fetched again
+    let mut last_seen: Option<(NaiveDateTime, EntityId)> = None;
+    loop {
+        let batch = {
+            let mut db = zamm_db.0.lock().await;
+            let conn = db.as_mut().ok_or(anyhow!("Failed to lock database"))?;
+            let mut query = llm_calls::table
+                .filter(not(exists(
+                    llm_call_embeddings::table
+                        .filter(llm_call_embeddings::llm_call_id.eq(llm_calls::id))
+                        .filter(llm_call_embeddings::model.eq(embedder.model())),
+                )))
+                .into_boxed();
+            if let Some((timestamp, id)) = &last_seen {
+                query =
+                    query.filter(llm_calls::timestamp.gt(timestamp).or(
+                        llm_calls::timestamp.eq(timestamp).and(llm_calls::id.gt(id)),
+                    ));
+            }
+            query
+                .order((llm_calls::timestamp.asc(), llm_calls::id.asc()))
+                .limit(BACKFILL_BATCH_SIZE)
+                .load::<LlmCallRow>(conn)?
+        };
+        let Some(last_call) = batch.last() else {
+            return Ok(num_embedded);
+        };
+        last_seen = Some((last_call.timestamp, last_call.id.clone()));
+
+        let mut embedded_calls = Vec::new();
+        let mut last_error = None;
+        for call in &batch {
+            match embed_call(embedder, call).await {
+                Ok(embeddings) => embedded_calls.push((call, embeddings)),
+                Err(e) => {
+                    eprintln!("Couldn't embed API call {}: {e}", call.id.uuid);
+                    last_error = Some(e);
+                }
+            }
+        }
+        if embedded_calls.is_empty() {
+            if let Some(e) = last_error {
+                return Err(e);
+            }
+        }
+
+        let mut db = zamm_db.0.lock().await;
+        let conn = db.as_mut().ok_or(anyhow!("Failed to lock database"))?;
+        for (call, embeddings) in &embedded_calls {
+            for (kind, embedding) in embeddings {
+                // the call might have been embedded in the meantime by a
+                // concurrent backfill
+                diesel::insert_or_ignore_into(llm_call_embeddings::table)
+                    .values(NewLlmCallEmbedding {
+                        llm_call_id: &call.id,
+                        model: embedder.model(),
+                        kind: *kind,
+                        embedding: encode_embedding(embedding),
+                    })
+                    .execute(conn)?;
+            }
+        }
+        num_embedded += embedded_calls.len();
+    }
+}
+
+async fn backfill_app_embeddings(app_handle: &tauri::AppHandle) -> ZammResult<usize> {
+    let zamm_api_keys = app_handle.state::<ZammApiKeys>();
+    let zamm_db = app_handle.state::<ZammDatabase>();
+    let http_client =
+        service_http_client(app_handle, &zamm_api_keys, &Service::OpenAI, None).await?;
+    let embedder = default_embedder(&zamm_api_keys, http_client).await;
+    let _backfill_guard = BACKFILL_LOCK.lock().await;
+    backfill_embeddings(&zamm_db, embedder.as_ref()).await
+}
+
+/// Embeds any calls that haven't been embedded yet, without waiting for it to
+/// finish. Gets called at startup and whenever new calls are saved, so that
+/// semantic search keeps up with the history. Does nothing if the user has turned
+/// background embeddings off.
+pub fn spawn_embedding_backfill(app_handle: &tauri::AppHandle) {
+    let config_dir = app_handle.path().app_config_dir().ok();
+    match get_preferences_file_contents(&config_dir) {
+        Ok(prefs) if prefs.background_embeddings.unwrap_or(true) => {}
+        Ok(_) => return,
+        Err(e) => {
+            eprintln!("Couldn't check whether to embed API calls: {e}");
+            return;
+        }
+    }
+
+    let app_handle = app_handle.clone();
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = backfill_app_embeddings(&app_handle).await {
+            eprintln!("Couldn't backfill embeddings: {e}");
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::database::setup_zamm_db;
+    use crate::test_helpers::sqlite::load_sqlite_database;
+    use std::path::PathBuf;
+
+    /// Embeds text by its length, except for the one completion that mentions a
+    /// Rust program.
+    struct FlakyEmbedder;
+
+    #[async_trait]
+    impl Embedder for FlakyEmbedder {
+        fn model(&self) -> &str {
+            "test-flaky"
+        }
+
+        async fn embed(&self, text: &str) -> ZammResult<Vec<f32>> {
+            if text.contains("Rust program") {
+                return Err(anyhow!("Embedding service unavailable").into());
+            }
+            Ok(vec![text.len() as f32])
+        }
+    }
+
+    struct UnreachableEmbedder;
+
+    #[async_trait]
+    impl Embedder for UnreachableEmbedder {
+        fn model(&self) -> &str {
+            "test-unreachable"
+        }
+
+        async fn embed(&self, _text: &str) -> ZammResult<Vec<f32>> {
+            Err(anyhow!("Connection refused").into())
+        }
+    }
+
+    async fn embedded_call_ids(
+        zamm_db: &ZammDatabase,
+    ) -> Vec<(EntityId, EmbeddingKind)> {
+        let mut db = zamm_db.0.lock().await;
+        llm_call_embeddings::table
+            .select((llm_call_embeddings::llm_call_id, llm_call_embeddings::kind))
+            .order((llm_call_embeddings::llm_call_id, llm_call_embeddings::kind))
+            .load(db.as_mut().unwrap())
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_backfill_skips_failed_calls() {
+        let zamm_db = setup_zamm_db(None);
+        load_sqlite_database(
+            &zamm_db,
+            &PathBuf::from(
+                "./api/sample-database-writes/conversation-edited-2/dump.sql",
+            ),
+        )
+        .await;
+
+        assert_eq!(
+            backfill_embeddings(&zamm_db, &FlakyEmbedder).await.unwrap(),
+            5
+        );
+        let embedded = embedded_call_ids(&zamm_db).await;
+        assert_eq!(embedded.len(), 10);
+        let failed_id =
+            EntityId::try_from("63b5c02e-b864-4efe-a286-fbef48b152ef").unwrap();
+        assert!(embedded.iter().all(|(id, _)| *id != failed_id));
+
+        // the failed call gets tried again, without getting stuck on it
+        assert_eq!(
+            backfill_embeddings(&zamm_db, &FlakyEmbedder).await.unwrap(),
+            0
+        );
+        assert_eq!(embedded_call_ids(&zamm_db).await, embedded);
+    }
+
+    #[tokio::test]
+    async fn test_backfill_stops_when_embedder_unreachable() {
+        let zamm_db = setup_zamm_db(None);
+        load_sqlite_database(
+            &zamm_db,
+            &PathBuf::from(
+                "./api/sample-database-writes/conversation-edited-2/dump.sql",
+            ),
+        )
+        .await;
+
+        let result = backfill_embeddings(&zamm_db, &UnreachableEmbedder).await;
+        assert_eq!(result.unwrap_err().to_string(), "Connection refused");
+        assert!(embedded_call_ids(&zamm_db).await.is_empty());
+    }
+}
@@ -0,0 +1,301 @@
+use crate::commands::errors::ZammResult;
+use crate::models::llm_calls::{ChatMessage, EntityId, LlmCallRow, Prompt};
+use crate::schema::{llm_call_follow_ups, llm_calls};
+use crate::setup::api_keys::Service;
+use crate::ZammDatabase;
+use anyhow::anyhow;
+use diesel::prelude::*;
+use path_absolutize::Absolutize;
+use serde::{Deserialize, Serialize};
+use specta::specta;
+use std::fs;
+use std::path::PathBuf;
+use tauri::State;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, specta::Type)]
+pub enum TranscriptFormat {
+    Markdown,
+    Html,
+}
+
+/// A message in the conversation, along with the call that produced it if it was
+/// written by the AI.
+struct TranscriptEntry<'a> {
+    message: &'a ChatMessage,
+    call: Option<&'a LlmCallRow>,
+}
+
+fn role_name(message: &ChatMessage) -> &'static str {
+    match message {
+        ChatMessage::System { .. } => "System",
+        ChatMessage::Human { .. } => "Human",
+        ChatMessage::AI { .. } => "AI",
+    }
+}
+
+fn call_metadata(call: &LlmCallRow) -> String {
+    let provider = match &call.provider {
+        Service::OpenAI => "OpenAI",
+        Service::Azure => "Azure OpenAI",
+        Service::Ollama => "Ollama",
+        Service::Mock => "Mock",
+        Service::Unknown(name) => name,
+    };
+    format!(
+        "{} via {} at {} UTC",
+        call.llm,
+        provider,
+        call.timestamp.format("%Y-%m-%d %H:%M:%S")
+    )
+}
+
+/// Load the calls leading up to and including the given one, oldest first.
+fn load_follow_up_chain(
+    conn: &mut SqliteConnection,
+    id: &EntityId,
+) -> ZammResult<Vec<LlmCallRow>> {
+    let mut chain = vec![llm_calls::table.find(id).first::<LlmCallRow>(conn)?];
+    while let Some(previous_call_id) = llm_call_follow_ups::table
+        .select(llm_call_follow_ups::previous_call_id)
+        .filter(llm_call_follow_ups::next_call_id.eq(&chain.last().unwrap().id))
+        .first::<EntityId>(conn)
+        .optional()?
+    {
+        chain.push(
+            llm_calls::table
+                .find(&previous_call_id)
+                .first::<LlmCallRow>(conn)?,
+        );
+    }
+    chain.reverse();
+    Ok(chain)
+}
+
+/// Flatten the chain into a single list of messages. Every call's prompt repeats
+/// the conversation so far, so only the messages that are new to it get added.
+fn transcript_entries(chain: &[LlmCallRow]) -> ZammResult<Vec<TranscriptEntry<'_>>> {
+    let mut entries = Vec::new();
+    for call in chain {
+        let prompt = match &call.prompt {
+            Prompt::Chat(chat_prompt) => chat_prompt,
+            Prompt::Unknown => {
+                return Err(anyhow!("Can't export unknown prompt type").into());
+            }
+        };
+        entries.extend(prompt.iter().skip(entries.len()).map(|message| {
+            TranscriptEntry {
+                message,
+                call: None,
+            }
+        }));
+        entries.push(TranscriptEntry {
+            message: &call.completion,
+            call: Some(call),
+        });
+    }
+    Ok(entries)
+}
+
+fn render_markdown(entries: &[TranscriptEntry]) -> String {
+    let mut markdown = String::from("# Conversation\n");
+    for entry in entries {
+        markdown.push_str(&format!("\n## {}\n\n", role_name(entry.message)));
+        if let Some(call) = entry.call {
+            markdown.push_str(&format!("_{}_\n\n", call_metadata(call)));
+        }
+        markdown.push_str(entry.message.text());
+        markdown.push('\n');
+    }
+    markdown
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
+
+/// Turn message text into paragraphs, keeping fenced code blocks intact.
+fn render_html_text(text: &str) -> String {
+    let mut html = String::new();
+    let mut paragraph: Vec<String> = Vec::new();
+    let mut code_block: Option<Vec<String>> = None;
+    let flush_paragraph = |html: &mut String, paragraph: &mut Vec<String>| {
+        if !paragraph.is_empty() {
+            html.push_str(&format!("<p>{}</p>\n", paragraph.join("<br>\n")));
+            paragraph.clear();
+        }
+    };
+
+    for line in text.lines() {
+        match (&mut code_block, line.trim_start().strip_prefix("```")) {
+            (Some(code_lines), Some(_)) => {
+                html.push_str(&format!("{}</code></pre>\n", code_lines.join("\n")));
+                code_block = None;
+            }
+            (Some(code_lines), None) => code_lines.push(escape_html(line)),
+            (None, Some(language)) => {
+                flush_paragraph(&mut html, &mut paragraph);
+                let language = language.trim();
+                if language.is_empty() {
+                    html.push_str("<pre><code>");
+                } else {
+                    html.push_str(&format!(
+                        "<pre><code class=\"language-{}\">",
+                        escape_html(language)
+                    ));
+                }
+                code_block = Some(Vec::new());
+            }
+            (None, None) if line.trim().is_empty() => {
+                flush_paragraph(&mut html, &mut paragraph);
+            }
+            (None, None) => paragraph.push(escape_html(line)),
+        }
+    }
+    // an unterminated code block still gets closed off
+    if let Some(code_lines) = code_block {
+        html.push_str(&format!("{}</code></pre>\n", code_lines.join("\n")));
+    }
+    flush_paragraph(&mut html, &mut paragraph);
+    html
+}
+
+fn render_html(entries: &[TranscriptEntry]) -> String {
+    let mut html = String::from(concat!(
+        "<!DOCTYPE html>\n",
+        "<html>\n",
+        "<head>\n",
+        "<meta charset=\"utf-8\">\n",
+        "<title>Conversation</title>\n",
+        "<style>\n",
+        "body { font-family: sans-serif; max-width: 50rem; margin: 2rem auto; }\n",
+        ".metadata { color: #666; font-style: italic; }\n",
+        "pre { background: #f4f4f4; padding: 0.75rem; overflow-x: auto; }\n",
+        "</style>\n",
+        "</head>\n",
+        "<body>\n",
+        "<h1>Conversation</h1>\n",
+    ));
+    for entry in entries {
+        let role = role_name(entry.message);
+        html.push_str(&format!(
+            "<section class=\"message {}\">\n<h2>{}</h2>\n",
+            role.to_lowercase(),
+            role
+        ));
+        if let Some(call) = entry.call {
+            html.push_str(&format!(
+                "<p class=\"metadata\">{}</p>\n",
+                escape_html(&call_metadata(call))
+            ));
+        }
+        html.push_str(&render_html_text(entry.message.text()));
+        html.push_str("</section>\n");
+    }
+    html.push_str("</body>\n</html>\n");
+    html
+}
+
+async fn export_conversation_helper(
+    zamm_db: &ZammDatabase,
+    api_call_id: &str,
+    path: &str,
+    format: TranscriptFormat,
+) -> ZammResult<()> {
+    let parsed_uuid = EntityId {
+        uuid: Uuid::parse_str(api_call_id)?,
+    };
+    let file_path_buf = PathBuf::from(path);
+    let file_path_abs = file_path_buf.absolutize()?;
+    let mut db = zamm_db.0.lock().await;
+    let conn = db.as_mut().ok_or(anyhow!("Failed to lock database"))?;
+
+    let chain = load_follow_up_chain(conn, &parsed_uuid)?;
+    let entries = transcript_entries(&chain)?;
+    let rendered = match format {
+        TranscriptFormat::Markdown => render_markdown(&entries),
+        TranscriptFormat::Html => render_html(&entries),
+    };
+
+    if let Some(parent) = file_path_abs.parent() {
+        fs::create_dir_all(parent).map_err(|e| {
+            anyhow!(
+                "Error creating parent directory {}: {}",
+                parent.display(),
+                e
+            )
+        })?;
+    }
+    fs::write(&file_path_abs, rendered).map_err(|e| {
+        anyhow!("Error exporting to {}: {}", &file_path_abs.display(), e)
+    })?;
+    Ok(())
+}
+
+#[tauri::command(async)]
+#[specta]
+pub async fn export_conversation(
+    database: State<'_, ZammDatabase>,
+    id: &str,
+    path: String,
+    format: TranscriptFormat,
+) -> ZammResult<()> {
+    export_conversation_helper(&database, id, &path, format).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::SideEffectsHelpers;
+    use crate::{check_sample, impl_result_test_case};
+
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    struct ExportConversationRequest {
+        id: String,
+        path: String,
+        format: TranscriptFormat,
+    }
+
+    async fn make_request_helper(
+        args: &ExportConversationRequest,
+        side_effects: &mut SideEffectsHelpers,
+    ) -> ZammResult<()> {
+        export_conversation_helper(
+            side_effects.db.as_ref().unwrap(),
+            &args.id,
+            &args.path,
+            args.format,
+        )
+        .await
+    }
+
+    impl_result_test_case!(
+        ExportConversationTestCase,
+        export_conversation,
+        true,
+        ExportConversationRequest,
+        ()
+    );
+
+    check_sample!(
+        ExportConversationTestCase,
+        test_markdown,
+        "./api/sample-calls/export_conversation-markdown.yaml"
+    );
+
+    check_sample!(
+        ExportConversationTestCase,
+        test_html,
+        "./api/sample-calls/export_conversation-html.yaml"
+    );
+
+    check_sample!(
+        ExportConversationTestCase,
+        test_unknown_prompt,
+        "./api/sample-calls/export_conversation-unknown-prompt.yaml"
+    );
+}
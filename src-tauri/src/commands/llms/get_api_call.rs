@@ -1,6 +1,10 @@
 use crate::commands::errors::ZammResult;
-use crate::models::llm_calls::{ChatMessage, EntityId, LlmCall, LlmCallLeftJoinResult};
-use crate::schema::llm_calls;
+use crate::models::llm_calls::{
+    ChatMessage, EntityId, LlmCall, LlmCallAttribution, LlmCallLeftJoinResult,
+};
+use crate::schema::{
+    llm_call_attributions, llm_call_deployments, llm_call_key_profiles, llm_calls,
+};
 use crate::views::{llm_call_named_follow_ups, llm_call_named_variants};
 use crate::ZammDatabase;
 use anyhow::anyhow;
@@ -45,6 +49,21 @@ async fn get_api_call_helper(
         ))
         .filter(llm_call_named_follow_ups::previous_call_id.eq(&parsed_uuid))
         .load::<(EntityId, ChatMessage)>(conn)?;
+    let attribution = llm_call_attributions::table
+        .select(LlmCallAttribution::as_select())
+        .filter(llm_call_attributions::llm_call_id.eq(&parsed_uuid))
+        .first::<LlmCallAttribution>(conn)
+        .optional()?;
+    let deployment = llm_call_deployments::table
+        .select(llm_call_deployments::deployment)
+        .filter(llm_call_deployments::llm_call_id.eq(&parsed_uuid))
+        .first::<String>(conn)
+        .optional()?;
+    let key_profile = llm_call_key_profiles::table
+        .select(llm_call_key_profiles::profile)
+        .filter(llm_call_key_profiles::llm_call_id.eq(&parsed_uuid))
+        .first::<String>(conn)
+        .optional()?;
     let canonical_id = left_join_result.3.clone().unwrap_or(parsed_uuid);
     let variants_result = llm_call_named_variants::table
         .select((
@@ -53,7 +72,12 @@ async fn get_api_call_helper(
         ))
         .filter(llm_call_named_variants::canonical_id.eq(canonical_id))
         .load::<(EntityId, ChatMessage)>(conn)?;
-    Ok((left_join_result, next_calls_result, variants_result).into())
+    let mut llm_call: LlmCall =
+        (left_join_result, next_calls_result, variants_result).into();
+    llm_call.attribution = attribution;
+    llm_call.llm.deployment = deployment;
+    llm_call.llm.key_profile = key_profile;
+    Ok(llm_call)
 }
 
 #[tauri::command(async)]
@@ -126,4 +150,28 @@ mod tests {
         test_ollama,
         "./api/sample-calls/get_api_call-ollama.yaml"
     );
+
+    check_sample!(
+        GetApiCallTestCase,
+        test_attachment,
+        "./api/sample-calls/get_api_call-attachment.yaml"
+    );
+
+    check_sample!(
+        GetApiCallTestCase,
+        test_attribution,
+        "./api/sample-calls/get_api_call-attribution.yaml"
+    );
+
+    check_sample!(
+        GetApiCallTestCase,
+        test_azure_deployment,
+        "./api/sample-calls/get_api_call-azure.yaml"
+    );
+
+    check_sample!(
+        GetApiCallTestCase,
+        test_key_profile,
+        "./api/sample-calls/get_api_call-key-profile.yaml"
+    );
 }
@@ -1,22 +1,150 @@
 use crate::commands::errors::ZammResult;
 use crate::commands::PAGE_SIZE;
-use crate::models::llm_calls::{LightweightLlmCall, LlmCallRow};
-use crate::schema::llm_calls;
+use crate::models::llm_calls::{LightweightLlmCall, LlmCallRow, Rating};
+use crate::schema::{
+    llm_call_follow_ups, llm_call_ratings, llm_call_variants, llm_calls,
+};
+use crate::setup::api_keys::Service;
 use crate::ZammDatabase;
 use anyhow::anyhow;
+use chrono::naive::NaiveDateTime;
+use diesel::dsl::{exists, not};
 use diesel::prelude::*;
+use diesel::sqlite::Sqlite;
 use diesel::RunQueryDsl;
+use serde::{Deserialize, Serialize};
 use specta::specta;
 use tauri::State;
 
+#[derive(
+    Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize, specta::Type,
+)]
+pub enum ApiCallSortKey {
+    #[default]
+    Timestamp,
+    TotalTokens,
+}
+
+#[derive(
+    Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize, specta::Type,
+)]
+pub enum SortOrder {
+    Ascending,
+    #[default]
+    Descending,
+}
+
+/// Criteria for narrowing down and ordering the list of API calls. Every field is
+/// optional, and the default filter lists all calls from newest to oldest.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize, specta::Type)]
+pub struct ApiCallFilter {
+    pub provider: Option<Service>,
+    /// The model that actually answered the call, as opposed to the one requested
+    pub model: Option<String>,
+    /// Inclusive lower bound on the call timestamp
+    pub start_time: Option<NaiveDateTime>,
+    /// Exclusive upper bound on the call timestamp
+    pub end_time: Option<NaiveDateTime>,
+    pub min_total_tokens: Option<i32>,
+    /// Only list calls that do not continue an earlier conversation
+    pub conversation_roots_only: Option<bool>,
+    /// Only list calls that do (or do not) belong to a set of variants
+    pub has_variants: Option<bool>,
+    /// Only list calls that have (or have not) been rated yet
+    pub rated: Option<bool>,
+    pub rating: Option<Rating>,
+    pub sort_by: Option<ApiCallSortKey>,
+    pub sort_order: Option<SortOrder>,
+}
+
+/// Query for the calls that match the filter, without any ordering applied yet.
+pub fn filtered_api_calls(filter: &ApiCallFilter) -> llm_calls::BoxedQuery<'_, Sqlite> {
+    let mut query = llm_calls::table.into_boxed();
+    if let Some(provider) = &filter.provider {
+        query = query.filter(llm_calls::provider.eq(provider));
+    }
+    if let Some(model) = &filter.model {
+        query = query.filter(llm_calls::llm.eq(model));
+    }
+    if let Some(start_time) = &filter.start_time {
+        query = query.filter(llm_calls::timestamp.ge(start_time));
+    }
+    if let Some(end_time) = &filter.end_time {
+        query = query.filter(llm_calls::timestamp.lt(end_time));
+    }
+    if let Some(min_total_tokens) = filter.min_total_tokens {
+        query = query.filter(llm_calls::total_tokens.ge(min_total_tokens));
+    }
+    if filter.conversation_roots_only == Some(true) {
+        query = query.filter(not(exists(
+            llm_call_follow_ups::table
+                .filter(llm_call_follow_ups::next_call_id.eq(llm_calls::id)),
+        )));
+    }
+    if let Some(has_variants) = filter.has_variants {
+        let variant_exists = exists(
+            llm_call_variants::table.filter(
+                llm_call_variants::canonical_id
+                    .eq(llm_calls::id)
+                    .or(llm_call_variants::variant_id.eq(llm_calls::id)),
+            ),
+        );
+        query = if has_variants {
+            query.filter(variant_exists)
+        } else {
+            query.filter(not(variant_exists))
+        };
+    }
+    if let Some(rated) = filter.rated {
+        let rating_exists = exists(
+            llm_call_ratings::table
+                .filter(llm_call_ratings::llm_call_id.eq(llm_calls::id)),
+        );
+        query = if rated {
+            query.filter(rating_exists)
+        } else {
+            query.filter(not(rating_exists))
+        };
+    }
+    if let Some(rating) = &filter.rating {
+        query = query.filter(exists(
+            llm_call_ratings::table
+                .filter(llm_call_ratings::llm_call_id.eq(llm_calls::id))
+                .filter(llm_call_ratings::rating.eq(rating)),
+        ));
+    }
+    query
+}
+
 async fn get_api_calls_helper(
     zamm_db: &ZammDatabase,
     offset: i32,
+    filter: Option<ApiCallFilter>,
 ) -> ZammResult<Vec<LightweightLlmCall>> {
+    let filter = filter.unwrap_or_default();
     let mut db = zamm_db.0.lock().await;
     let conn = db.as_mut().ok_or(anyhow!("Failed to lock database"))?;
-    let result: Vec<LlmCallRow> = llm_calls::table
-        .order(llm_calls::timestamp.desc())
+
+    let query = filtered_api_calls(&filter);
+    let query = match (
+        filter.sort_by.unwrap_or_default(),
+        filter.sort_order.unwrap_or_default(),
+    ) {
+        (ApiCallSortKey::Timestamp, SortOrder::Ascending) => {
+            query.order(llm_calls::timestamp.asc())
+        }
+        (ApiCallSortKey::Timestamp, SortOrder::Descending) => {
+            query.order(llm_calls::timestamp.desc())
+        }
+        (ApiCallSortKey::TotalTokens, SortOrder::Ascending) => query
+            .order(llm_calls::total_tokens.asc())
+            .then_order_by(llm_calls::timestamp.asc()),
+        (ApiCallSortKey::TotalTokens, SortOrder::Descending) => query
+            .order(llm_calls::total_tokens.desc())
+            .then_order_by(llm_calls::timestamp.desc()),
+    };
+
+    let result: Vec<LlmCallRow> = query
         .offset(offset as i64)
         .limit(PAGE_SIZE)
         .load::<LlmCallRow>(conn)?;
@@ -30,8 +158,9 @@ async fn get_api_calls_helper(
 pub async fn get_api_calls(
     database: State<'_, ZammDatabase>,
     offset: i32,
+    filter: Option<ApiCallFilter>,
 ) -> ZammResult<Vec<LightweightLlmCall>> {
-    get_api_calls_helper(&database, offset).await
+    get_api_calls_helper(&database, offset, filter).await
 }
 
 #[cfg(test)]
@@ -39,18 +168,23 @@ mod tests {
     use super::*;
     use crate::test_helpers::SideEffectsHelpers;
     use crate::{check_sample, impl_result_test_case};
-    use serde::{Deserialize, Serialize};
 
     #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
     struct GetApiCallsRequest {
         offset: i32,
+        filter: Option<ApiCallFilter>,
     }
 
     async fn make_request_helper(
         args: &GetApiCallsRequest,
         side_effects: &mut SideEffectsHelpers,
     ) -> ZammResult<Vec<LightweightLlmCall>> {
-        get_api_calls_helper(side_effects.db.as_ref().unwrap(), args.offset).await
+        get_api_calls_helper(
+            side_effects.db.as_ref().unwrap(),
+            args.offset,
+            args.filter.clone(),
+        )
+        .await
     }
 
     impl_result_test_case!(
@@ -96,4 +230,34 @@ mod tests {
         test_unknown_provider_promptr,
         "./api/sample-calls/get_api_calls-unknown-provider-prompt.yaml"
     );
+
+    check_sample!(
+        GetApiCallsTestCase,
+        test_filter_by_provider_and_date,
+        "./api/sample-calls/get_api_calls-filter-provider-date.yaml"
+    );
+
+    check_sample!(
+        GetApiCallsTestCase,
+        test_conversation_roots_by_tokens,
+        "./api/sample-calls/get_api_calls-filter-roots-by-tokens.yaml"
+    );
+
+    check_sample!(
+        GetApiCallsTestCase,
+        test_variants_with_min_tokens,
+        "./api/sample-calls/get_api_calls-filter-variants.yaml"
+    );
+
+    check_sample!(
+        GetApiCallsTestCase,
+        test_bad_rating,
+        "./api/sample-calls/get_api_calls-filter-rating.yaml"
+    );
+
+    check_sample!(
+        GetApiCallsTestCase,
+        test_unrated,
+        "./api/sample-calls/get_api_calls-filter-unrated.yaml"
+    );
 }
@@ -0,0 +1,136 @@
+use crate::commands::errors::ZammResult;
+use crate::commands::llms::get_api_calls::{
+    filtered_api_calls, ApiCallFilter, ApiCallSortKey, SortOrder,
+};
+use crate::commands::pagination::{Page, PageCursor};
+use crate::commands::PAGE_SIZE;
+use crate::models::llm_calls::{LightweightLlmCall, LlmCallRow};
+use crate::schema::llm_calls;
+use crate::ZammDatabase;
+use anyhow::anyhow;
+use diesel::prelude::*;
+use diesel::RunQueryDsl;
+use specta::specta;
+use tauri::State;
+
+async fn get_api_calls_page_helper(
+    zamm_db: &ZammDatabase,
+    cursor: Option<PageCursor>,
+    filter: Option<ApiCallFilter>,
+) -> ZammResult<Page<LightweightLlmCall>> {
+    let filter = filter.unwrap_or_default();
+    if filter.sort_by.unwrap_or_default() != ApiCallSortKey::Timestamp {
+        return Err(
+            anyhow!("Cursor pagination only supports sorting by timestamp").into(),
+        );
+    }
+    let mut db = zamm_db.0.lock().await;
+    let conn = db.as_mut().ok_or(anyhow!("Failed to lock database"))?;
+
+    let mut query = filtered_api_calls(&filter);
+    query = match filter.sort_order.unwrap_or_default() {
+        SortOrder::Ascending => {
+            if let Some(cursor) = &cursor {
+                query = query.filter(
+                    llm_calls::timestamp
+                        .gt(&cursor.timestamp)
+                        .or(llm_calls::timestamp
+                            .eq(&cursor.timestamp)
+                            .and(llm_calls::id.gt(&cursor.id))),
+                );
+            }
+            query
+                .order(llm_calls::timestamp.asc())
+                .then_order_by(llm_calls::id.asc())
+        }
+        SortOrder::Descending => {
+            if let Some(cursor) = &cursor {
+                query = query.filter(
+                    llm_calls::timestamp
+                        .lt(&cursor.timestamp)
+                        .or(llm_calls::timestamp
+                            .eq(&cursor.timestamp)
+                            .and(llm_calls::id.lt(&cursor.id))),
+                );
+            }
+            query
+                .order(llm_calls::timestamp.desc())
+                .then_order_by(llm_calls::id.desc())
+        }
+    };
+
+    let result: Vec<LlmCallRow> =
+        query.limit(PAGE_SIZE + 1).load::<LlmCallRow>(conn)?;
+    Ok(Page::from_rows(result, |row| PageCursor {
+        timestamp: row.timestamp,
+        id: row.id.clone(),
+    }))
+}
+
+#[tauri::command(async)]
+#[specta]
+pub async fn get_api_calls_page(
+    database: State<'_, ZammDatabase>,
+    cursor: Option<PageCursor>,
+    filter: Option<ApiCallFilter>,
+) -> ZammResult<Page<LightweightLlmCall>> {
+    get_api_calls_page_helper(&database, cursor, filter).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::SideEffectsHelpers;
+    use crate::{check_sample, impl_result_test_case};
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    struct GetApiCallsPageRequest {
+        cursor: Option<PageCursor>,
+        filter: Option<ApiCallFilter>,
+    }
+
+    async fn make_request_helper(
+        args: &GetApiCallsPageRequest,
+        side_effects: &mut SideEffectsHelpers,
+    ) -> ZammResult<Page<LightweightLlmCall>> {
+        get_api_calls_page_helper(
+            side_effects.db.as_ref().unwrap(),
+            args.cursor.clone(),
+            args.filter.clone(),
+        )
+        .await
+    }
+
+    impl_result_test_case!(
+        GetApiCallsPageTestCase,
+        get_api_calls_page,
+        true,
+        GetApiCallsPageRequest,
+        Page<LightweightLlmCall>
+    );
+
+    check_sample!(
+        GetApiCallsPageTestCase,
+        test_first_page,
+        "./api/sample-calls/get_api_calls_page-first.yaml"
+    );
+
+    check_sample!(
+        GetApiCallsPageTestCase,
+        test_last_page,
+        "./api/sample-calls/get_api_calls_page-last.yaml"
+    );
+
+    check_sample!(
+        GetApiCallsPageTestCase,
+        test_ascending_after_cursor,
+        "./api/sample-calls/get_api_calls_page-ascending.yaml"
+    );
+
+    check_sample!(
+        GetApiCallsPageTestCase,
+        test_unsupported_sort,
+        "./api/sample-calls/get_api_calls_page-unsupported-sort.yaml"
+    );
+}
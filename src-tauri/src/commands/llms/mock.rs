@@ -0,0 +1,92 @@
+use crate::commands::errors::ZammResult;
+use crate::models::llm_calls::{ChatMessage, TokenMetadata};
+use anyhow::anyhow;
+use serde::Deserialize;
+use std::fs;
+use std::time::Duration;
+
+/// Model name that makes the mock provider repeat the last human message.
+const ECHO_MODEL: &str = "echo";
+/// Prefix for model names that point the mock provider at a script file.
+const SCRIPT_MODEL_PREFIX: &str = "script:";
+
+/// Replies that the mock provider gives out, read from a YAML file.
+#[derive(Debug, Deserialize)]
+struct MockScript {
+    /// Simulated response time of the provider
+    #[serde(default)]
+    latency_ms: u64,
+    replies: Vec<MockReply>,
+}
+
+#[derive(Debug, Deserialize)]
+struct MockReply {
+    /// Only use this reply if the last human message contains this text. Replies
+    /// without it match everything.
+    when: Option<String>,
+    text: String,
+}
+
+/// Rough stand-in for a real tokenizer, so that mock calls still show up with
+/// plausible token counts.
+fn count_tokens(text: &str) -> i32 {
+    text.split_whitespace().count() as i32
+}
+
+/// Returns the text of the first matching reply, along with the script's latency.
+fn scripted_reply(
+    script_path: &str,
+    last_human_text: &str,
+) -> ZammResult<(String, u64)> {
+    let contents = fs::read_to_string(script_path)
+        .map_err(|e| anyhow!("Couldn't read mock script {}: {}", script_path, e))?;
+    let script: MockScript = serde_yaml::from_str(&contents)?;
+    let reply = script
+        .replies
+        .into_iter()
+        .find(|reply| match &reply.when {
+            Some(trigger) => last_human_text.contains(trigger.as_str()),
+            None => true,
+        })
+        .ok_or(anyhow!("No reply in {} matches the prompt", script_path))?;
+    Ok((reply.text, script.latency_ms))
+}
+
+/// Responds to a chat prompt without making any network requests. The model name
+/// decides how: `echo` repeats the last human message, while `script:<path>`
+/// gives the first matching reply from the YAML file at that path.
+pub async fn mock_chat(
+    llm: &str,
+    prompt: &[ChatMessage],
+) -> ZammResult<(TokenMetadata, ChatMessage)> {
+    let last_human_text = prompt
+        .iter()
+        .rev()
+        .find(|message| matches!(message, ChatMessage::Human { .. }))
+        .map(ChatMessage::text)
+        .unwrap_or_default();
+
+    let (text, latency_ms) = if llm == ECHO_MODEL {
+        (last_human_text.to_string(), 0)
+    } else if let Some(script_path) = llm.strip_prefix(SCRIPT_MODEL_PREFIX) {
+        scripted_reply(script_path, last_human_text)?
+    } else {
+        return Err(anyhow!("Unknown mock model \"{}\"", llm).into());
+    };
+
+    if latency_ms > 0 {
+        tokio::time::sleep(Duration::from_millis(latency_ms)).await;
+    }
+
+    let prompt_tokens: i32 = prompt
+        .iter()
+        .map(|message| count_tokens(message.text()))
+        .sum();
+    let response_tokens = count_tokens(&text);
+    let token_metadata = TokenMetadata {
+        prompt: Some(prompt_tokens),
+        response: Some(response_tokens),
+        total: Some(prompt_tokens + response_tokens),
+    };
+    Ok((token_metadata, ChatMessage::AI { text }))
+}
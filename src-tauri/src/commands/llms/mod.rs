@@ -1,7 +1,28 @@
 pub mod chat;
+pub mod clear_api_call_rating;
+pub mod delete_api_call;
+pub mod delete_conversation;
+pub mod embeddings;
+pub mod export_conversation;
 pub mod get_api_call;
 pub mod get_api_calls;
+pub mod get_api_calls_page;
+pub mod mock;
+pub mod purge_deleted_api_calls;
+pub mod restore_api_calls;
+pub mod semantic_search;
+pub mod set_api_call_rating;
+pub mod trash;
 
 pub use chat::chat;
+pub use clear_api_call_rating::clear_api_call_rating;
+pub use delete_api_call::delete_api_call;
+pub use delete_conversation::delete_conversation;
+pub use export_conversation::export_conversation;
 pub use get_api_call::get_api_call;
 pub use get_api_calls::get_api_calls;
+pub use get_api_calls_page::get_api_calls_page;
+pub use purge_deleted_api_calls::purge_deleted_api_calls;
+pub use restore_api_calls::restore_api_calls;
+pub use semantic_search::semantic_search;
+pub use set_api_call_rating::set_api_call_rating;
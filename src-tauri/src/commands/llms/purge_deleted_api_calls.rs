@@ -0,0 +1,73 @@
+use crate::commands::errors::ZammResult;
+use crate::commands::llms::trash::purge_trash_entry;
+use crate::models::llm_calls::EntityId;
+use crate::ZammDatabase;
+use anyhow::anyhow;
+use specta::specta;
+use tauri::State;
+use uuid::Uuid;
+
+async fn purge_deleted_api_calls_helper(
+    zamm_db: &ZammDatabase,
+    trash_id: &str,
+) -> ZammResult<Vec<EntityId>> {
+    let parsed_uuid = EntityId {
+        uuid: Uuid::parse_str(trash_id)?,
+    };
+    let mut db = zamm_db.0.lock().await;
+    let conn = db.as_mut().ok_or(anyhow!("Failed to lock database"))?;
+    purge_trash_entry(conn, &parsed_uuid)
+}
+
+#[tauri::command(async)]
+#[specta]
+pub async fn purge_deleted_api_calls(
+    database: State<'_, ZammDatabase>,
+    trash_id: &str,
+) -> ZammResult<Vec<EntityId>> {
+    purge_deleted_api_calls_helper(&database, trash_id).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::SideEffectsHelpers;
+    use crate::{check_sample, impl_result_test_case};
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    struct PurgeDeletedApiCallsRequest {
+        trash_id: String,
+    }
+
+    async fn make_request_helper(
+        args: &PurgeDeletedApiCallsRequest,
+        side_effects: &mut SideEffectsHelpers,
+    ) -> ZammResult<Vec<EntityId>> {
+        purge_deleted_api_calls_helper(
+            side_effects.db.as_ref().unwrap(),
+            &args.trash_id,
+        )
+        .await
+    }
+
+    impl_result_test_case!(
+        PurgeDeletedApiCallsTestCase,
+        purge_deleted_api_calls,
+        true,
+        PurgeDeletedApiCallsRequest,
+        Vec<EntityId>
+    );
+
+    check_sample!(
+        PurgeDeletedApiCallsTestCase,
+        test_purge_canonical,
+        "./api/sample-calls/purge_deleted_api_calls-canonical.yaml"
+    );
+
+    check_sample!(
+        PurgeDeletedApiCallsTestCase,
+        test_purge_nonexistent,
+        "./api/sample-calls/purge_deleted_api_calls-nonexistent.yaml"
+    );
+}
@@ -0,0 +1,168 @@
+use crate::commands::errors::ZammResult;
+use crate::commands::http_client::service_http_client;
+use crate::commands::llms::embeddings::{
+    cosine_similarity, decode_embedding, default_embedder, Embedder,
+};
+use crate::models::llm_calls::{EntityId, LightweightLlmCall, LlmCallRow};
+use crate::schema::{llm_call_embeddings, llm_calls};
+use crate::setup::api_keys::Service;
+use crate::{ZammApiKeys, ZammDatabase};
+use anyhow::anyhow;
+use diesel::prelude::*;
+use specta::specta;
+use std::collections::HashMap;
+use tauri::State;
+
+const NUM_SEMANTIC_RESULTS: usize = 10;
+
+async fn semantic_search_helper(
+    zamm_db: &ZammDatabase,
+    embedder: &dyn Embedder,
+    query: &str,
+) -> ZammResult<Vec<LightweightLlmCall>> {
+    // only calls that have already been embedded in the background are searched,
+    // so that a search never waits on embedding the rest of the history
+    let query_embedding = embedder.embed(query).await?;
+
+    let mut db = zamm_db.0.lock().await;
+    let conn = db.as_mut().ok_or(anyhow!("Failed to lock database"))?;
+    let mut best_similarities: HashMap<EntityId, f32> = HashMap::new();
+    let embeddings = llm_call_embeddings::table
+        .select((
+            llm_call_embeddings::llm_call_id,
+            llm_call_embeddings::embedding,
+        ))
+        .filter(llm_call_embeddings::model.eq(embedder.model()))
+        .load::<(EntityId, Vec<u8>)>(conn)?;
+    for (id, bytes) in embeddings {
+        // a call matches as well as the better of its prompt and its completion
+        let similarity = cosine_similarity(&query_embedding, &decode_embedding(&bytes));
+        let best = best_similarities.entry(id).or_insert(similarity);
+        *best = best.max(similarity);
+    }
+    let mut scored_ids: Vec<(EntityId, f32)> = best_similarities
+        .into_iter()
+        .filter(|(_, similarity)| *similarity > 0.0)
+        .collect();
+    scored_ids
+        .sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.uuid.cmp(&b.0.uuid)));
+    scored_ids.truncate(NUM_SEMANTIC_RESULTS);
+
+    let mut calls_by_id: HashMap<EntityId, LlmCallRow> = llm_calls::table
+        .filter(llm_calls::id.eq_any(scored_ids.iter().map(|(id, _)| id)))
+        .load::<LlmCallRow>(conn)?
+        .into_iter()
+        .map(|call| (call.id.clone(), call))
+        .collect();
+    Ok(scored_ids
+        .iter()
+        .filter_map(|(id, _)| calls_by_id.remove(id))
+        .map(|call| call.into())
+        .collect())
+}
+
+#[tauri::command(async)]
+#[specta]
+pub async fn semantic_search(
+    app_handle: tauri::AppHandle,
+    api_keys: State<'_, ZammApiKeys>,
+    database: State<'_, ZammDatabase>,
+    query: String,
+) -> ZammResult<Vec<LightweightLlmCall>> {
+    let http_client =
+        service_http_client(&app_handle, &api_keys, &Service::OpenAI, None).await?;
+    let embedder = default_embedder(&api_keys, http_client).await;
+    semantic_search_helper(&database, embedder.as_ref(), &query).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::SideEffectsHelpers;
+    use crate::{check_sample, impl_result_test_case};
+    use async_trait::async_trait;
+    use serde::{Deserialize, Serialize};
+
+    const KEYWORDS: [&str; 5] = ["joke", "python", "rust", "work", "scientist"];
+
+    /// Stands in for a real embedding model by counting the words that start with
+    /// each keyword.
+    struct KeywordEmbedder;
+
+    #[async_trait]
+    impl Embedder for KeywordEmbedder {
+        fn model(&self) -> &str {
+            "test-keywords"
+        }
+
+        async fn embed(&self, text: &str) -> ZammResult<Vec<f32>> {
+            let lowercase_text = text.to_lowercase();
+            let words: Vec<&str> = lowercase_text
+                .split(|c: char| !c.is_alphanumeric())
+                .collect();
+            Ok(KEYWORDS
+                .iter()
+                .map(|keyword| {
+                    words
+                        .iter()
+                        .filter(|word| word.starts_with(keyword))
+                        .count() as f32
+                })
+                .collect())
+        }
+    }
+
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    struct SemanticSearchRequest {
+        query: String,
+    }
+
+    async fn make_request_helper(
+        args: &SemanticSearchRequest,
+        side_effects: &mut SideEffectsHelpers,
+    ) -> ZammResult<Vec<LightweightLlmCall>> {
+        semantic_search_helper(
+            side_effects.db.as_ref().unwrap(),
+            &KeywordEmbedder,
+            &args.query,
+        )
+        .await
+    }
+
+    impl_result_test_case!(
+        SemanticSearchTestCase,
+        semantic_search,
+        true,
+        SemanticSearchRequest,
+        Vec<LightweightLlmCall>
+    );
+
+    check_sample!(
+        SemanticSearchTestCase,
+        test_single_match,
+        "./api/sample-calls/semantic_search-rust.yaml"
+    );
+
+    check_sample!(
+        SemanticSearchTestCase,
+        test_rank_by_best_match,
+        "./api/sample-calls/semantic_search-python-joke.yaml"
+    );
+
+    check_sample!(
+        SemanticSearchTestCase,
+        test_not_embedded_yet,
+        "./api/sample-calls/semantic_search-not-embedded.yaml"
+    );
+
+    check_sample!(
+        SemanticSearchTestCase,
+        test_no_matches,
+        "./api/sample-calls/semantic_search-no-matches.yaml"
+    );
+
+    #[test]
+    fn test_cosine_similarity_of_zero_vector() {
+        assert_eq!(cosine_similarity(&[0.0, 0.0], &[1.0, 2.0]), 0.0);
+    }
+}
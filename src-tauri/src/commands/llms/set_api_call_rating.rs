@@ -0,0 +1,100 @@
+use crate::commands::errors::ZammResult;
+use crate::models::llm_calls::{EntityId, LlmCallRating, Rating};
+use crate::schema::{llm_call_ratings, llm_calls};
+use crate::ZammDatabase;
+use anyhow::anyhow;
+use diesel::prelude::*;
+use specta::specta;
+use tauri::State;
+use uuid::Uuid;
+
+async fn set_api_call_rating_helper(
+    zamm_db: &ZammDatabase,
+    api_call_id: &str,
+    rating: Rating,
+    ideal_completion: Option<String>,
+) -> ZammResult<LlmCallRating> {
+    let parsed_uuid = EntityId {
+        uuid: Uuid::parse_str(api_call_id)?,
+    };
+    let mut db = zamm_db.0.lock().await;
+    let conn = db.as_mut().ok_or(anyhow!("Failed to lock database"))?;
+    let llm_call_id = llm_calls::table
+        .find(&parsed_uuid)
+        .select(llm_calls::id)
+        .first::<EntityId>(conn)?;
+    let new_rating = LlmCallRating {
+        llm_call_id,
+        rating,
+        ideal_completion,
+    };
+    diesel::replace_into(llm_call_ratings::table)
+        .values(new_rating.as_insertable())
+        .execute(conn)?;
+    Ok(new_rating)
+}
+
+#[tauri::command(async)]
+#[specta]
+pub async fn set_api_call_rating(
+    database: State<'_, ZammDatabase>,
+    id: &str,
+    rating: Rating,
+    ideal_completion: Option<String>,
+) -> ZammResult<LlmCallRating> {
+    set_api_call_rating_helper(&database, id, rating, ideal_completion).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::SideEffectsHelpers;
+    use crate::{check_sample, impl_result_test_case};
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    struct SetApiCallRatingRequest {
+        id: String,
+        rating: Rating,
+        ideal_completion: Option<String>,
+    }
+
+    async fn make_request_helper(
+        args: &SetApiCallRatingRequest,
+        side_effects: &mut SideEffectsHelpers,
+    ) -> ZammResult<LlmCallRating> {
+        set_api_call_rating_helper(
+            side_effects.db.as_ref().unwrap(),
+            &args.id,
+            args.rating,
+            args.ideal_completion.clone(),
+        )
+        .await
+    }
+
+    impl_result_test_case!(
+        SetApiCallRatingTestCase,
+        set_api_call_rating,
+        true,
+        SetApiCallRatingRequest,
+        LlmCallRating
+    );
+
+    check_sample!(
+        SetApiCallRatingTestCase,
+        test_rate_good,
+        "./api/sample-calls/set_api_call_rating-good.yaml"
+    );
+
+    check_sample!(
+        SetApiCallRatingTestCase,
+        test_replace_with_ideal,
+        "./api/sample-calls/set_api_call_rating-replace-with-ideal.yaml"
+    );
+
+    check_sample!(
+        SetApiCallRatingTestCase,
+        test_rate_nonexistent,
+        "./api/sample-calls/set_api_call_rating-nonexistent.yaml"
+    );
+}
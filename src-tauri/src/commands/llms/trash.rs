@@ -0,0 +1,466 @@
+use crate::commands::errors::{Error, ZammResult};
+use crate::models::llm_calls::{
+    EntityId, LlmCallAttributionRow, LlmCallDeployment, LlmCallEmbedding,
+    LlmCallFollowUp, LlmCallKeyProfile, LlmCallLatency, LlmCallRating, LlmCallRow,
+    LlmCallVariant,
+};
+use crate::models::trash::{LlmCallTrashEntry, TrashedLlmCalls};
+use crate::models::LlmCallData;
+use crate::schema::{
+    llm_call_attributions, llm_call_deployments, llm_call_embeddings,
+    llm_call_follow_ups, llm_call_key_profiles, llm_call_latencies, llm_call_ratings,
+    llm_call_trash, llm_call_variants, llm_calls,
+};
+use crate::ZammDatabase;
+use anyhow::anyhow;
+use chrono::naive::NaiveDateTime;
+use chrono::TimeDelta;
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+// how long deleted calls can still be restored, unless the user says otherwise
+const DEFAULT_TRASH_RETENTION_DAYS: u32 = 30;
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct DeletedApiCalls {
+    /// ID to pass in when restoring these calls from the trash
+    pub trash_id: EntityId,
+    pub deleted_at: NaiveDateTime,
+    pub llm_call_ids: Vec<EntityId>,
+}
+
+/// Find every call that is linked to the given one, whether as a follow-up or as
+/// a variant, directly or indirectly.
+pub fn conversation_call_ids(
+    conn: &mut SqliteConnection,
+    id: &EntityId,
+) -> QueryResult<Vec<EntityId>> {
+    let mut found: HashSet<EntityId> = HashSet::from([id.clone()]);
+    let mut frontier = vec![id.clone()];
+    while !frontier.is_empty() {
+        let follow_ups = llm_call_follow_ups::table
+            .filter(
+                llm_call_follow_ups::previous_call_id
+                    .eq_any(&frontier)
+                    .or(llm_call_follow_ups::next_call_id.eq_any(&frontier)),
+            )
+            .load::<LlmCallFollowUp>(conn)?;
+        let variants = llm_call_variants::table
+            .filter(
+                llm_call_variants::canonical_id
+                    .eq_any(&frontier)
+                    .or(llm_call_variants::variant_id.eq_any(&frontier)),
+            )
+            .load::<LlmCallVariant>(conn)?;
+        let linked_ids = follow_ups
+            .into_iter()
+            .flat_map(|f| [f.previous_call_id, f.next_call_id])
+            .chain(
+                variants
+                    .into_iter()
+                    .flat_map(|v| [v.canonical_id, v.variant_id]),
+            );
+        frontier = linked_ids.filter(|id| found.insert(id.clone())).collect();
+    }
+    Ok(found.into_iter().collect())
+}
+
+/// Remove the given calls along with every link to them and everything else that
+/// was recorded about them, saving all of it into a single trash entry. When a
+/// canonical call is removed, its earliest surviving variant becomes the new
+/// canonical for the rest.
+pub fn trash_llm_calls(
+    conn: &mut SqliteConnection,
+    trash_id: &EntityId,
+    ids: &[EntityId],
+    deleted_at: NaiveDateTime,
+) -> ZammResult<DeletedApiCalls> {
+    conn.transaction::<DeletedApiCalls, Error, _>(|conn| {
+        let instances = llm_calls::table
+            .filter(llm_calls::id.eq_any(ids))
+            .order(llm_calls::timestamp.asc())
+            .load::<LlmCallRow>(conn)?;
+        let follow_ups = llm_call_follow_ups::table
+            .filter(
+                llm_call_follow_ups::previous_call_id
+                    .eq_any(ids)
+                    .or(llm_call_follow_ups::next_call_id.eq_any(ids)),
+            )
+            .load::<LlmCallFollowUp>(conn)?;
+        let variants = llm_call_variants::table
+            .filter(
+                llm_call_variants::canonical_id
+                    .eq_any(ids)
+                    .or(llm_call_variants::variant_id.eq_any(ids)),
+            )
+            .load::<LlmCallVariant>(conn)?;
+        let ratings = llm_call_ratings::table
+            .filter(llm_call_ratings::llm_call_id.eq_any(ids))
+            .load::<LlmCallRating>(conn)?;
+        let latencies = llm_call_latencies::table
+            .filter(llm_call_latencies::llm_call_id.eq_any(ids))
+            .load::<LlmCallLatency>(conn)?;
+        let attributions = llm_call_attributions::table
+            .filter(llm_call_attributions::llm_call_id.eq_any(ids))
+            .load::<LlmCallAttributionRow>(conn)?;
+        let key_profiles = llm_call_key_profiles::table
+            .filter(llm_call_key_profiles::llm_call_id.eq_any(ids))
+            .load::<LlmCallKeyProfile>(conn)?;
+        let deployments = llm_call_deployments::table
+            .filter(llm_call_deployments::llm_call_id.eq_any(ids))
+            .load::<LlmCallDeployment>(conn)?;
+        let embeddings = llm_call_embeddings::table
+            .filter(llm_call_embeddings::llm_call_id.eq_any(ids))
+            .load::<LlmCallEmbedding>(conn)?;
+
+        // variants of a deleted canonical that are themselves staying behind
+        let orphaned_variants: Vec<(EntityId, EntityId)> = llm_call_variants::table
+            .inner_join(
+                llm_calls::table.on(llm_calls::id.eq(llm_call_variants::variant_id)),
+            )
+            .select((
+                llm_call_variants::canonical_id,
+                llm_call_variants::variant_id,
+            ))
+            .filter(llm_call_variants::canonical_id.eq_any(ids))
+            .filter(llm_call_variants::variant_id.ne_all(ids))
+            .order((llm_call_variants::canonical_id, llm_calls::timestamp.asc()))
+            .load(conn)?;
+        let mut repaired_variants: Vec<LlmCallVariant> = Vec::new();
+        let mut new_canonical: Option<(EntityId, EntityId)> = None;
+        for (old_canonical_id, variant_id) in orphaned_variants {
+            match &new_canonical {
+                Some((old_id, new_id)) if *old_id == old_canonical_id => {
+                    repaired_variants.push(LlmCallVariant {
+                        canonical_id: new_id.clone(),
+                        variant_id,
+                    });
+                }
+                _ => new_canonical = Some((old_canonical_id, variant_id)),
+            }
+        }
+
+        diesel::delete(
+            llm_call_follow_ups::table.filter(
+                llm_call_follow_ups::previous_call_id
+                    .eq_any(ids)
+                    .or(llm_call_follow_ups::next_call_id.eq_any(ids)),
+            ),
+        )
+        .execute(conn)?;
+        diesel::delete(
+            llm_call_variants::table.filter(
+                llm_call_variants::canonical_id
+                    .eq_any(ids)
+                    .or(llm_call_variants::variant_id.eq_any(ids)),
+            ),
+        )
+        .execute(conn)?;
+        // foreign key constraints aren't enforced, so nothing cascades on its own
+        diesel::delete(
+            llm_call_ratings::table.filter(llm_call_ratings::llm_call_id.eq_any(ids)),
+        )
+        .execute(conn)?;
+        diesel::delete(
+            llm_call_latencies::table
+                .filter(llm_call_latencies::llm_call_id.eq_any(ids)),
+        )
+        .execute(conn)?;
+        diesel::delete(
+            llm_call_attributions::table
+                .filter(llm_call_attributions::llm_call_id.eq_any(ids)),
+        )
+        .execute(conn)?;
+        diesel::delete(
+            llm_call_key_profiles::table
+                .filter(llm_call_key_profiles::llm_call_id.eq_any(ids)),
+        )
+        .execute(conn)?;
+        diesel::delete(
+            llm_call_deployments::table
+                .filter(llm_call_deployments::llm_call_id.eq_any(ids)),
+        )
+        .execute(conn)?;
+        diesel::delete(
+            llm_call_embeddings::table
+                .filter(llm_call_embeddings::llm_call_id.eq_any(ids)),
+        )
+        .execute(conn)?;
+        diesel::delete(llm_calls::table.filter(llm_calls::id.eq_any(ids)))
+            .execute(conn)?;
+        diesel::insert_into(llm_call_variants::table)
+            .values(
+                repaired_variants
+                    .iter()
+                    .map(|v| v.as_insertable())
+                    .collect::<Vec<_>>(),
+            )
+            .execute(conn)?;
+
+        let llm_call_ids = instances.iter().map(|row| row.id.clone()).collect();
+        let entry = LlmCallTrashEntry {
+            id: trash_id.clone(),
+            deleted_at,
+            contents: TrashedLlmCalls {
+                llm_calls: LlmCallData {
+                    instances,
+                    follow_ups,
+                    variants,
+                    ratings,
+                    latencies,
+                    attributions,
+                    key_profiles,
+                    deployments,
+                },
+                repaired_variants,
+                embeddings,
+            },
+        };
+        diesel::insert_into(llm_call_trash::table)
+            .values(&entry)
+            .execute(conn)?;
+
+        Ok(DeletedApiCalls {
+            trash_id: entry.id,
+            deleted_at,
+            llm_call_ids,
+        })
+    })
+}
+
+fn get_trash_entry(
+    conn: &mut SqliteConnection,
+    trash_id: &EntityId,
+) -> ZammResult<LlmCallTrashEntry> {
+    let entry = llm_call_trash::table
+        .find(trash_id)
+        .first::<LlmCallTrashEntry>(conn)
+        .optional()?
+        .ok_or(anyhow!(
+            "No deleted API calls found with ID {}",
+            trash_id.uuid
+        ))?;
+    Ok(entry)
+}
+
+/// Put the calls from a trash entry back where they were, undoing any changes
+/// that were made to variant links when they were removed.
+pub fn restore_llm_calls(
+    conn: &mut SqliteConnection,
+    trash_id: &EntityId,
+) -> ZammResult<Vec<EntityId>> {
+    conn.transaction::<Vec<EntityId>, Error, _>(|conn| {
+        let contents = get_trash_entry(conn, trash_id)?.contents;
+
+        for repaired in &contents.repaired_variants {
+            diesel::delete(
+                llm_call_variants::table
+                    .filter(llm_call_variants::canonical_id.eq(&repaired.canonical_id))
+                    .filter(llm_call_variants::variant_id.eq(&repaired.variant_id)),
+            )
+            .execute(conn)?;
+        }
+        let instances = &contents.llm_calls.instances;
+        diesel::insert_into(llm_calls::table)
+            .values(
+                instances
+                    .iter()
+                    .map(|row| row.as_insertable())
+                    .collect::<Vec<_>>(),
+            )
+            .execute(conn)?;
+        diesel::insert_into(llm_call_ratings::table)
+            .values(
+                contents
+                    .llm_calls
+                    .ratings
+                    .iter()
+                    .map(|rating| rating.as_insertable())
+                    .collect::<Vec<_>>(),
+            )
+            .execute(conn)?;
+        diesel::insert_into(llm_call_latencies::table)
+            .values(&contents.llm_calls.latencies)
+            .execute(conn)?;
+        diesel::insert_into(llm_call_attributions::table)
+            .values(&contents.llm_calls.attributions)
+            .execute(conn)?;
+        diesel::insert_into(llm_call_key_profiles::table)
+            .values(&contents.llm_calls.key_profiles)
+            .execute(conn)?;
+        diesel::insert_into(llm_call_deployments::table)
+            .values(&contents.llm_calls.deployments)
+            .execute(conn)?;
+        diesel::insert_into(llm_call_embeddings::table)
+            .values(&contents.embeddings)
+            .execute(conn)?;
+
+        // links to calls that have since been deleted for good stay deleted
+        let linked_ids = contents
+            .llm_calls
+            .follow_ups
+            .iter()
+            .flat_map(|f| [&f.previous_call_id, &f.next_call_id])
+            .chain(
+                contents
+                    .llm_calls
+                    .variants
+                    .iter()
+                    .flat_map(|v| [&v.canonical_id, &v.variant_id]),
+            )
+            .collect::<Vec<&EntityId>>();
+        let existing_ids: HashSet<EntityId> = llm_calls::table
+            .select(llm_calls::id)
+            .filter(llm_calls::id.eq_any(linked_ids))
+            .load::<EntityId>(conn)?
+            .into_iter()
+            .collect();
+        diesel::insert_or_ignore_into(llm_call_follow_ups::table)
+            .values(
+                contents
+                    .llm_calls
+                    .follow_ups
+                    .iter()
+                    .filter(|f| {
+                        existing_ids.contains(&f.previous_call_id)
+                            && existing_ids.contains(&f.next_call_id)
+                    })
+                    .map(|f| f.as_insertable())
+                    .collect::<Vec<_>>(),
+            )
+            .execute(conn)?;
+        diesel::insert_or_ignore_into(llm_call_variants::table)
+            .values(
+                contents
+                    .llm_calls
+                    .variants
+                    .iter()
+                    .filter(|v| {
+                        existing_ids.contains(&v.canonical_id)
+                            && existing_ids.contains(&v.variant_id)
+                    })
+                    .map(|v| v.as_insertable())
+                    .collect::<Vec<_>>(),
+            )
+            .execute(conn)?;
+
+        diesel::delete(llm_call_trash::table.find(trash_id)).execute(conn)?;
+        Ok(contents
+            .llm_calls
+            .instances
+            .into_iter()
+            .map(|row| row.id)
+            .collect())
+    })
+}
+
+/// Permanently remove a trash entry right away, for when the calls in it shouldn't
+/// be kept around for the rest of the retention period, such as when they contain
+/// a secret that was pasted in by accident.
+pub fn purge_trash_entry(
+    conn: &mut SqliteConnection,
+    trash_id: &EntityId,
+) -> ZammResult<Vec<EntityId>> {
+    conn.transaction::<Vec<EntityId>, Error, _>(|conn| {
+        let entry = get_trash_entry(conn, trash_id)?;
+        diesel::delete(llm_call_trash::table.find(trash_id)).execute(conn)?;
+        Ok(entry
+            .contents
+            .llm_calls
+            .instances
+            .into_iter()
+            .map(|row| row.id)
+            .collect())
+    })
+}
+
+/// Permanently remove trash entries that are older than the retention period.
+pub async fn purge_expired_trash(
+    zamm_db: &ZammDatabase,
+    retention_days: Option<u32>,
+) -> ZammResult<usize> {
+    let mut db = zamm_db.0.lock().await;
+    let conn = db.as_mut().ok_or(anyhow!("Failed to lock database"))?;
+    let retention = TimeDelta::days(
+        retention_days
+            .unwrap_or(DEFAULT_TRASH_RETENTION_DAYS)
+            .into(),
+    );
+    let cutoff = chrono::Utc::now().naive_utc() - retention;
+    let num_purged = diesel::delete(
+        llm_call_trash::table.filter(llm_call_trash::deleted_at.lt(cutoff)),
+    )
+    .execute(conn)?;
+    Ok(num_purged)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::database::setup_database;
+    use diesel::connection::SimpleConnection;
+    use std::fs;
+    use uuid::Uuid;
+
+    fn count_side_rows(conn: &mut SqliteConnection) -> Vec<i64> {
+        vec![
+            llm_call_latencies::table.count().get_result(conn).unwrap(),
+            llm_call_attributions::table
+                .count()
+                .get_result(conn)
+                .unwrap(),
+            llm_call_key_profiles::table
+                .count()
+                .get_result(conn)
+                .unwrap(),
+            llm_call_deployments::table
+                .count()
+                .get_result(conn)
+                .unwrap(),
+            llm_call_embeddings::table.count().get_result(conn).unwrap(),
+        ]
+    }
+
+    #[test]
+    fn test_trash_and_restore_side_tables() {
+        let mut conn = setup_database(None);
+        let dump = fs::read_to_string(
+            "./api/sample-database-writes/conversation-started-with-attribution/dump.sql",
+        )
+        .unwrap();
+        conn.batch_execute(&dump).unwrap();
+        conn.batch_execute(concat!(
+            "INSERT INTO llm_call_latencies VALUES",
+            "('d5ad1e49-f57f-4481-84fb-4d70ba8a7a74',1234);",
+            "INSERT INTO llm_call_key_profiles VALUES",
+            "('d5ad1e49-f57f-4481-84fb-4d70ba8a7a74','work');",
+            "INSERT INTO llm_call_deployments VALUES",
+            "('d5ad1e49-f57f-4481-84fb-4d70ba8a7a74','gpt-4-deployment');",
+            "INSERT INTO llm_call_embeddings VALUES",
+            "('d5ad1e49-f57f-4481-84fb-4d70ba8a7a74','text-embedding-3-small',",
+            "'completion',X'0000803F');",
+        ))
+        .unwrap();
+        assert_eq!(count_side_rows(&mut conn), vec![1, 1, 1, 1, 1]);
+
+        let call_id = EntityId {
+            uuid: Uuid::parse_str("d5ad1e49-f57f-4481-84fb-4d70ba8a7a74").unwrap(),
+        };
+        let trash_id = EntityId {
+            uuid: Uuid::new_v4(),
+        };
+        trash_llm_calls(
+            &mut conn,
+            &trash_id,
+            &[call_id.clone()],
+            chrono::Utc::now().naive_utc(),
+        )
+        .unwrap();
+        assert_eq!(count_side_rows(&mut conn), vec![0, 0, 0, 0, 0]);
+
+        let restored = restore_llm_calls(&mut conn, &trash_id).unwrap();
+        assert_eq!(restored, vec![call_id]);
+        assert_eq!(count_side_rows(&mut conn), vec![1, 1, 1, 1, 1]);
+    }
+}
@@ -1,22 +1,38 @@
 pub mod database;
 pub mod errors;
+pub mod http_client;
 mod keys;
-mod llms;
+pub mod llms;
+mod pagination;
 pub mod preferences;
+pub mod search;
 mod sounds;
 mod system;
 pub mod terminal;
+mod usage_stats;
 
 // size of one page of results in database list view
 const PAGE_SIZE: i64 = 50;
 
-pub use database::{export_db, import_db};
+pub use database::{
+    export_db, export_fine_tuning_data, import_chatgpt, import_db, import_jsonl,
+};
 pub use errors::Error;
-pub use keys::{get_api_keys, set_api_key};
-pub use llms::{chat, get_api_call, get_api_calls};
+pub use keys::{
+    get_api_keys, set_active_api_key_profile, set_api_key, set_api_key_options,
+    set_api_key_passphrase, set_api_key_profile, test_api_key, unlock_api_keys,
+};
+pub use llms::{
+    chat, clear_api_call_rating, delete_api_call, delete_conversation,
+    export_conversation, get_api_call, get_api_calls, get_api_calls_page,
+    purge_deleted_api_calls, restore_api_calls, semantic_search, set_api_call_rating,
+};
 pub use preferences::{get_preferences, set_preferences};
+pub use search::search;
 pub use sounds::play_sound;
 pub use system::get_system_info;
 pub use terminal::{
-    get_terminal_session, get_terminal_sessions, run_command, send_command_input,
+    get_terminal_session, get_terminal_sessions, get_terminal_sessions_page,
+    resize_terminal_session, run_command, send_command_input, TerminalOutput,
 };
+pub use usage_stats::get_usage_stats;
@@ -0,0 +1,39 @@
+use crate::commands::PAGE_SIZE;
+use crate::models::EntityId;
+use chrono::naive::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+/// Position of the last item on a page. Because items are ordered by timestamp and
+/// then by ID, the next page can pick up right after this item even if new items
+/// have been recorded in the meantime.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, specta::Type)]
+pub struct PageCursor {
+    pub timestamp: NaiveDateTime,
+    pub id: EntityId,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    pub next_cursor: Option<PageCursor>,
+}
+
+impl<T> Page<T> {
+    /// Build a page out of rows that were loaded with a limit of `PAGE_SIZE + 1`,
+    /// using the extra row (if any) only to tell whether there is a next page.
+    pub fn from_rows<R, F>(mut rows: Vec<R>, cursor_of: F) -> Self
+    where
+        R: Into<T>,
+        F: Fn(&R) -> PageCursor,
+    {
+        let mut next_cursor = None;
+        if rows.len() > PAGE_SIZE as usize {
+            rows.truncate(PAGE_SIZE as usize);
+            next_cursor = rows.last().map(cursor_of);
+        }
+        Page {
+            items: rows.into_iter().map(|row| row.into()).collect(),
+            next_cursor,
+        }
+    }
+}
@@ -2,5 +2,8 @@ mod models;
 mod read;
 mod write;
 
-pub use read::{get_preferences, get_preferences_file_contents};
+pub use models::Preferences;
+pub use read::{
+    get_preferences, get_preferences_file_contents, get_preferences_helper,
+};
 pub use write::{set_preferences, set_preferences_helper};
@@ -25,6 +25,30 @@ pub struct Preferences {
     pub sound_on: Option<bool>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub volume: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub trash_retention_days: Option<u32>,
+    /// Proxy for all requests to LLM providers, e.g. `http://proxy.corp:8080`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub proxy_url: Option<String>,
+    /// Comma-separated hosts that bypass the proxy, in the same format as the
+    /// `NO_PROXY` environment variable
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub no_proxy: Option<String>,
+    /// PEM file with extra root certificates to trust
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ca_bundle_path: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub request_timeout_secs: Option<u32>,
+    /// `.env` files to read API keys from, in order of precedence. Defaults to the
+    /// one in the current directory followed by the one in the config directory.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dotenv_files: Option<Vec<String>>,
+    /// Embed API calls in the background so that they can be found by semantic
+    /// search. On unless turned off. Turning it off keeps the history from being
+    /// sent to the embedding provider, but leaves semantic search with nothing to
+    /// find.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub background_embeddings: Option<bool>,
 }
 
 pub fn get_preferences_file(
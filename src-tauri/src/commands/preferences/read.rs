@@ -132,6 +132,13 @@ mod tests {
         "./api/sample-calls/get_preferences-extra-settings.yaml"
     );
 
+    #[cfg(target_os = "linux")]
+    check_sample!(
+        GetPreferencesTestCase,
+        test_network,
+        "./api/sample-calls/get_preferences-network.yaml"
+    );
+
     #[cfg(not(target_os = "windows"))]
     check_sample!(
         GetPreferencesTestCase,
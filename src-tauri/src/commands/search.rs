@@ -0,0 +1,239 @@
+use crate::commands::errors::ZammResult;
+use crate::commands::terminal::clean_cast_output;
+use crate::commands::PAGE_SIZE;
+use crate::models::asciicasts::{AsciiCast, AsciiCastData};
+use crate::models::EntityId;
+use crate::schema::asciicasts;
+use crate::ZammDatabase;
+use anyhow::anyhow;
+use chrono::naive::NaiveDateTime;
+use diesel::dsl::sql;
+use diesel::prelude::*;
+use diesel::sql_types::{BigInt, Bool, Text, Timestamp};
+use serde::{Deserialize, Serialize};
+use specta::specta;
+use strum_macros::{Display, EnumString};
+use tauri::State;
+
+// number of tokens to show around each match in a snippet
+const SNIPPET_NUM_TOKENS: i64 = 16;
+
+#[derive(
+    Debug,
+    Clone,
+    Eq,
+    PartialEq,
+    Serialize,
+    Deserialize,
+    specta::Type,
+    EnumString,
+    Display,
+)]
+#[strum(serialize_all = "snake_case")]
+pub enum SearchEntityType {
+    LlmCall,
+    TerminalSession,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+pub struct SearchResult {
+    pub id: EntityId,
+    pub entity_type: SearchEntityType,
+    pub timestamp: NaiveDateTime,
+    pub snippet: String,
+}
+
+#[derive(QueryableByName)]
+struct SearchIndexRow {
+    #[diesel(sql_type = Text)]
+    entity_id: EntityId,
+    #[diesel(sql_type = Text)]
+    entity_type: String,
+    #[diesel(sql_type = Timestamp)]
+    timestamp: NaiveDateTime,
+    #[diesel(sql_type = Text)]
+    snippet: String,
+}
+
+impl TryFrom<SearchIndexRow> for SearchResult {
+    type Error = anyhow::Error;
+
+    fn try_from(row: SearchIndexRow) -> Result<Self, Self::Error> {
+        let entity_type = row
+            .entity_type
+            .parse()
+            .map_err(|_| anyhow!("Unknown search entity type '{}'", row.entity_type))?;
+        Ok(SearchResult {
+            id: row.entity_id,
+            entity_type,
+            timestamp: row.timestamp,
+            snippet: row.snippet,
+        })
+    }
+}
+
+/// Replace the search index entry for a terminal session with its latest output.
+pub fn index_terminal_session(
+    conn: &mut SqliteConnection,
+    id: &EntityId,
+    cast: &AsciiCastData,
+) -> QueryResult<()> {
+    let entity_type = SearchEntityType::TerminalSession.to_string();
+    diesel::sql_query(
+        "DELETE FROM search_index WHERE entity_type = ? AND entity_id = ?",
+    )
+    .bind::<Text, _>(&entity_type)
+    .bind::<Text, _>(id)
+    .execute(conn)?;
+    diesel::sql_query(
+        "INSERT INTO search_index (entity_id, entity_type, content) VALUES (?, ?, ?)",
+    )
+    .bind::<Text, _>(id)
+    .bind::<Text, _>(&entity_type)
+    .bind::<Text, _>(clean_cast_output(cast))
+    .execute(conn)?;
+    Ok(())
+}
+
+/// Index any terminal sessions that were recorded before search was available.
+pub fn index_unindexed_terminal_sessions(
+    conn: &mut SqliteConnection,
+) -> ZammResult<usize> {
+    // `search_index` is a virtual table that Diesel doesn't know about
+    let not_indexed = sql::<Bool>(
+        "asciicasts.id NOT IN \
+            (SELECT entity_id FROM search_index WHERE entity_type = ",
+    )
+    .bind::<Text, _>(SearchEntityType::TerminalSession.to_string())
+    .sql(")");
+    let unindexed_sessions = asciicasts::table
+        .filter(not_indexed)
+        .load::<AsciiCast>(conn)?;
+    for session in &unindexed_sessions {
+        index_terminal_session(conn, &session.id, &session.cast)?;
+    }
+    Ok(unindexed_sessions.len())
+}
+
+/// Quote each search term so that user input is never parsed as FTS5 syntax.
+fn to_fts_query(query: &str) -> String {
+    query
+        .split_whitespace()
+        .map(|term| format!("\"{}\"", term.replace('"', "\"\"")))
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+async fn search_helper(
+    zamm_db: &ZammDatabase,
+    query: &str,
+    offset: i32,
+) -> ZammResult<Vec<SearchResult>> {
+    let fts_query = to_fts_query(query);
+    if fts_query.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mut db = zamm_db.0.lock().await;
+    let conn = db.as_mut().ok_or(anyhow!("Failed to lock database"))?;
+    let rows = diesel::sql_query(
+        "SELECT
+            search_index.entity_id AS entity_id,
+            search_index.entity_type AS entity_type,
+            COALESCE(llm_calls.timestamp, asciicasts.timestamp) AS timestamp,
+            snippet(search_index, 2, '**', '**', '...', ?) AS snippet
+        FROM search_index
+            LEFT JOIN llm_calls
+                ON search_index.entity_type = 'llm_call'
+                AND llm_calls.id = search_index.entity_id
+            LEFT JOIN asciicasts
+                ON search_index.entity_type = 'terminal_session'
+                AND asciicasts.id = search_index.entity_id
+        WHERE search_index MATCH ?
+            AND COALESCE(llm_calls.id, asciicasts.id) IS NOT NULL
+        ORDER BY rank
+        LIMIT ? OFFSET ?",
+    )
+    .bind::<BigInt, _>(SNIPPET_NUM_TOKENS)
+    .bind::<Text, _>(fts_query)
+    .bind::<BigInt, _>(PAGE_SIZE)
+    .bind::<BigInt, _>(offset as i64)
+    .load::<SearchIndexRow>(conn)?;
+    let results = rows
+        .into_iter()
+        .map(|row| row.try_into())
+        .collect::<Result<Vec<SearchResult>, anyhow::Error>>()?;
+    Ok(results)
+}
+
+#[tauri::command(async)]
+#[specta]
+pub async fn search(
+    database: State<'_, ZammDatabase>,
+    query: String,
+    offset: i32,
+) -> ZammResult<Vec<SearchResult>> {
+    search_helper(&database, &query, offset).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::SideEffectsHelpers;
+    use crate::{check_sample, impl_result_test_case};
+
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    struct SearchRequest {
+        query: String,
+        offset: i32,
+    }
+
+    async fn make_request_helper(
+        args: &SearchRequest,
+        side_effects: &mut SideEffectsHelpers,
+    ) -> ZammResult<Vec<SearchResult>> {
+        let zamm_db = side_effects.db.as_ref().unwrap();
+        {
+            // sample database dumps are loaded as raw SQL, bypassing the app's own
+            // indexing of terminal sessions
+            let mut db = zamm_db.0.lock().await;
+            index_unindexed_terminal_sessions(db.as_mut().unwrap())?;
+        }
+        search_helper(zamm_db, &args.query, args.offset).await
+    }
+
+    impl_result_test_case!(
+        SearchTestCase,
+        search,
+        true,
+        SearchRequest,
+        Vec<SearchResult>
+    );
+
+    #[test]
+    fn test_fts_query_escaping() {
+        assert_eq!(
+            to_fts_query("borrow  \"checker\" -lifetimes"),
+            "\"borrow\" \"\"\"checker\"\"\" \"-lifetimes\""
+        );
+        assert_eq!(to_fts_query("   "), "");
+    }
+
+    check_sample!(
+        SearchTestCase,
+        test_llm_calls,
+        "./api/sample-calls/search-llm-calls.yaml"
+    );
+
+    check_sample!(
+        SearchTestCase,
+        test_terminal_sessions,
+        "./api/sample-calls/search-terminal-sessions.yaml"
+    );
+
+    check_sample!(
+        SearchTestCase,
+        test_no_results,
+        "./api/sample-calls/search-no-results.yaml"
+    );
+}
@@ -31,6 +31,7 @@ fn get_shell_init_file(shell: &Option<Shell>) -> Option<String> {
     let relative_file = match shell {
         Some(Shell::Bash) => Some("~/.bashrc".to_string()),
         Some(Shell::Zsh) => Some("~/.zshrc".to_string()),
+        Some(Shell::Fish) => Some("~/.config/fish/config.fish".to_string()),
         Some(Shell::PowerShell) => None,
         None => get_relative_profile_init_file(),
     };
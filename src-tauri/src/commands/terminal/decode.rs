@@ -0,0 +1,109 @@
+use std::char::REPLACEMENT_CHARACTER;
+use std::str;
+
+/// Decodes terminal output as UTF-8 as it gets read in. A character can get split
+/// across two reads, so any incomplete sequence at the end of a read is held onto
+/// until the rest of it arrives.
+#[derive(Debug, Default)]
+pub struct Utf8Decoder {
+    incomplete: Vec<u8>,
+}
+
+impl Utf8Decoder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns all the text that can be decoded so far. Invalid bytes get replaced
+    /// with U+FFFD, the same as `String::from_utf8_lossy` would.
+    pub fn decode(&mut self, bytes: &[u8]) -> String {
+        self.incomplete.extend_from_slice(bytes);
+        let mut output = String::new();
+        let mut remaining = self.incomplete.as_slice();
+        loop {
+            match str::from_utf8(remaining) {
+                Ok(valid) => {
+                    output.push_str(valid);
+                    remaining = &[];
+                    break;
+                }
+                Err(e) => {
+                    let (valid, rest) = remaining.split_at(e.valid_up_to());
+                    // already checked by `from_utf8`
+                    output.push_str(str::from_utf8(valid).unwrap_or_default());
+                    match e.error_len() {
+                        Some(invalid_length) => {
+                            output.push(REPLACEMENT_CHARACTER);
+                            remaining = &rest[invalid_length..];
+                        }
+                        // the sequence might still get completed by the next read
+                        None => {
+                            remaining = rest;
+                            break;
+                        }
+                    }
+                }
+            }
+        }
+        let consumed = self.incomplete.len() - remaining.len();
+        self.incomplete.drain(..consumed);
+        output
+    }
+
+    /// Called once there's nothing left to read, so that a sequence which never got
+    /// completed still shows up in the output.
+    pub fn finish(&mut self) -> String {
+        if self.incomplete.is_empty() {
+            String::new()
+        } else {
+            self.incomplete.clear();
+            REPLACEMENT_CHARACTER.to_string()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decode_in_chunks(bytes: &[u8], chunk_size: usize) -> String {
+        let mut decoder = Utf8Decoder::new();
+        let mut output: String = bytes
+            .chunks(chunk_size)
+            .map(|chunk| decoder.decode(chunk))
+            .collect();
+        output.push_str(&decoder.finish());
+        output
+    }
+
+    #[test]
+    fn test_sequences_split_across_reads() {
+        let text = "សួស្តី 👋 ┌─┐";
+        for chunk_size in 1..=5 {
+            assert_eq!(decode_in_chunks(text.as_bytes(), chunk_size), text);
+        }
+    }
+
+    #[test]
+    fn test_incomplete_sequence_held_until_next_read() {
+        let wave = "👋".as_bytes();
+        let mut decoder = Utf8Decoder::new();
+        assert_eq!(decoder.decode(&wave[..2]), "");
+        assert_eq!(decoder.decode(&wave[2..]), "👋");
+    }
+
+    #[test]
+    fn test_invalid_bytes() {
+        let bytes = b"bad \xff byte, bad \xe2\x94 sequence";
+        assert_eq!(
+            decode_in_chunks(bytes, 1),
+            String::from_utf8_lossy(bytes).to_string()
+        );
+    }
+
+    #[test]
+    fn test_truncated_sequence_at_end() {
+        let bytes = &"ok ┌".as_bytes()[..5];
+        assert_eq!(decode_in_chunks(bytes, 2), "ok \u{FFFD}");
+    }
+}
@@ -0,0 +1,106 @@
+use crate::commands::errors::ZammResult;
+use crate::commands::terminal::models::OutputListener;
+use crate::commands::terminal::render::RenderedUpdate;
+use crate::commands::terminal::send_input::{
+    update_and_index_stored_cast, update_stored_cast,
+};
+use crate::models::EntityId;
+use crate::{ZammDatabase, ZammTerminalSessions};
+use specta::Type;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+use tauri_specta::Event;
+
+/// Output tends to arrive in bursts, so saves of the session get spaced out by at
+/// least this much instead of happening on every chunk.
+const SAVE_INTERVAL: Duration = Duration::from_millis(500);
+
+/// What a running terminal session shows now, sent out whenever more output
+/// arrives. Lines that have scrolled off the screen only get sent once, so the
+/// output replaces everything after the first `from_line` lines sent before.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, Type, Event)]
+pub struct TerminalOutput {
+    pub session_id: EntityId,
+    pub from_line: u32,
+    pub output: String,
+}
+
+async fn save_cast(
+    app_handle: &AppHandle,
+    session_id: &EntityId,
+    reindex: bool,
+) -> ZammResult<()> {
+    let zamm_db = app_handle.state::<ZammDatabase>();
+    let zamm_sessions = app_handle.state::<ZammTerminalSessions>();
+    let db = &mut zamm_db.0.lock().await;
+    let sessions = zamm_sessions.0.lock().await;
+    if let (Some(conn), Some(terminal)) = (db.as_mut(), sessions.get(session_id)) {
+        let cast = terminal.get_cast()?;
+        // if the session hasn't been saved yet, this output will be once it is
+        if reindex {
+            update_and_index_stored_cast(conn, session_id, &cast)?;
+        } else {
+            update_stored_cast(conn, session_id, &cast)?;
+        }
+    }
+    Ok(())
+}
+
+/// Forwards output to the frontend, and keeps the saved copy of the session up to
+/// date so that nothing printed in between inputs gets lost.
+struct SessionOutputListener {
+    app_handle: AppHandle,
+    session_id: EntityId,
+    save_pending: Arc<AtomicBool>,
+}
+
+impl OutputListener for SessionOutputListener {
+    fn on_output(&self, update: RenderedUpdate) {
+        let event = TerminalOutput {
+            session_id: self.session_id.clone(),
+            from_line: update.from_line as u32,
+            output: update.text,
+        };
+        if let Err(e) = self.app_handle.emit(TerminalOutput::NAME, event) {
+            eprintln!("Couldn't send terminal output: {e}");
+        }
+
+        // whatever else arrives before the save happens gets saved along with this
+        if self.save_pending.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        let app_handle = self.app_handle.clone();
+        let session_id = self.session_id.clone();
+        let save_pending = Arc::clone(&self.save_pending);
+        tauri::async_runtime::spawn(async move {
+            tokio::time::sleep(SAVE_INTERVAL).await;
+            save_pending.store(false, Ordering::SeqCst);
+            if let Err(e) = save_cast(&app_handle, &session_id, false).await {
+                eprintln!("Couldn't save terminal output: {e}");
+            }
+        });
+    }
+
+    fn on_finished(&self) {
+        let app_handle = self.app_handle.clone();
+        let session_id = self.session_id.clone();
+        tauri::async_runtime::spawn(async move {
+            if let Err(e) = save_cast(&app_handle, &session_id, true).await {
+                eprintln!("Couldn't save finished terminal session: {e}");
+            }
+        });
+    }
+}
+
+pub fn output_listener(
+    app_handle: AppHandle,
+    session_id: EntityId,
+) -> Box<dyn OutputListener> {
+    Box::new(SessionOutputListener {
+        app_handle,
+        session_id,
+        save_pending: Arc::new(AtomicBool::new(false)),
+    })
+}
@@ -1,6 +1,7 @@
-use super::parse::clean_output;
+use super::render::clean_cast_output;
 use crate::commands::errors::ZammResult;
 use crate::commands::terminal::models::TerminalSessionInfo;
+use crate::commands::terminal::Terminal;
 use crate::models::asciicasts::AsciiCast;
 use crate::models::EntityId;
 use crate::schema::asciicasts;
@@ -26,20 +27,12 @@ async fn get_terminal_session_helper(
     let result: AsciiCast = asciicasts::table
         .filter(asciicasts::id.eq(&parsed_uuid))
         .first::<AsciiCast>(conn)?;
-    let concantenated_output = result
-        .cast
-        .entries
-        .iter()
-        .flat_map(|e| {
-            if e.event_type == asciicast::EventType::Output {
-                Some(clean_output(&e.event_data))
-            } else {
-                None
-            }
-        })
-        .collect::<Vec<String>>()
-        .join("");
-    let is_active = sessions.contains_key(&parsed_uuid);
+    let concantenated_output = clean_cast_output(&result.cast);
+    // a session whose program has exited can't take any more input
+    let is_active = match sessions.get(&parsed_uuid) {
+        Some(terminal) => terminal.exit_code()?.is_none(),
+        None => false,
+    };
     let recovered_session = TerminalSessionInfo {
         id: result.id,
         timestamp: result.timestamp,
@@ -1,11 +1,10 @@
 use crate::commands::errors::ZammResult;
 use crate::commands::PAGE_SIZE;
-use crate::models::asciicasts::AsciiCast;
+use crate::models::asciicasts::{AsciiCast, EventType};
 use crate::models::EntityId;
 use crate::schema::asciicasts;
 use crate::ZammDatabase;
 use anyhow::anyhow;
-use asciicast::EventType;
 use chrono::naive::NaiveDateTime;
 use diesel::prelude::*;
 use diesel::RunQueryDsl;
@@ -0,0 +1,92 @@
+use crate::commands::errors::ZammResult;
+use crate::commands::pagination::{Page, PageCursor};
+use crate::commands::terminal::get_sessions::TerminalSessionReference;
+use crate::commands::PAGE_SIZE;
+use crate::models::asciicasts::AsciiCast;
+use crate::schema::asciicasts;
+use crate::ZammDatabase;
+use anyhow::anyhow;
+use diesel::prelude::*;
+use diesel::RunQueryDsl;
+use specta::specta;
+use tauri::State;
+
+async fn get_terminal_sessions_page_helper(
+    zamm_db: &ZammDatabase,
+    cursor: Option<PageCursor>,
+) -> ZammResult<Page<TerminalSessionReference>> {
+    let mut db = zamm_db.0.lock().await;
+    let conn = db.as_mut().ok_or(anyhow!("Failed to lock database"))?;
+    let mut query = asciicasts::table.into_boxed();
+    if let Some(cursor) = &cursor {
+        query = query.filter(
+            asciicasts::timestamp
+                .lt(&cursor.timestamp)
+                .or(asciicasts::timestamp
+                    .eq(&cursor.timestamp)
+                    .and(asciicasts::id.lt(&cursor.id))),
+        );
+    }
+    let result: Vec<AsciiCast> = query
+        .order(asciicasts::timestamp.desc())
+        .then_order_by(asciicasts::id.desc())
+        .limit(PAGE_SIZE + 1)
+        .load::<AsciiCast>(conn)?;
+    Ok(Page::from_rows(result, |row| PageCursor {
+        timestamp: row.timestamp,
+        id: row.id.clone(),
+    }))
+}
+
+#[tauri::command(async)]
+#[specta]
+pub async fn get_terminal_sessions_page(
+    database: State<'_, ZammDatabase>,
+    cursor: Option<PageCursor>,
+) -> ZammResult<Page<TerminalSessionReference>> {
+    get_terminal_sessions_page_helper(&database, cursor).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::SideEffectsHelpers;
+    use crate::{check_sample, impl_result_test_case};
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    struct GetTerminalSessionsPageRequest {
+        cursor: Option<PageCursor>,
+    }
+
+    async fn make_request_helper(
+        args: &GetTerminalSessionsPageRequest,
+        side_effects: &mut SideEffectsHelpers,
+    ) -> ZammResult<Page<TerminalSessionReference>> {
+        get_terminal_sessions_page_helper(
+            side_effects.db.as_ref().unwrap(),
+            args.cursor.clone(),
+        )
+        .await
+    }
+
+    impl_result_test_case!(
+        GetTerminalSessionsPageTestCase,
+        get_terminal_sessions_page,
+        true,
+        GetTerminalSessionsPageRequest,
+        Page<TerminalSessionReference>
+    );
+
+    check_sample!(
+        GetTerminalSessionsPageTestCase,
+        test_first_page,
+        "./api/sample-calls/get_terminal_sessions_page-first.yaml"
+    );
+
+    check_sample!(
+        GetTerminalSessionsPageTestCase,
+        test_after_cursor,
+        "./api/sample-calls/get_terminal_sessions_page-after-cursor.yaml"
+    );
+}
@@ -1,12 +1,20 @@
+mod decode;
+mod events;
 mod get_session;
 mod get_sessions;
+mod get_sessions_page;
 pub mod models;
-mod parse;
+mod render;
+mod resize;
 mod run;
 mod send_input;
 
+pub use events::TerminalOutput;
 pub use get_session::get_terminal_session;
 pub use get_sessions::get_terminal_sessions;
+pub use get_sessions_page::get_terminal_sessions_page;
 pub use models::{ActualTerminal, Terminal};
+pub use render::clean_cast_output;
+pub use resize::resize_terminal_session;
 pub use run::run_command;
 pub use send_input::send_command_input;
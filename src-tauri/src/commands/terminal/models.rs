@@ -1,24 +1,18 @@
 use crate::commands::errors::ZammResult;
-use crate::models::asciicasts::AsciiCastData;
+use crate::commands::terminal::decode::Utf8Decoder;
+use crate::commands::terminal::render::{RenderedUpdate, TerminalRenderer};
+use crate::models::asciicasts::{AsciiCastData, Entry, EventType, TerminalSize};
 use crate::models::os::OS;
 use crate::models::EntityId;
 use anyhow::anyhow;
 use chrono::DateTime;
 use chrono::NaiveDateTime;
-use portable_pty::{
-    native_pty_system, Child, CommandBuilder, MasterPty, PtySize, SlavePty,
-};
+use portable_pty::{native_pty_system, Child, CommandBuilder, MasterPty, PtySize};
 use std::io::{Read, Write};
-use std::sync::mpsc;
-use std::sync::mpsc::{Receiver, Sender};
 use std::sync::{Arc, Mutex};
-use std::thread::{sleep, spawn};
-use std::time::Duration;
+use std::thread::spawn;
 
-#[cfg(test)]
-const TERMINAL_READ_TIMEOUT: Duration = Duration::from_millis(500);
-#[cfg(not(test))]
-const TERMINAL_READ_TIMEOUT: Duration = Duration::from_millis(100);
+const READ_BUFFER_SIZE: usize = 1024;
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize, specta::Type)]
 pub struct TerminalSessionInfo {
@@ -31,26 +25,44 @@ pub struct TerminalSessionInfo {
 }
 
 pub trait Terminal: Send + Sync {
-    fn run_command(&mut self, command: &str) -> ZammResult<String>;
-    #[allow(dead_code)]
-    fn read_updates(&mut self) -> ZammResult<String>;
+    fn run_command(&mut self, command: &str, size: TerminalSize) -> ZammResult<String>;
     fn send_input(&mut self, input: &str) -> ZammResult<String>;
+    fn resize(&mut self, size: TerminalSize) -> ZammResult<()>;
     fn get_cast(&self) -> ZammResult<AsciiCastData>;
+    /// `None` for as long as the program is still running.
+    fn exit_code(&self) -> ZammResult<Option<u32>>;
+}
+
+/// Gets called from the session's reader thread as output arrives.
+pub trait OutputListener: Send + Sync {
+    /// Gets what the session shows now, rendered the same way as `clean_cast_output`
+    /// would, since new output can change what was already on the screen.
+    fn on_output(&self, update: RenderedUpdate);
+    /// Called once after the last of the output, when the program is done.
+    fn on_finished(&self);
+}
+
+fn pty_size(size: TerminalSize) -> PtySize {
+    PtySize {
+        rows: size.rows,
+        cols: size.cols,
+        pixel_width: 0,
+        pixel_height: 0,
+    }
 }
 
 struct PtySession {
-    #[allow(dead_code)]
     master: Box<dyn MasterPty + Send>,
-    #[allow(dead_code)]
-    slave: Box<dyn SlavePty + Send>,
     child: Box<dyn Child + Send + Sync>,
     writer: Box<dyn Write + Send>,
-    input_receiver: Receiver<char>,
     exit_code: Option<u32>,
 }
 
 impl PtySession {
-    fn new(command: &str) -> ZammResult<Self> {
+    fn new(
+        command: &str,
+        size: TerminalSize,
+    ) -> ZammResult<(Self, Box<dyn Read + Send>)> {
         let cmd_and_args = shlex::split(command)
             .ok_or_else(|| anyhow!("Failed to split command '{}'", command))?;
         let parsed_cmd = cmd_and_args
@@ -62,49 +74,38 @@ impl PtySession {
         let current_dir = std::env::current_dir()?;
         cmd_builder.cwd(current_dir);
 
-        let session = native_pty_system().openpty(PtySize {
-            rows: 24,
-            cols: 80,
-            pixel_width: 0,
-            pixel_height: 0,
-        })?;
-
-        let (tx, rx): (Sender<char>, Receiver<char>) = mpsc::channel();
-        let mut reader = session.master.try_clone_reader()?;
-        spawn(move || {
-            let buf = &mut [0; 1];
-            loop {
-                let bytes_read = reader.read(buf).unwrap();
-                if bytes_read == 0 {
-                    break;
-                }
-                tx.send(buf[0] as char).unwrap();
-            }
-        });
+        let session = native_pty_system().openpty(pty_size(size))?;
 
+        let reader = session.master.try_clone_reader()?;
         let child = session.slave.spawn_command(cmd_builder)?;
+        // the reader only sees the end of the output once every handle to the
+        // slave side is closed, so don't keep ours around after the child has it
+        drop(session.slave);
         let writer = session.master.take_writer()?;
-        Ok(Self {
+        let pty_session = Self {
             master: session.master,
-            slave: session.slave,
             child,
             writer,
-            input_receiver: rx,
             exit_code: None,
-        })
+        };
+        Ok((pty_session, reader))
     }
 }
 
 pub struct ActualTerminalInner {
     session: Option<PtySession>,
     session_data: AsciiCastData,
+    unread_output: String,
+    renderer: TerminalRenderer,
 }
 
 impl ActualTerminalInner {
     pub fn new() -> Self {
         Self {
             session: None,
-            session_data: AsciiCastData::new(),
+            session_data: AsciiCastData::default(),
+            unread_output: String::new(),
+            renderer: TerminalRenderer::new(TerminalSize::default()),
         }
     }
 
@@ -122,56 +123,45 @@ impl ActualTerminalInner {
         Ok(time_diff.num_milliseconds() as f64 / 1000.0)
     }
 
-    fn read_once(&mut self) -> ZammResult<String> {
-        match self.session.as_mut() {
-            None => Err(anyhow!("No session started").into()),
-            Some(session) => {
-                let mut partial_output = String::new();
-                while let Ok(c) = session.input_receiver.try_recv() {
-                    partial_output.push(c);
-                }
-                Ok(partial_output)
-            }
-        }
-    }
-
-    fn run_command(&mut self, command: &str) -> ZammResult<String> {
+    fn run_command(
+        &mut self,
+        command: &str,
+        size: TerminalSize,
+    ) -> ZammResult<Box<dyn Read + Send>> {
         if self.session.is_some() {
             return Err(anyhow!("Session already started").into());
         }
 
         self.session_data.header.command = Some(command.to_string());
         self.session_data.header.timestamp = Some(chrono::Utc::now());
+        self.session_data.set_size(size);
+        self.renderer = TerminalRenderer::new(size);
 
-        let session = PtySession::new(command)?;
+        let (session, reader) = PtySession::new(command, size)?;
         self.session = Some(session);
+        Ok(reader)
+    }
 
-        let result = self.read_updates()?;
-        Ok(result)
+    /// Records output as soon as the reader thread gets it. Output only gets
+    /// buffered for `read_updates` if nobody is listening for it.
+    fn record_output(&mut self, output: &str, buffer: bool) -> ZammResult<()> {
+        self.session_data.entries.push(Entry {
+            time: self.relative_time()?,
+            event_type: EventType::Output,
+            event_data: output.to_string(),
+        });
+        self.renderer.process(output);
+        if buffer {
+            self.unread_output.push_str(output);
+        }
+        Ok(())
     }
 
     fn read_updates(&mut self) -> ZammResult<String> {
-        let output = {
-            let mut partial_output = String::new();
-            loop {
-                sleep(TERMINAL_READ_TIMEOUT);
-                let partial = self.read_once()?;
-                if partial.is_empty() {
-                    break;
-                }
-                partial_output.push_str(&partial);
-            }
-            partial_output
-        };
-
-        if !output.is_empty() {
-            self.session_data.entries.push(asciicast::Entry {
-                time: self.relative_time()?,
-                event_type: asciicast::EventType::Output,
-                event_data: output.clone(),
-            });
+        if self.session.is_none() {
+            return Err(anyhow!("No session started").into());
         }
-        Ok(output)
+        Ok(std::mem::take(&mut self.unread_output))
     }
 
     fn send_input(&mut self, input: &str) -> ZammResult<String> {
@@ -181,9 +171,9 @@ impl ActualTerminalInner {
                 session.writer.write_all(input.as_bytes())?;
                 session.writer.flush()?;
 
-                self.session_data.entries.push(asciicast::Entry {
+                self.session_data.entries.push(Entry {
                     time: self.relative_time()?,
-                    event_type: asciicast::EventType::Input,
+                    event_type: EventType::Input,
                     event_data: input.to_string(),
                 });
 
@@ -192,6 +182,23 @@ impl ActualTerminalInner {
         }
     }
 
+    fn resize(&mut self, size: TerminalSize) -> ZammResult<()> {
+        match self.session.as_mut() {
+            None => Err(anyhow!("No session started").into()),
+            Some(session) => {
+                session.master.resize(pty_size(size))?;
+                self.renderer.resize(size);
+
+                self.session_data.entries.push(Entry {
+                    time: self.relative_time()?,
+                    event_type: EventType::Resize,
+                    event_data: size.to_string(),
+                });
+                Ok(())
+            }
+        }
+    }
+
     fn get_cast(&self) -> &AsciiCastData {
         &self.session_data
     }
@@ -219,55 +226,187 @@ impl ActualTerminalInner {
     }
 }
 
+/// A terminal session backed by a real PTY. Output gets read in the background
+/// as it arrives, so none of the methods here wait on the running program.
 pub struct ActualTerminal {
     inner: Arc<Mutex<ActualTerminalInner>>,
+    output_listener: Option<Box<dyn OutputListener>>,
 }
 
 impl ActualTerminal {
     pub fn new() -> Self {
         Self {
             inner: Arc::new(Mutex::new(ActualTerminalInner::new())),
+            output_listener: None,
         }
     }
 
-    #[allow(dead_code)]
-    fn exit_code(&self) -> ZammResult<Option<u32>> {
+    /// Hands output to `listener` as it arrives, instead of holding onto it until
+    /// the next call to `read_updates`.
+    pub fn with_output_listener(listener: Box<dyn OutputListener>) -> Self {
+        Self {
+            output_listener: Some(listener),
+            ..Self::new()
+        }
+    }
+
+    fn spawn_reader(&mut self, mut reader: Box<dyn Read + Send>) {
+        let inner = Arc::clone(&self.inner);
+        let listener = self.output_listener.take();
+        spawn(move || {
+            let mut buf = [0; READ_BUFFER_SIZE];
+            let mut decoder = Utf8Decoder::new();
+            loop {
+                let (output, finished) = match reader.read(&mut buf) {
+                    Ok(0) | Err(_) => (decoder.finish(), true),
+                    Ok(bytes_read) => (decoder.decode(&buf[..bytes_read]), false),
+                };
+                // nothing to show if all we got was the start of a character
+                if !output.is_empty() {
+                    let update = match inner.lock() {
+                        Ok(mut inner) => {
+                            let buffer = listener.is_none();
+                            if let Err(e) = inner.record_output(&output, buffer) {
+                                eprintln!("Couldn't record terminal output: {e}");
+                            }
+                            listener.as_ref().map(|_| inner.renderer.update())
+                        }
+                        Err(_) => break,
+                    };
+                    // call the listener without holding the lock, so that it can
+                    // look at the terminal too
+                    if let (Some(listener), Some(update)) = (&listener, update) {
+                        listener.on_output(update);
+                    }
+                }
+                if finished {
+                    break;
+                }
+            }
+            if let Some(listener) = &listener {
+                listener.on_finished();
+            }
+        });
+    }
+
+    /// Only sessions without an output listener hold onto their output, and the
+    /// app always gives its sessions one.
+    #[cfg(test)]
+    pub fn read_updates(&mut self) -> ZammResult<String> {
         let mut inner = self.inner.lock()?;
-        Ok(inner.exit_code())
+        inner.read_updates()
     }
 }
 
 impl Terminal for ActualTerminal {
-    fn run_command(&mut self, command: &str) -> ZammResult<String> {
+    fn run_command(&mut self, command: &str, size: TerminalSize) -> ZammResult<String> {
         let mut inner = self.inner.lock()?;
-        inner.run_command(command)
+        let reader = inner.run_command(command, size)?;
+        drop(inner);
+        self.spawn_reader(reader);
+        Ok(String::new())
     }
 
-    fn read_updates(&mut self) -> ZammResult<String> {
+    fn send_input(&mut self, input: &str) -> ZammResult<String> {
         let mut inner = self.inner.lock()?;
-        inner.read_updates()
+        inner.send_input(input)
     }
 
-    fn send_input(&mut self, input: &str) -> ZammResult<String> {
+    fn resize(&mut self, size: TerminalSize) -> ZammResult<()> {
         let mut inner = self.inner.lock()?;
-        inner.send_input(input)
+        inner.resize(size)
     }
 
     fn get_cast(&self) -> ZammResult<AsciiCastData> {
         let inner = self.inner.lock()?;
         Ok(inner.get_cast().clone())
     }
+
+    fn exit_code(&self) -> ZammResult<Option<u32>> {
+        let mut inner = self.inner.lock()?;
+        Ok(inner.exit_code())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::commands::terminal::render::clean_cast_output;
+    use std::sync::mpsc;
+    use std::thread::sleep;
+    use std::time::{Duration, Instant};
 
     #[cfg(target_os = "windows")]
     const SHELL_COMMAND: &str = "cmd";
     #[cfg(not(target_os = "windows"))]
     const SHELL_COMMAND: &str = "bash";
 
+    const WAIT_TIMEOUT: Duration = Duration::from_secs(10);
+    const POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+    /// Passes output along to the test, followed by `None` once the program is done.
+    struct ChannelListener {
+        sender: Mutex<mpsc::Sender<Option<RenderedUpdate>>>,
+    }
+
+    impl OutputListener for ChannelListener {
+        fn on_output(&self, update: RenderedUpdate) {
+            self.sender.lock().unwrap().send(Some(update)).ok();
+        }
+
+        fn on_finished(&self) {
+            self.sender.lock().unwrap().send(None).ok();
+        }
+    }
+
+    fn read_until(
+        terminal: &mut ActualTerminal,
+        done: impl Fn(&str) -> bool,
+    ) -> String {
+        let start = Instant::now();
+        let mut output = String::new();
+        while !done(&output) {
+            assert!(
+                start.elapsed() < WAIT_TIMEOUT,
+                "Timed out with output {:?}",
+                output
+            );
+            sleep(POLL_INTERVAL);
+            output.push_str(&terminal.read_updates().unwrap());
+        }
+        output
+    }
+
+    fn wait_for_exit(terminal: &ActualTerminal) -> u32 {
+        let start = Instant::now();
+        loop {
+            if let Some(code) = terminal.exit_code().unwrap() {
+                return code;
+            }
+            assert!(start.elapsed() < WAIT_TIMEOUT, "Process never exited");
+            sleep(POLL_INTERVAL);
+        }
+    }
+
+    fn is_prompt(output: &str) -> bool {
+        if cfg!(target_os = "windows") {
+            output.contains("src-tauri>")
+        } else {
+            output.ends_with("$ ") || output.ends_with("# ")
+        }
+    }
+
+    fn recorded_output(terminal: &ActualTerminal) -> String {
+        terminal
+            .get_cast()
+            .unwrap()
+            .entries
+            .iter()
+            .filter(|entry| entry.event_type == EventType::Output)
+            .map(|entry| entry.event_data.as_str())
+            .collect()
+    }
+
     #[tokio::test]
     async fn test_capture_command_output() {
         let (command, expected_output) = if cfg!(target_os = "windows") {
@@ -277,64 +416,74 @@ mod tests {
         };
 
         let mut terminal = ActualTerminal::new();
-        let output = terminal.run_command(command).unwrap();
+        terminal
+            .run_command(command, TerminalSize::default())
+            .unwrap();
+        let output = read_until(&mut terminal, |o| o.len() >= expected_output.len());
         assert_eq!(output, expected_output);
-        assert_eq!(terminal.get_cast().unwrap().entries.len(), 1);
-        assert_eq!(terminal.exit_code().unwrap(), Some(0));
+        assert_eq!(recorded_output(&terminal), expected_output);
+        assert_eq!(wait_for_exit(&terminal), 0);
     }
 
     #[tokio::test]
     async fn test_capture_interleaved_output() {
         let mut terminal = ActualTerminal::new();
-        let output = terminal
-            .run_command("python api/sample-terminal-sessions/interleaved.py")
+        terminal
+            .run_command(
+                "python api/sample-terminal-sessions/interleaved.py",
+                TerminalSize::default(),
+            )
             .unwrap();
-
+        #[cfg(not(target_os = "windows"))]
+        {
+            let expected_output = "stdout\r\nstderr\r\nstdout\r\n";
+            let output =
+                read_until(&mut terminal, |o| o.len() >= expected_output.len());
+            assert_eq!(output, expected_output);
+        }
         // No trailing newline on Windows
         #[cfg(target_os = "windows")]
-        assert!(
-            output.contains("stdout\r\nstderr\r\nstdout"),
-            "Output: {:?}",
-            output
-        );
-        #[cfg(not(target_os = "windows"))]
-        assert_eq!(output, "stdout\r\nstderr\r\nstdout\r\n");
+        read_until(&mut terminal, |o| o.contains("stdout\r\nstderr\r\nstdout"));
 
-        assert_eq!(terminal.get_cast().unwrap().entries.len(), 1);
-        assert_eq!(terminal.exit_code().unwrap(), Some(0));
+        assert_eq!(wait_for_exit(&terminal), 0);
     }
 
     #[tokio::test]
     async fn test_capture_output_without_blocking() {
         let mut terminal = ActualTerminal::new();
-        let output = terminal.run_command(SHELL_COMMAND).unwrap();
+        let start = Instant::now();
+        let output = terminal
+            .run_command(SHELL_COMMAND, TerminalSize::default())
+            .unwrap();
+        // this used to wait for the output to go quiet for at least this long
+        assert!(start.elapsed() < Duration::from_millis(500));
+        assert_eq!(output, "");
 
         // Windows output contains a whole lot of control characters, so we don't test
         // directly with `starts_with` or `ends_with` here
+        let output = read_until(&mut terminal, is_prompt);
         #[cfg(target_os = "windows")]
         assert!(
-            output.contains("(c) Microsoft Corporation. All rights reserved.")
-                && output.contains("src-tauri>"),
+            output.contains("(c) Microsoft Corporation. All rights reserved."),
             "Output: {:?}",
             output
         );
-        #[cfg(not(target_os = "windows"))]
-        assert!(
-            output.ends_with("$ ") || output.ends_with("# "),
-            "Output: {}",
-            output
-        );
 
-        assert_eq!(terminal.get_cast().unwrap().entries.len(), 1);
+        assert_eq!(recorded_output(&terminal), output);
         assert_eq!(terminal.exit_code().unwrap(), None);
     }
 
     #[tokio::test]
     async fn test_no_entry_on_empty_capture() {
         let mut terminal = ActualTerminal::new();
-        terminal.run_command(SHELL_COMMAND).unwrap();
-        terminal.read_updates().unwrap();
-        assert_eq!(terminal.get_cast().unwrap().entries.len(), 1);
+        terminal
+            .run_command(SHELL_COMMAND, TerminalSize::default())
+            .unwrap();
+        read_until(&mut terminal, is_prompt);
+        let num_entries = terminal.get_cast().unwrap().entries.len();
+
+        assert_eq!(terminal.read_updates().unwrap(), "");
+        assert_eq!(terminal.get_cast().unwrap().entries.len(), num_entries);
     }
 
     #[tokio::test]
@@ -346,24 +495,124 @@ mod tests {
         };
 
         let mut terminal = ActualTerminal::new();
-        terminal.run_command(SHELL_COMMAND).unwrap();
+        terminal
+            .run_command(SHELL_COMMAND, TerminalSize::default())
+            .unwrap();
+        let prompt = read_until(&mut terminal, is_prompt);
 
-        let output = terminal.send_input(input).unwrap();
-        #[cfg(target_os = "windows")]
-        assert!(
-            output.contains("stdout\r\nstderr\r\nstdout"),
-            "Output: {:?}",
-            output
-        );
+        let mut output = terminal.send_input(input).unwrap();
+        output.push_str(&read_until(&mut terminal, |o| {
+            o.contains("stdout\r\nstderr\r\nstdout") && is_prompt(o)
+        }));
         #[cfg(not(target_os = "windows"))]
         assert!(
-            output.contains("stdout\r\nstderr\r\nstdout\r\n")
-                && (output.ends_with("$ ") || output.ends_with("# ")),
+            output.contains("stdout\r\nstderr\r\nstdout\r\n"),
             "Output: {:?}",
             output
         );
 
-        assert_eq!(terminal.get_cast().unwrap().entries.len(), 3);
+        let cast = terminal.get_cast().unwrap();
+        let inputs: Vec<&Entry> = cast
+            .entries
+            .iter()
+            .filter(|entry| entry.event_type == EventType::Input)
+            .collect();
+        assert_eq!(inputs.len(), 1);
+        assert_eq!(inputs[0].event_data, input);
+        assert_eq!(recorded_output(&terminal), prompt + &output);
         assert_eq!(terminal.exit_code().unwrap(), None);
     }
+
+    #[tokio::test]
+    async fn test_output_listener() {
+        let (tx, rx) = mpsc::channel();
+        let mut terminal =
+            ActualTerminal::with_output_listener(Box::new(ChannelListener {
+                sender: Mutex::new(tx),
+            }));
+        terminal
+            .run_command(
+                "python api/sample-terminal-sessions/pause.py",
+                TerminalSize::default(),
+            )
+            .unwrap();
+
+        let mut output = String::new();
+        while let Some(update) = rx.recv_timeout(WAIT_TIMEOUT).unwrap() {
+            output = update.apply(&output);
+        }
+        // output that arrives after a pause still gets picked up, and only by the
+        // listener
+        assert!(output.contains("First"), "Output: {:?}", output);
+        assert!(output.contains("Second"), "Output: {:?}", output);
+        assert_eq!(terminal.read_updates().unwrap(), "");
+        assert_eq!(clean_cast_output(&terminal.get_cast().unwrap()), output);
+    }
+
+    #[tokio::test]
+    async fn test_capture_unicode_output() {
+        let mut terminal = ActualTerminal::new();
+        terminal
+            .run_command(
+                "python api/sample-terminal-sessions/unicode.py",
+                TerminalSize::default(),
+            )
+            .unwrap();
+        let output = read_until(&mut terminal, |o| o.contains("👋"));
+
+        for line in ["សួស្តី ពិភពលោក", "┌────┐", "│ ok │", "└────┘"]
+        {
+            assert!(output.contains(line), "Output: {:?}", output);
+        }
+        assert!(!output.contains('\u{FFFD}'), "Output: {:?}", output);
+        assert_eq!(recorded_output(&terminal), output);
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    #[tokio::test]
+    async fn test_initial_size() {
+        let mut terminal = ActualTerminal::new();
+        terminal
+            .run_command(
+                "stty size",
+                TerminalSize {
+                    rows: 30,
+                    cols: 100,
+                },
+            )
+            .unwrap();
+        let output = read_until(&mut terminal, |o| o.ends_with("\r\n"));
+        assert_eq!(output, "30 100\r\n");
+
+        let header = terminal.get_cast().unwrap().header;
+        assert_eq!((header.width, header.height), (100, 30));
+    }
+
+    #[tokio::test]
+    async fn test_resize() {
+        let mut terminal = ActualTerminal::new();
+        terminal
+            .run_command(SHELL_COMMAND, TerminalSize::default())
+            .unwrap();
+        read_until(&mut terminal, is_prompt);
+
+        terminal
+            .resize(TerminalSize {
+                rows: 40,
+                cols: 120,
+            })
+            .unwrap();
+        let cast = terminal.get_cast().unwrap();
+        let resize_entry = cast.entries.last().unwrap();
+        assert_eq!(resize_entry.event_type, EventType::Resize);
+        assert_eq!(resize_entry.event_data, "120x40");
+        // the header keeps the size the session started out at
+        assert_eq!((cast.header.width, cast.header.height), (80, 24));
+
+        #[cfg(not(target_os = "windows"))]
+        {
+            terminal.send_input("stty size\n").unwrap();
+            read_until(&mut terminal, |o| o.contains("40 120\r\n"));
+        }
+    }
 }
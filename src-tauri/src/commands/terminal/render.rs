@@ -0,0 +1,322 @@
+use crate::models::asciicasts::{AsciiCastData, EventType, TerminalSize};
+use lazy_static::lazy_static;
+use regex::Regex;
+
+lazy_static! {
+    static ref THREE_OR_MORE_NEWLINES: Regex = Regex::new(r"\n{3,}").unwrap();
+}
+
+/// How many lines that have scrolled off the top of the screen get kept around.
+const MAX_SCROLLBACK_LINES: usize = 100_000;
+
+/// What a session shows now, compared to the last update. Lines that have scrolled
+/// off the screen can't change anymore, so they only ever get sent once.
+#[derive(Debug, Clone)]
+pub struct RenderedUpdate {
+    /// How many lines of the text sent so far stay as they were.
+    pub from_line: usize,
+    /// Replaces everything after those lines.
+    pub text: String,
+}
+
+impl RenderedUpdate {
+    #[cfg(test)]
+    pub fn apply(&self, text: &str) -> String {
+        let kept_lines = text.split('\n').take(self.from_line);
+        kept_lines
+            .map(|line| format!("{line}\n"))
+            .collect::<String>()
+            + &self.text
+    }
+}
+
+/// Plays output through a terminal emulator, so that colors, cursor movement,
+/// erased lines and `\r` progress bars turn into the text that a user would have
+/// actually seen on their screen.
+pub struct TerminalRenderer {
+    parser: vt100::Parser,
+    /// Lines that have scrolled off the screen and gone out in an update.
+    finished_lines: usize,
+    /// The start of a line that has scrolled off the screen, but that wraps onto
+    /// the row after it.
+    unfinished_line: String,
+}
+
+impl TerminalRenderer {
+    pub fn new(size: TerminalSize) -> Self {
+        Self {
+            parser: vt100::Parser::new(
+                size.rows.max(1),
+                size.cols.max(1),
+                MAX_SCROLLBACK_LINES,
+            ),
+            finished_lines: 0,
+            unfinished_line: String::new(),
+        }
+    }
+
+    pub fn process(&mut self, output: &str) {
+        self.parser.process(output.as_bytes());
+    }
+
+    pub fn resize(&mut self, size: TerminalSize) {
+        self.parser
+            .screen_mut()
+            .set_size(size.rows.max(1), size.cols.max(1));
+    }
+
+    /// Returns everything that has scrolled off the screen, followed by the screen
+    /// itself down to the cursor or the last line with text on it, whichever is
+    /// further down. Lines that only wrapped because they were wider than the
+    /// screen get joined back together.
+    pub fn text(&mut self) -> String {
+        let (_, cols) = self.parser.screen().size();
+        let mut lines = Vec::<(String, bool)>::new();
+
+        let screen = self.parser.screen_mut();
+        screen.set_scrollback(usize::MAX);
+        let scrollback_length = screen.scrollback();
+        for offset in (1..=scrollback_length).rev() {
+            // the top row of the screen is now `offset` lines back in the scrollback
+            screen.set_scrollback(offset);
+            let line = screen.rows(0, cols).next().unwrap_or_default();
+            lines.push((line, screen.row_wrapped(0)));
+        }
+        screen.set_scrollback(0);
+
+        lines.extend(screen_lines(self.parser.screen()));
+        join_lines(lines)
+    }
+
+    /// Gets what has changed since the last update, without going over the lines
+    /// that had already scrolled off the screen by then. Like with `text`, blank
+    /// lines at the start get left out.
+    pub fn update(&mut self) -> RenderedUpdate {
+        let (_, cols) = self.parser.screen().size();
+        let screen = self.parser.screen_mut();
+        // the last update left the view scrolled back by a line, and the view stays
+        // put while output scrolls past it. That keeps working once the scrollback
+        // is full, unlike counting how many lines are in it.
+        let scrolled_off = match screen.scrollback() {
+            0 => {
+                // the scrollback was empty, so there was nothing to scroll back to
+                screen.set_scrollback(usize::MAX);
+                screen.scrollback()
+            }
+            offset => offset - 1,
+        };
+        let mut finished = String::new();
+        for offset in (1..=scrolled_off).rev() {
+            screen.set_scrollback(offset);
+            let line = screen.rows(0, cols).next().unwrap_or_default();
+            self.unfinished_line.push_str(&line);
+            if !screen.row_wrapped(0) {
+                finished.push_str(&self.unfinished_line);
+                finished.push('\n');
+                self.unfinished_line.clear();
+            }
+        }
+        screen.set_scrollback(0);
+
+        if self.finished_lines == 0 {
+            finished = finished.trim_start().to_string();
+        }
+        let from_line = self.finished_lines;
+        self.finished_lines += finished.matches('\n').count();
+        let mut text = finished + &self.unfinished_line;
+        text.push_str(&join_lines(screen_lines(self.parser.screen())));
+        if self.finished_lines == 0 {
+            text = text.trim_start().to_string();
+        }
+
+        self.parser.screen_mut().set_scrollback(1);
+        RenderedUpdate { from_line, text }
+    }
+}
+
+/// The screen down to the cursor or the last line with text on it, whichever is
+/// further down, along with whether each line wraps onto the next.
+fn screen_lines(screen: &vt100::Screen) -> Vec<(String, bool)> {
+    let (_, cols) = screen.size();
+    let lines = screen
+        .rows(0, cols)
+        .zip(0..)
+        .map(|(line, row)| (line, screen.row_wrapped(row)))
+        .collect::<Vec<(String, bool)>>();
+    let (cursor_row, _) = screen.cursor_position();
+    let last_row = lines
+        .iter()
+        .rposition(|(line, _)| !line.is_empty())
+        .unwrap_or_default()
+        .max(cursor_row.into());
+    lines.into_iter().take(last_row + 1).collect()
+}
+
+fn join_lines(lines: Vec<(String, bool)>) -> String {
+    let mut text = String::new();
+    let num_lines = lines.len();
+    for (i, (line, wrapped)) in lines.into_iter().enumerate() {
+        text.push_str(&line);
+        if !wrapped && i + 1 < num_lines {
+            text.push('\n');
+        }
+    }
+    text
+}
+
+/// Renders a piece of output on its own, as if it were printed to a fresh screen.
+/// Without the rest of the screen to go on, jumps to an absolute position can leave
+/// behind a run of blank lines, so those get collapsed.
+pub fn clean_output(output: &str) -> String {
+    let mut renderer = TerminalRenderer::new(TerminalSize::default());
+    renderer.process(output);
+    THREE_OR_MORE_NEWLINES
+        .replace_all(&renderer.text(), "\n\n")
+        .trim_start()
+        .to_string()
+}
+
+/// Renders an entire session, taking into account everything that was printed
+/// before as well as any changes to the size of the terminal.
+pub fn clean_cast_output(cast: &AsciiCastData) -> String {
+    let mut renderer = TerminalRenderer::new(cast.size());
+    for entry in &cast.entries {
+        match entry.event_type {
+            EventType::Output => renderer.process(&entry.event_data),
+            EventType::Resize => {
+                if let Ok(size) = entry.event_data.parse() {
+                    renderer.resize(size);
+                }
+            }
+            EventType::Input => {}
+        }
+    }
+    renderer.text().trim_start().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plain_output() {
+        assert_eq!(clean_output("ls\r\nfile.txt\r\n$ "), "ls\nfile.txt\n$ ");
+    }
+
+    #[test]
+    fn test_carriage_return_progress_bar() {
+        let output = "Downloading...  0%\rDownloading... 50%\rDownloading... 100%\r\n";
+        assert_eq!(clean_output(output), "Downloading... 100%\n");
+    }
+
+    #[test]
+    fn test_colors() {
+        let output = "\u{1b}[01;34mdir\u{1b}[0m  \u{1b}[01;32mscript.sh\u{1b}[0m\r\n";
+        assert_eq!(clean_output(output), "dir  script.sh\n");
+    }
+
+    #[test]
+    fn test_erase_line() {
+        let output = "Waiting for server...\r\u{1b}[2KServer started\r\n";
+        assert_eq!(clean_output(output), "Server started\n");
+    }
+
+    #[test]
+    fn test_cursor_movement() {
+        let output =
+            "1. pending\r\n2. pending\r\n\u{1b}[2A\u{1b}[3Cdone\u{1b}[K\r\n\r\n";
+        assert_eq!(clean_output(output), "1. done\n2. pending\n");
+    }
+
+    #[test]
+    fn test_long_lines_not_wrapped() {
+        let line = "x".repeat(100);
+        assert_eq!(
+            clean_output(&format!("{line}\r\n$ ")),
+            format!("{line}\n$ ")
+        );
+    }
+
+    #[test]
+    fn test_scrollback() {
+        let output = (1..=30)
+            .map(|i| format!("line {i}\r\n"))
+            .collect::<String>();
+        let expected = (1..=30).map(|i| format!("line {i}\n")).collect::<String>();
+        assert_eq!(clean_output(&output), expected);
+    }
+
+    /// Goes over the output in pieces, the way the reader thread gets it.
+    fn render_updates(chunks: &[&str], size: TerminalSize) -> String {
+        let mut renderer = TerminalRenderer::new(size);
+        let mut text = String::new();
+        for chunk in chunks {
+            renderer.process(chunk);
+            text = renderer.update().apply(&text);
+        }
+        text
+    }
+
+    #[test]
+    fn test_updates_match_full_render() {
+        let size = TerminalSize { rows: 5, cols: 20 };
+        let long_line = "x".repeat(50);
+        let chunks = [
+            "\r\n\r\n  first\r\n",
+            "Downloading...  0%",
+            "\rDownloading... 50%",
+            "\rDownloading... 100%\r\n",
+            long_line.as_str(),
+            "\r\nline 1\r\nline 2\r\nline 3\r\nline 4\r\n$ ",
+            "\u{1b}[2A\u{1b}[2Kchanged\u{1b}[2B",
+        ];
+        let mut renderer = TerminalRenderer::new(size);
+        renderer.process(&chunks.concat());
+        let expected = renderer.text().trim_start().to_string();
+        assert_eq!(render_updates(&chunks, size), expected);
+        assert!(expected.contains("Downloading... 100%\n"), "{expected:?}");
+    }
+
+    #[test]
+    fn test_updates_only_send_new_lines() {
+        let mut renderer = TerminalRenderer::new(TerminalSize { rows: 2, cols: 20 });
+        renderer.process("line 1\r\nline 2\r\nline 3\r\n");
+        let update = renderer.update();
+        assert_eq!(
+            (update.from_line, update.text.as_str()),
+            (0, "line 1\nline 2\nline 3\n")
+        );
+
+        renderer.process("line 4\r\n");
+        let update = renderer.update();
+        assert_eq!(
+            (update.from_line, update.text.as_str()),
+            (2, "line 3\nline 4\n")
+        );
+    }
+
+    #[test]
+    fn test_updates_with_full_scrollback() {
+        let num_lines = MAX_SCROLLBACK_LINES + 30;
+        let lines = (1..=num_lines)
+            .map(|i| format!("line {i}\r\n"))
+            .collect::<Vec<String>>();
+        let chunks = lines.chunks(1_000).map(|c| c.concat()).collect::<Vec<_>>();
+        let chunks = chunks.iter().map(String::as_str).collect::<Vec<&str>>();
+        let text = render_updates(&chunks, TerminalSize::default());
+        // a full render only has room for the last lines by now, but nothing that
+        // was already sent gets taken back
+        assert_eq!(text.lines().count(), num_lines);
+        assert!(text.ends_with(&format!("line {num_lines}\n")));
+    }
+
+    #[test]
+    fn test_cast_resize() {
+        let cast =
+            AsciiCastData::load("./api/sample-terminal-sessions/resize.cast").unwrap();
+        assert_eq!(
+            clean_cast_output(&cast),
+            "bash-5.2# stty size\n40 120\nbash-5.2# "
+        );
+    }
+}
@@ -0,0 +1,88 @@
+use crate::commands::errors::ZammResult;
+use crate::commands::terminal::send_input::update_stored_cast;
+use crate::models::asciicasts::TerminalSize;
+use crate::models::llm_calls::EntityId;
+use crate::{ZammDatabase, ZammTerminalSessions};
+use anyhow::anyhow;
+use specta::specta;
+use tauri::State;
+use uuid::Uuid;
+
+async fn resize_terminal_session_helper(
+    zamm_db: &ZammDatabase,
+    zamm_sessions: &ZammTerminalSessions,
+    session_id: &Uuid,
+    size: TerminalSize,
+) -> ZammResult<()> {
+    let db = &mut zamm_db.0.lock().await;
+    let mut sessions = zamm_sessions.0.lock().await;
+    let session_entity_id = EntityId { uuid: *session_id };
+    let terminal = sessions
+        .get_mut(&session_entity_id)
+        .ok_or_else(|| anyhow!("No session found"))?;
+    terminal.resize(size)?;
+
+    if let Some(conn) = db.as_mut() {
+        let cast = terminal.get_cast()?;
+        if update_stored_cast(conn, &session_entity_id, &cast)? == 0 {
+            return Err(anyhow!("Couldn't update session in database").into());
+        }
+    }
+
+    Ok(())
+}
+
+#[tauri::command(async)]
+#[specta]
+pub async fn resize_terminal_session(
+    database: State<'_, ZammDatabase>,
+    sessions: State<'_, ZammTerminalSessions>,
+    session_id: Uuid,
+    size: TerminalSize,
+) -> ZammResult<()> {
+    resize_terminal_session_helper(&database, &sessions, &session_id, size).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::test_helpers::SideEffectsHelpers;
+    use crate::{check_sample, impl_result_test_case};
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    struct ResizeTerminalSessionRequest {
+        session_id: Uuid,
+        size: TerminalSize,
+    }
+
+    async fn make_request_helper(
+        args: &ResizeTerminalSessionRequest,
+        side_effects: &mut SideEffectsHelpers,
+    ) -> ZammResult<()> {
+        let terminal_helper = side_effects.terminal.as_mut().unwrap();
+        terminal_helper.change_mock_id(args.session_id).await;
+        resize_terminal_session_helper(
+            side_effects.db.as_mut().unwrap(),
+            &terminal_helper.sessions,
+            &args.session_id,
+            args.size,
+        )
+        .await
+    }
+
+    impl_result_test_case!(
+        ResizeTerminalSessionTestCase,
+        resize_terminal_session,
+        true,
+        ResizeTerminalSessionRequest,
+        ()
+    );
+
+    check_sample!(
+        ResizeTerminalSessionTestCase,
+        test_bash_resize,
+        "./api/sample-calls/resize_terminal_session-bash.yaml"
+    );
+}
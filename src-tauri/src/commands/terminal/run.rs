@@ -1,8 +1,10 @@
 use crate::commands::errors::ZammResult;
+use crate::commands::search::index_terminal_session;
+use crate::commands::terminal::events::output_listener;
 use crate::commands::terminal::models::TerminalSessionInfo;
-use crate::commands::terminal::parse::clean_output;
+use crate::commands::terminal::render::clean_output;
 use crate::commands::terminal::ActualTerminal;
-use crate::models::asciicasts::NewAsciiCast;
+use crate::models::asciicasts::{NewAsciiCast, TerminalSize};
 use crate::models::llm_calls::EntityId;
 use crate::models::os::get_os;
 use crate::schema::asciicasts::{self};
@@ -17,6 +19,7 @@ async fn run_command_helper(
     zamm_sessions: &ZammTerminalSessions,
     session_id: &EntityId,
     command: &str,
+    size: TerminalSize,
 ) -> ZammResult<TerminalSessionInfo> {
     let db = &mut zamm_db.0.lock().await;
     let mut sessions = zamm_sessions.0.lock().await;
@@ -24,7 +27,7 @@ async fn run_command_helper(
         .get_mut(session_id)
         .ok_or_else(|| anyhow!("No session found"))?;
 
-    let raw_output = terminal.run_command(command)?;
+    let raw_output = terminal.run_command(command, size)?;
     let cast = terminal.get_cast()?;
     let timestamp = cast
         .header
@@ -43,6 +46,7 @@ async fn run_command_helper(
                 cast: &cast,
             })
             .execute(conn)?;
+        index_terminal_session(conn, session_id, &cast)?;
     }
 
     let output = clean_output(&raw_output);
@@ -59,19 +63,31 @@ async fn run_command_helper(
 #[tauri::command(async)]
 #[specta]
 pub async fn run_command(
+    app_handle: tauri::AppHandle,
     database: State<'_, ZammDatabase>,
     sessions: State<'_, ZammTerminalSessions>,
     command: String,
+    size: Option<TerminalSize>,
 ) -> ZammResult<TerminalSessionInfo> {
-    let terminal = ActualTerminal::new();
     let new_session_id = EntityId::new();
+    let terminal = ActualTerminal::with_output_listener(output_listener(
+        app_handle,
+        new_session_id.clone(),
+    ));
     sessions
         .0
         .lock()
         .await
         .insert(new_session_id.clone(), Box::new(terminal));
 
-    run_command_helper(&database, &sessions, &new_session_id, &command).await
+    run_command_helper(
+        &database,
+        &sessions,
+        &new_session_id,
+        &command,
+        size.unwrap_or_default(),
+    )
+    .await
 }
 
 #[cfg(test)]
@@ -88,6 +104,7 @@ mod tests {
     #[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
     struct RunCommandRequest {
         command: String,
+        size: Option<TerminalSize>,
     }
 
     struct RunCommandTestCase {
@@ -123,6 +140,7 @@ mod tests {
                 &terminal_helper.sessions,
                 &terminal_helper.mock_session_id,
                 &args.command,
+                args.size.unwrap_or_default(),
             )
             .await
         }
@@ -203,6 +221,12 @@ mod tests {
         "./api/sample-calls/run_command-bash.yaml"
     );
 
+    check_sample!(
+        RunCommandTestCase,
+        test_unicode_output,
+        "./api/sample-calls/run_command-unicode.yaml"
+    );
+
     check_sample!(
         RunCommandTestCase,
         test_start_cmd,
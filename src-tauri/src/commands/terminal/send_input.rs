@@ -1,5 +1,7 @@
 use crate::commands::errors::ZammResult;
-use crate::commands::terminal::parse::clean_output;
+use crate::commands::search::index_terminal_session;
+use crate::commands::terminal::render::clean_output;
+use crate::models::asciicasts::AsciiCastData;
 use crate::models::llm_calls::EntityId;
 use crate::schema::asciicasts;
 
@@ -10,6 +12,35 @@ use specta::specta;
 use tauri::State;
 use uuid::Uuid;
 
+/// Returns how many sessions got updated, which is zero if the session hasn't
+/// been saved to the database yet.
+pub(super) fn update_stored_cast(
+    conn: &mut SqliteConnection,
+    session_id: &EntityId,
+    cast: &AsciiCastData,
+) -> ZammResult<usize> {
+    let num_updated = diesel::update(asciicasts::table)
+        .filter(asciicasts::id.eq(session_id))
+        .set(asciicasts::cast.eq(cast))
+        .execute(conn)?;
+    Ok(num_updated)
+}
+
+/// Like `update_stored_cast`, but also brings the search index up to date. That
+/// means going over the entire session again, so it's only worth doing when the
+/// user sends input or the session ends.
+pub(super) fn update_and_index_stored_cast(
+    conn: &mut SqliteConnection,
+    session_id: &EntityId,
+    cast: &AsciiCastData,
+) -> ZammResult<usize> {
+    let num_updated = update_stored_cast(conn, session_id, cast)?;
+    if num_updated > 0 {
+        index_terminal_session(conn, session_id, cast)?;
+    }
+    Ok(num_updated)
+}
+
 async fn send_command_input_helper(
     zamm_db: &ZammDatabase,
     zamm_sessions: &ZammTerminalSessions,
@@ -30,11 +61,8 @@ async fn send_command_input_helper(
     let output = clean_output(&raw_output);
 
     if let Some(conn) = db.as_mut() {
-        let db_update_result = diesel::update(asciicasts::table)
-            .filter(asciicasts::id.eq(session_entity_id))
-            .set(asciicasts::cast.eq(terminal.get_cast()?))
-            .execute(conn)?;
-        if db_update_result == 0 {
+        let cast = terminal.get_cast()?;
+        if update_and_index_stored_cast(conn, &session_entity_id, &cast)? == 0 {
             return Err(anyhow!("Couldn't update session in database").into());
         }
     }
@@ -0,0 +1,244 @@
+use crate::commands::errors::ZammResult;
+use crate::schema::{asciicasts, llm_call_latencies, llm_calls};
+use crate::setup::api_keys::Service;
+use crate::ZammDatabase;
+use anyhow::anyhow;
+use chrono::naive::NaiveDateTime;
+use diesel::dsl::{self, count, count_star, sql};
+use diesel::expression::{is_contained_in_group_by, IsContainedInGroupBy, SqlLiteral};
+use diesel::prelude::*;
+use diesel::sql_types::Text;
+use serde::{Deserialize, Serialize};
+use specta::specta;
+use tauri::State;
+
+// Diesel can only check GROUP BY clauses made up of columns, so it has to be told
+// that grouping by the date of a call isn't the same as grouping by these
+impl IsContainedInGroupBy<llm_calls::provider> for SqlLiteral<Text> {
+    type Output = is_contained_in_group_by::No;
+}
+
+impl IsContainedInGroupBy<llm_calls::llm> for SqlLiteral<Text> {
+    type Output = is_contained_in_group_by::No;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, specta::Type)]
+pub enum UsagePeriod {
+    Day,
+    /// Weeks start on Monday
+    Week,
+}
+
+impl UsagePeriod {
+    /// SQLite expression for the date that the period containing a call starts on.
+    fn call_period_start(&self) -> SqlLiteral<Text> {
+        match self {
+            UsagePeriod::Day => sql::<Text>("date(llm_calls.timestamp)"),
+            UsagePeriod::Week => {
+                sql::<Text>("date(llm_calls.timestamp, 'weekday 0', '-6 days')")
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, specta::Type)]
+pub struct LlmCallUsage {
+    /// Date that the day or week starts on, in YYYY-MM-DD format
+    pub period_start: String,
+    pub provider: Service,
+    pub llm: String,
+    pub num_calls: i32,
+    pub prompt_tokens: i32,
+    pub response_tokens: i32,
+    /// Only calls made from within ZAMM have their latency recorded
+    pub average_latency_ms: Option<f64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, specta::Type)]
+pub struct TerminalSessionUsage {
+    pub date: String,
+    pub num_sessions: i32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, specta::Type)]
+pub struct UsageStats {
+    pub llm_calls: Vec<LlmCallUsage>,
+    pub terminal_sessions: Vec<TerminalSessionUsage>,
+}
+
+type LlmCallUsageRow = (
+    String,
+    Service,
+    String,
+    i64,
+    Option<i64>,
+    Option<i64>,
+    Option<i64>,
+    i64,
+);
+
+impl From<LlmCallUsageRow> for LlmCallUsage {
+    fn from(row: LlmCallUsageRow) -> Self {
+        let (
+            period_start,
+            provider,
+            llm,
+            num_calls,
+            prompt_tokens,
+            response_tokens,
+            total_latency_ms,
+            num_latencies,
+        ) = row;
+        LlmCallUsage {
+            period_start,
+            provider,
+            llm,
+            num_calls: num_calls as i32,
+            prompt_tokens: prompt_tokens.unwrap_or(0) as i32,
+            response_tokens: response_tokens.unwrap_or(0) as i32,
+            average_latency_ms: total_latency_ms
+                .filter(|_| num_latencies > 0)
+                .map(|total| total as f64 / num_latencies as f64),
+        }
+    }
+}
+
+async fn get_usage_stats_helper(
+    zamm_db: &ZammDatabase,
+    start_time: Option<NaiveDateTime>,
+    end_time: Option<NaiveDateTime>,
+    period: UsagePeriod,
+) -> ZammResult<UsageStats> {
+    let mut db = zamm_db.0.lock().await;
+    let conn = db.as_mut().ok_or(anyhow!("Failed to lock database"))?;
+
+    let period_start = period.call_period_start();
+    let mut llm_calls_query = llm_calls::table
+        .left_join(llm_call_latencies::table)
+        .group_by((period_start.clone(), llm_calls::provider, llm_calls::llm))
+        .select((
+            period_start.clone(),
+            llm_calls::provider,
+            llm_calls::llm,
+            count_star(),
+            dsl::sum(llm_calls::prompt_tokens),
+            dsl::sum(llm_calls::response_tokens),
+            dsl::sum(llm_call_latencies::latency_ms.nullable()),
+            count(llm_call_latencies::latency_ms.nullable()),
+        ))
+        .order_by((period_start, llm_calls::provider, llm_calls::llm))
+        .into_boxed();
+    if let Some(start_time) = start_time {
+        llm_calls_query = llm_calls_query.filter(llm_calls::timestamp.ge(start_time));
+    }
+    if let Some(end_time) = end_time {
+        llm_calls_query = llm_calls_query.filter(llm_calls::timestamp.lt(end_time));
+    }
+    let llm_calls = llm_calls_query
+        .load::<LlmCallUsageRow>(conn)?
+        .into_iter()
+        .map(LlmCallUsage::from)
+        .collect();
+
+    let session_date = sql::<Text>("date(asciicasts.timestamp)");
+    let mut sessions_query = asciicasts::table
+        .group_by(session_date.clone())
+        .select((session_date.clone(), count_star()))
+        .order_by(session_date)
+        .into_boxed();
+    if let Some(start_time) = start_time {
+        sessions_query = sessions_query.filter(asciicasts::timestamp.ge(start_time));
+    }
+    if let Some(end_time) = end_time {
+        sessions_query = sessions_query.filter(asciicasts::timestamp.lt(end_time));
+    }
+    let terminal_sessions = sessions_query
+        .load::<(String, i64)>(conn)?
+        .into_iter()
+        .map(|(date, num_sessions)| TerminalSessionUsage {
+            date,
+            num_sessions: num_sessions as i32,
+        })
+        .collect();
+
+    Ok(UsageStats {
+        llm_calls,
+        terminal_sessions,
+    })
+}
+
+#[tauri::command(async)]
+#[specta]
+pub async fn get_usage_stats(
+    database: State<'_, ZammDatabase>,
+    start_time: Option<NaiveDateTime>,
+    end_time: Option<NaiveDateTime>,
+    period: UsagePeriod,
+) -> ZammResult<UsageStats> {
+    get_usage_stats_helper(&database, start_time, end_time, period).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::llm_calls::{EntityId, NewLlmCallLatency};
+    use crate::schema::llm_call_latencies;
+    use crate::test_helpers::SideEffectsHelpers;
+    use crate::{check_sample, impl_result_test_case};
+
+    // latencies are left out of sample database dumps because they differ every
+    // time a call is recorded, so they get inserted here instead
+    const SAMPLE_LATENCIES: [(&str, i32); 3] = [
+        ("d5ad1e49-f57f-4481-84fb-4d70ba8a7a74", 1200),
+        ("c13c1e67-2de3-48de-a34c-a32079c03316", 1800),
+        ("506e2d1f-549c-45cc-ad65-57a0741f06ee", 4500),
+    ];
+
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    struct GetUsageStatsRequest {
+        start_time: Option<NaiveDateTime>,
+        end_time: Option<NaiveDateTime>,
+        period: UsagePeriod,
+    }
+
+    async fn make_request_helper(
+        args: &GetUsageStatsRequest,
+        side_effects: &mut SideEffectsHelpers,
+    ) -> ZammResult<UsageStats> {
+        let zamm_db = side_effects.db.as_ref().unwrap();
+        {
+            let mut db = zamm_db.0.lock().await;
+            let conn = db.as_mut().unwrap();
+            for (id, latency_ms) in SAMPLE_LATENCIES {
+                diesel::insert_into(llm_call_latencies::table)
+                    .values(NewLlmCallLatency {
+                        llm_call_id: &EntityId::try_from(id).unwrap(),
+                        latency_ms,
+                    })
+                    .execute(conn)?;
+            }
+        }
+        get_usage_stats_helper(zamm_db, args.start_time, args.end_time, args.period)
+            .await
+    }
+
+    impl_result_test_case!(
+        GetUsageStatsTestCase,
+        get_usage_stats,
+        true,
+        GetUsageStatsRequest,
+        UsageStats
+    );
+
+    check_sample!(
+        GetUsageStatsTestCase,
+        test_by_day,
+        "./api/sample-calls/get_usage_stats-day.yaml"
+    );
+
+    check_sample!(
+        GetUsageStatsTestCase,
+        test_by_week_in_range,
+        "./api/sample-calls/get_usage_stats-week-range.yaml"
+    );
+}
@@ -20,20 +20,31 @@ use commands::terminal::Terminal;
 use diesel::sqlite::SqliteConnection;
 use futures::executor;
 use models::llm_calls::EntityId;
-use setup::api_keys::{setup_api_keys, ApiKeys};
+use setup::api_keys::{dotenv_files, setup_api_keys, ApiKeys};
+use setup::encryption::unlock_at_startup;
 #[cfg(debug_assertions)]
 use specta_typescript::Typescript;
 use tauri::Manager;
 #[cfg(debug_assertions)]
-use tauri_specta::{collect_commands, Builder};
+use tauri_specta::{collect_commands, collect_events, Builder};
 use tokio::sync::Mutex;
 
 use cli::{Cli, Commands};
+use commands::llms::embeddings::spawn_embedding_backfill;
+use commands::llms::trash::purge_expired_trash;
 use commands::preferences::get_preferences_file_contents;
+#[cfg(debug_assertions)]
+use commands::TerminalOutput;
 use commands::{
-    chat, export_db, get_api_call, get_api_calls, get_api_keys, get_preferences,
-    get_system_info, get_terminal_session, get_terminal_sessions, import_db,
-    play_sound, run_command, send_command_input, set_api_key, set_preferences,
+    chat, clear_api_call_rating, delete_api_call, delete_conversation,
+    export_conversation, export_db, export_fine_tuning_data, get_api_call,
+    get_api_calls, get_api_calls_page, get_api_keys, get_preferences, get_system_info,
+    get_terminal_session, get_terminal_sessions, get_terminal_sessions_page,
+    get_usage_stats, import_chatgpt, import_db, import_jsonl, play_sound,
+    purge_deleted_api_calls, resize_terminal_session, restore_api_calls, run_command,
+    search, semantic_search, send_command_input, set_active_api_key_profile,
+    set_api_call_rating, set_api_key, set_api_key_options, set_api_key_passphrase,
+    set_api_key_profile, set_preferences, test_api_key, unlock_api_keys,
 };
 use upgrades::handle_app_upgrades;
 
@@ -50,6 +61,12 @@ fn main() {
             let builder = Builder::<tauri::Wry>::new().commands(collect_commands![
                 get_api_keys,
                 set_api_key,
+                set_api_key_options,
+                set_api_key_profile,
+                set_active_api_key_profile,
+                unlock_api_keys,
+                set_api_key_passphrase,
+                test_api_key,
                 play_sound,
                 get_preferences,
                 set_preferences,
@@ -57,21 +74,40 @@ fn main() {
                 chat,
                 get_api_call,
                 get_api_calls,
+                get_api_calls_page,
+                delete_api_call,
+                delete_conversation,
+                export_conversation,
+                purge_deleted_api_calls,
+                restore_api_calls,
+                set_api_call_rating,
+                clear_api_call_rating,
                 import_db,
+                import_chatgpt,
+                import_jsonl,
                 export_db,
+                export_fine_tuning_data,
                 run_command,
                 send_command_input,
+                resize_terminal_session,
                 get_terminal_session,
                 get_terminal_sessions,
+                get_terminal_sessions_page,
+                search,
+                semantic_search,
+                get_usage_stats,
             ]);
             builder
+                .events(collect_events![TerminalOutput])
                 .export(Typescript::default(), "../src-svelte/src/lib/bindings.ts")
                 .expect("Failed to export Specta bindings");
             println!("Specta bindings should be exported to ../src-svelte/src/lib/bindings.ts");
         }
         Some(Commands::Gui {}) | None => {
             let mut possible_db = setup::get_db();
-            let api_keys = setup_api_keys(&mut possible_db);
+            let key_cipher = unlock_at_startup(&mut possible_db);
+            // .env files get loaded in once the config dir is known
+            let api_keys = setup_api_keys(&mut possible_db, key_cipher, Vec::new());
             let terminal_sessions = HashMap::new();
 
             tauri::Builder::default()
@@ -89,6 +125,22 @@ fn main() {
                     });
 
                     let prefs = get_preferences_file_contents(&config_dir)?;
+                    let dotenv_files = dotenv_files(&config_dir, &prefs);
+                    executor::block_on(async {
+                        let zamm_api_keys = app.state::<ZammApiKeys>();
+                        zamm_api_keys.0.lock().await.load_environment(dotenv_files);
+                    });
+                    executor::block_on(async {
+                        if let Err(e) =
+                            purge_expired_trash(&zamm_db, prefs.trash_retention_days)
+                                .await
+                        {
+                            eprintln!("Couldn't empty expired trash: {e}");
+                        }
+                    });
+
+                    spawn_embedding_backfill(app.handle());
+
                     #[cfg(target_os = "macos")]
                     let high_dpi_adjust_on = prefs.high_dpi_adjust.unwrap_or(true);
                     #[cfg(not(target_os = "macos"))]
@@ -120,6 +172,12 @@ fn main() {
                 .invoke_handler(tauri::generate_handler![
                     get_api_keys,
                     set_api_key,
+                    set_api_key_options,
+                    set_api_key_profile,
+                    set_active_api_key_profile,
+                    unlock_api_keys,
+                    set_api_key_passphrase,
+                    test_api_key,
                     play_sound,
                     get_preferences,
                     set_preferences,
@@ -127,12 +185,28 @@ fn main() {
                     chat,
                     get_api_call,
                     get_api_calls,
+                    get_api_calls_page,
+                    delete_api_call,
+                    delete_conversation,
+                    export_conversation,
+                    purge_deleted_api_calls,
+                    restore_api_calls,
+                    set_api_call_rating,
+                    clear_api_call_rating,
                     import_db,
+                    import_chatgpt,
+                    import_jsonl,
                     export_db,
+                    export_fine_tuning_data,
                     run_command,
                     send_command_input,
+                    resize_terminal_session,
                     get_terminal_session,
                     get_terminal_sessions,
+                    get_terminal_sessions_page,
+                    search,
+                    semantic_search,
+                    get_usage_stats,
                 ])
                 .run(tauri::generate_context!())
                 .expect("error while running tauri application");
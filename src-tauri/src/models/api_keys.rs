@@ -1,5 +1,7 @@
-use crate::schema::api_keys;
-use crate::setup::api_keys::Service;
+use crate::schema::{
+    active_api_key_profiles, api_key_encryption, api_key_options, api_keys,
+};
+use crate::setup::api_keys::{ApiKeyOptions, Service, DEFAULT_PROFILE};
 use diesel::backend::Backend;
 use diesel::deserialize::{self, FromSql};
 use diesel::prelude::*;
@@ -11,13 +13,27 @@ use std::str::FromStr;
 #[derive(Queryable, Selectable, Debug, serde::Serialize, serde::Deserialize)]
 pub struct ApiKey {
     pub service: Service,
+    #[serde(
+        skip_serializing_if = "is_default_profile",
+        default = "default_profile"
+    )]
+    pub profile: String,
     pub api_key: String,
 }
 
+fn is_default_profile(profile: &str) -> bool {
+    profile == DEFAULT_PROFILE
+}
+
+fn default_profile() -> String {
+    DEFAULT_PROFILE.to_string()
+}
+
 impl ApiKey {
     pub fn as_insertable(&self) -> NewApiKey {
         NewApiKey {
             service: self.service.clone(),
+            profile: &self.profile,
             api_key: &self.api_key,
         }
     }
@@ -27,9 +43,53 @@ impl ApiKey {
 #[diesel(table_name = api_keys)]
 pub struct NewApiKey<'a> {
     pub service: Service,
+    pub profile: &'a str,
     pub api_key: &'a str,
 }
 
+/// The profile that a service uses by default, if it's not the default profile.
+#[derive(Queryable, Selectable, Insertable, Debug)]
+#[diesel(table_name = active_api_key_profiles)]
+pub struct ActiveApiKeyProfile {
+    pub service: Service,
+    pub profile: String,
+}
+
+/// Present only when stored keys are encrypted. The verifier is a known value
+/// encrypted with the passphrase, so that a wrong passphrase can be detected
+/// before it gets used on the actual keys.
+#[derive(
+    Queryable,
+    Selectable,
+    Insertable,
+    Debug,
+    Clone,
+    PartialEq,
+    serde::Serialize,
+    serde::Deserialize,
+)]
+#[diesel(table_name = api_key_encryption)]
+pub struct ApiKeyEncryption {
+    pub salt: String,
+    pub verifier: String,
+}
+
+#[derive(Queryable, Selectable, Debug)]
+#[diesel(table_name = api_key_options)]
+pub struct ApiKeyOptionsRow {
+    pub service: Service,
+    pub profile: String,
+    pub options: ApiKeyOptions,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = api_key_options)]
+pub struct NewApiKeyOptions<'a> {
+    pub service: Service,
+    pub profile: &'a str,
+    pub options: &'a ApiKeyOptions,
+}
+
 impl ToSql<Text, Sqlite> for Service
 where
     String: ToSql<Text, Sqlite>,
@@ -53,6 +113,29 @@ where
     }
 }
 
+impl ToSql<Text, Sqlite> for ApiKeyOptions
+where
+    String: ToSql<Text, Sqlite>,
+{
+    fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Sqlite>) -> serialize::Result {
+        let json_str = serde_json::to_string(&self)?;
+        out.set_value(json_str);
+        Ok(IsNull::No)
+    }
+}
+
+impl<DB> FromSql<Text, DB> for ApiKeyOptions
+where
+    DB: Backend,
+    String: FromSql<Text, DB>,
+{
+    fn from_sql(bytes: DB::RawValue<'_>) -> deserialize::Result<Self> {
+        let json_str = String::from_sql(bytes)?;
+        let parsed_json: Self = serde_json::from_str(&json_str)?;
+        Ok(parsed_json)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -65,6 +148,7 @@ mod tests {
 
         let openai_api_key = NewApiKey {
             service: Service::OpenAI,
+            profile: DEFAULT_PROFILE,
             api_key: dummy_api_key,
         };
 
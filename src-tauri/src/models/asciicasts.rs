@@ -5,7 +5,7 @@ use crate::models::llm_calls::EntityId;
 use crate::models::os::OS;
 use crate::schema::asciicasts;
 use anyhow::anyhow;
-use asciicast::{Entry, Header};
+use asciicast::Header;
 use chrono::naive::NaiveDateTime;
 use diesel::backend::Backend;
 use diesel::deserialize::{self, FromSql, FromSqlRow};
@@ -19,6 +19,86 @@ use std::fmt;
 use std::fmt::{Display, Formatter};
 use std::str::FromStr;
 
+/// The size of a terminal, in characters.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    serde::Serialize,
+    serde::Deserialize,
+    specta::Type,
+)]
+pub struct TerminalSize {
+    pub rows: u16,
+    pub cols: u16,
+}
+
+impl Default for TerminalSize {
+    fn default() -> Self {
+        Self { rows: 24, cols: 80 }
+    }
+}
+
+impl Display for TerminalSize {
+    /// The format that asciicast resize events use.
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}x{}", self.cols, self.rows)
+    }
+}
+
+impl FromStr for TerminalSize {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid_size = || anyhow!("Invalid terminal size: {}", s);
+        let (cols, rows) = s.split_once('x').ok_or_else(invalid_size)?;
+        Ok(Self {
+            rows: rows.parse().map_err(|_| invalid_size())?,
+            cols: cols.parse().map_err(|_| invalid_size())?,
+        })
+    }
+}
+
+/// Like `asciicast::EventType`, except with resizes, which that crate doesn't
+/// know about.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum EventType {
+    #[serde(rename = "o")]
+    Output,
+    #[serde(rename = "i")]
+    Input,
+    #[serde(rename = "r")]
+    Resize,
+}
+
+/// Gets saved as a `[time, event_type, event_data]` array, same as
+/// `asciicast::Entry`.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(from = "(f64, EventType, String)", into = "(f64, EventType, String)")]
+pub struct Entry {
+    pub time: f64,
+    pub event_type: EventType,
+    pub event_data: String,
+}
+
+impl From<(f64, EventType, String)> for Entry {
+    fn from((time, event_type, event_data): (f64, EventType, String)) -> Self {
+        Self {
+            time,
+            event_type,
+            event_data,
+        }
+    }
+}
+
+impl From<Entry> for (f64, EventType, String) {
+    fn from(entry: Entry) -> Self {
+        (entry.time, entry.event_type, entry.event_data)
+    }
+}
+
 #[derive(
     Debug,
     Clone,
@@ -36,17 +116,17 @@ pub struct AsciiCastData {
 
 impl Default for AsciiCastData {
     fn default() -> Self {
-        Self::new()
+        Self::new(TerminalSize::default())
     }
 }
 
 impl AsciiCastData {
-    pub fn new() -> Self {
+    pub fn new(size: TerminalSize) -> Self {
         Self {
             header: Header {
                 version: 2,
-                width: 80,
-                height: 24,
+                width: size.cols.into(),
+                height: size.rows.into(),
                 timestamp: None,
                 duration: None,
                 idle_time_limit: None,
@@ -58,6 +138,18 @@ impl AsciiCastData {
         }
     }
 
+    pub fn size(&self) -> TerminalSize {
+        TerminalSize {
+            rows: self.header.height.try_into().unwrap_or(u16::MAX),
+            cols: self.header.width.try_into().unwrap_or(u16::MAX),
+        }
+    }
+
+    pub fn set_size(&mut self, size: TerminalSize) {
+        self.header.width = size.cols.into();
+        self.header.height = size.rows.into();
+    }
+
     #[cfg(test)]
     pub fn load(file: &str) -> ZammResult<Self> {
         let contents = std::fs::read_to_string(file)?;
@@ -156,7 +248,6 @@ where
 mod tests {
     use super::*;
     use crate::test_helpers::database::setup_database;
-    use asciicast::{Entry, EventType, Header};
     use chrono::{DateTime, SubsecRound};
     use uuid::Uuid;
 
@@ -203,6 +294,30 @@ mod tests {
         assert_eq!(deserialized, get_test_ascii_cast());
     }
 
+    #[test]
+    fn test_resize_entry() {
+        let entry = Entry {
+            time: 1.0,
+            event_type: EventType::Resize,
+            event_data: TerminalSize {
+                rows: 40,
+                cols: 120,
+            }
+            .to_string(),
+        };
+        let serialized = serde_json::to_string(&entry).unwrap();
+        assert_eq!(serialized, r#"[1.0,"r","120x40"]"#);
+        assert_eq!(serde_json::from_str::<Entry>(&serialized).unwrap(), entry);
+        assert_eq!(
+            entry.event_data.parse::<TerminalSize>().unwrap(),
+            TerminalSize {
+                rows: 40,
+                cols: 120,
+            }
+        );
+        assert!("120 by 40".parse::<TerminalSize>().is_err());
+    }
+
     #[test]
     fn test_ascii_cast_round_trip() {
         let mut conn = setup_database(None);
@@ -1,10 +1,13 @@
 use crate::models::asciicasts::{AsciiCast, NewAsciiCast};
 use crate::models::llm_calls::{
-    LlmCallFollowUp, LlmCallRow, LlmCallVariant, NewLlmCallFollowUp, NewLlmCallRow,
-    NewLlmCallVariant,
+    LlmCallAttributionRow, LlmCallDeployment, LlmCallFollowUp, LlmCallKeyProfile,
+    LlmCallLatency, LlmCallRating, LlmCallRow, LlmCallVariant, NewLlmCallFollowUp,
+    NewLlmCallRating, NewLlmCallRow, NewLlmCallVariant,
 };
-use crate::models::{ApiKey, NewApiKey};
+use crate::models::{ApiKey, ApiKeyEncryption, NewApiKey};
 
+/// Embeddings are left out, since they're only of use to the embedding model that
+/// made them, and get computed again in the background after an import.
 #[derive(Debug, Default, serde::Serialize, serde::Deserialize)]
 pub struct LlmCallData {
     pub instances: Vec<LlmCallRow>,
@@ -12,6 +15,16 @@ pub struct LlmCallData {
     pub follow_ups: Vec<LlmCallFollowUp>,
     #[serde(skip_serializing_if = "Vec::is_empty", default)]
     pub variants: Vec<LlmCallVariant>,
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub ratings: Vec<LlmCallRating>,
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub latencies: Vec<LlmCallLatency>,
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub attributions: Vec<LlmCallAttributionRow>,
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub key_profiles: Vec<LlmCallKeyProfile>,
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub deployments: Vec<LlmCallDeployment>,
 }
 
 impl LlmCallData {
@@ -19,6 +32,11 @@ impl LlmCallData {
         self.instances.is_empty()
             && self.follow_ups.is_empty()
             && self.variants.is_empty()
+            && self.ratings.is_empty()
+            && self.latencies.is_empty()
+            && self.attributions.is_empty()
+            && self.key_profiles.is_empty()
+            && self.deployments.is_empty()
     }
 }
 
@@ -28,6 +46,9 @@ pub struct DatabaseContents {
     pub zamm_version: Option<String>,
     #[serde(skip_serializing_if = "Vec::is_empty", default)]
     pub api_keys: Vec<ApiKey>,
+    /// Needed to unlock any of the API keys that are encrypted
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub api_key_encryption: Option<ApiKeyEncryption>,
     #[serde(skip_serializing_if = "LlmCallData::is_default", default)]
     pub llm_calls: LlmCallData,
     #[serde(skip_serializing_if = "Vec::is_empty", default)]
@@ -63,6 +84,14 @@ impl DatabaseContents {
             .collect()
     }
 
+    pub fn insertable_call_ratings(&self) -> Vec<NewLlmCallRating> {
+        self.llm_calls
+            .ratings
+            .iter()
+            .map(|k| k.as_insertable())
+            .collect()
+    }
+
     pub fn insertable_terminal_sessions(&self) -> Vec<NewAsciiCast> {
         self.terminal_sessions
             .iter()
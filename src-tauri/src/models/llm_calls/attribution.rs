@@ -0,0 +1,63 @@
+use crate::models::llm_calls::entity_id::EntityId;
+use crate::schema::llm_call_attributions;
+use crate::setup::api_keys::ApiKeyOptions;
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Who a call was billed to. Only the names of custom headers get recorded,
+/// because their values might be secret.
+#[derive(
+    Debug, Clone, PartialEq, Queryable, Selectable, Serialize, Deserialize, specta::Type,
+)]
+#[diesel(table_name = llm_call_attributions)]
+pub struct LlmCallAttribution {
+    pub organization: Option<String>,
+    pub project: Option<String>,
+    pub extra_header_names: Option<String>,
+}
+
+/// The full row, including which call it belongs to.
+#[derive(
+    Debug, Clone, PartialEq, Queryable, Selectable, Insertable, Serialize, Deserialize,
+)]
+#[diesel(table_name = llm_call_attributions)]
+pub struct LlmCallAttributionRow {
+    pub llm_call_id: EntityId,
+    pub organization: Option<String>,
+    pub project: Option<String>,
+    pub extra_header_names: Option<String>,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = llm_call_attributions)]
+pub struct NewLlmCallAttribution<'a> {
+    pub llm_call_id: &'a EntityId,
+    pub organization: Option<&'a str>,
+    pub project: Option<&'a str>,
+    pub extra_header_names: Option<String>,
+}
+
+impl<'a> NewLlmCallAttribution<'a> {
+    /// Returns nothing if there's nothing to attribute the call to.
+    pub fn from_options(
+        llm_call_id: &'a EntityId,
+        options: &'a ApiKeyOptions,
+    ) -> Option<Self> {
+        if options.is_default() {
+            return None;
+        }
+        let extra_header_names = if options.extra_headers.is_empty() {
+            None
+        } else {
+            let names: Vec<&str> =
+                options.extra_headers.keys().map(|k| k.as_str()).collect();
+            Some(names.join(", "))
+        };
+        Some(NewLlmCallAttribution {
+            llm_call_id,
+            organization: options.organization.as_deref(),
+            project: options.project.as_deref(),
+            extra_header_names,
+        })
+    }
+}
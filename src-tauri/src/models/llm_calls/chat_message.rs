@@ -35,6 +35,16 @@ pub enum ChatMessage {
     AI { text: String },
 }
 
+impl ChatMessage {
+    pub fn text(&self) -> &str {
+        match self {
+            ChatMessage::System { text }
+            | ChatMessage::Human { text }
+            | ChatMessage::AI { text } => text,
+        }
+    }
+}
+
 impl TryFrom<ChatCompletionRequestMessage> for ChatMessage {
     type Error = Error;
 
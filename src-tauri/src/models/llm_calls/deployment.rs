@@ -0,0 +1,22 @@
+use crate::models::llm_calls::entity_id::EntityId;
+use crate::schema::llm_call_deployments;
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Which Azure deployment a call went to, since the deployment name is what
+/// gets requested but need not match the model behind it.
+#[derive(Insertable)]
+#[diesel(table_name = llm_call_deployments)]
+pub struct NewLlmCallDeployment<'a> {
+    pub llm_call_id: &'a EntityId,
+    pub deployment: &'a str,
+}
+
+#[derive(
+    Debug, Clone, PartialEq, Queryable, Selectable, Insertable, Serialize, Deserialize,
+)]
+#[diesel(table_name = llm_call_deployments)]
+pub struct LlmCallDeployment {
+    pub llm_call_id: EntityId,
+    pub deployment: String,
+}
@@ -0,0 +1,70 @@
+use crate::models::llm_calls::entity_id::EntityId;
+use crate::schema::llm_call_embeddings;
+use diesel::backend::Backend;
+use diesel::deserialize::{self, FromSql, FromSqlRow};
+use diesel::expression::AsExpression;
+use diesel::prelude::*;
+use diesel::serialize::{self, IsNull, Output, ToSql};
+use diesel::sql_types::Text;
+use diesel::sqlite::Sqlite;
+use serde::{Deserialize, Serialize};
+
+/// Which half of a call a vector was computed from.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Eq,
+    PartialEq,
+    Hash,
+    Serialize,
+    Deserialize,
+    AsExpression,
+    FromSqlRow,
+)]
+#[diesel(sql_type = Text)]
+pub enum EmbeddingKind {
+    Prompt,
+    Completion,
+}
+
+/// A vector for a call, encoded as little-endian `f32`s.
+#[derive(
+    Debug, Clone, PartialEq, Queryable, Selectable, Insertable, Serialize, Deserialize,
+)]
+#[diesel(table_name = llm_call_embeddings)]
+pub struct LlmCallEmbedding {
+    pub llm_call_id: EntityId,
+    pub model: String,
+    pub kind: EmbeddingKind,
+    pub embedding: Vec<u8>,
+}
+
+impl ToSql<Text, Sqlite> for EmbeddingKind
+where
+    String: ToSql<Text, Sqlite>,
+{
+    fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Sqlite>) -> serialize::Result {
+        let kind_str = match self {
+            EmbeddingKind::Prompt => "prompt",
+            EmbeddingKind::Completion => "completion",
+        };
+        out.set_value(kind_str);
+        Ok(IsNull::No)
+    }
+}
+
+impl<DB> FromSql<Text, DB> for EmbeddingKind
+where
+    DB: Backend,
+    String: FromSql<Text, DB>,
+{
+    fn from_sql(bytes: DB::RawValue<'_>) -> deserialize::Result<Self> {
+        let kind_str = String::from_sql(bytes)?;
+        match kind_str.as_str() {
+            "prompt" => Ok(EmbeddingKind::Prompt),
+            "completion" => Ok(EmbeddingKind::Completion),
+            _ => Err("Invalid embedding kind string".into()),
+        }
+    }
+}
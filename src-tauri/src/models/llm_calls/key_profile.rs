@@ -0,0 +1,22 @@
+use crate::models::llm_calls::entity_id::EntityId;
+use crate::schema::llm_call_key_profiles;
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Which saved key a call was made with. Only the name of the profile gets
+/// recorded, never the key itself.
+#[derive(Insertable)]
+#[diesel(table_name = llm_call_key_profiles)]
+pub struct NewLlmCallKeyProfile<'a> {
+    pub llm_call_id: &'a EntityId,
+    pub profile: &'a str,
+}
+
+#[derive(
+    Debug, Clone, PartialEq, Queryable, Selectable, Insertable, Serialize, Deserialize,
+)]
+#[diesel(table_name = llm_call_key_profiles)]
+pub struct LlmCallKeyProfile {
+    pub llm_call_id: EntityId,
+    pub profile: String,
+}
@@ -0,0 +1,21 @@
+use crate::models::llm_calls::entity_id::EntityId;
+use crate::schema::llm_call_latencies;
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// How long the provider took to respond to a call made from within ZAMM.
+#[derive(Insertable)]
+#[diesel(table_name = llm_call_latencies)]
+pub struct NewLlmCallLatency<'a> {
+    pub llm_call_id: &'a EntityId,
+    pub latency_ms: i32,
+}
+
+#[derive(
+    Debug, Clone, PartialEq, Queryable, Selectable, Insertable, Serialize, Deserialize,
+)]
+#[diesel(table_name = llm_call_latencies)]
+pub struct LlmCallLatency {
+    pub llm_call_id: EntityId,
+    pub latency_ms: i32,
+}
@@ -10,6 +10,11 @@ pub struct LightweightLlmCall {
     pub id: EntityId,
     pub timestamp: NaiveDateTime,
     pub response_message: ChatMessage,
+    /// The prompt as the model actually saw it, when that differs from the one
+    /// that was sent because attached files got inlined. Follow-up calls should
+    /// continue from this one so that the files stay in context.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prompt: Option<Vec<ChatMessage>>,
 }
 
 impl From<LlmCall> for LightweightLlmCall {
@@ -18,6 +23,7 @@ impl From<LlmCall> for LightweightLlmCall {
             id: value.id,
             timestamp: value.timestamp,
             response_message: value.response.completion,
+            prompt: None,
         }
     }
 }
@@ -28,6 +34,7 @@ impl From<LlmCallRow> for LightweightLlmCall {
             id: value.id,
             timestamp: value.timestamp,
             response_message: value.completion,
+            prompt: None,
         }
     }
 }
@@ -1,3 +1,4 @@
+use crate::models::llm_calls::attribution::LlmCallAttribution;
 use crate::models::llm_calls::chat_message::ChatMessage;
 use crate::models::llm_calls::entity_id::EntityId;
 use crate::models::llm_calls::row::LlmCallRow;
@@ -20,6 +21,8 @@ pub struct LlmCall {
     pub conversation: ConversationMetadata,
     #[serde(skip_serializing_if = "VariantMetadata::is_default", default)]
     pub variation: VariantMetadata,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub attribution: Option<LlmCallAttribution>,
 }
 
 pub type LlmCallLeftJoinResult = (
@@ -56,6 +59,8 @@ impl From<LlmCallQueryResults> for LlmCall {
             name: llm_call_row.llm,
             requested: llm_call_row.llm_requested,
             provider: llm_call_row.provider,
+            deployment: None,
+            key_profile: None,
         };
         let request = Request {
             prompt: llm_call_row.prompt,
@@ -113,6 +118,7 @@ impl From<LlmCallQueryResults> for LlmCall {
             tokens: token_metadata,
             conversation: conversation_metadata,
             variation: variant_metadata,
+            attribution: None,
         }
     }
 }
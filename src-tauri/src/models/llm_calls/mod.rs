@@ -1,21 +1,36 @@
+mod attribution;
 mod chat_message;
+mod deployment;
+mod embedding;
 mod entity_id;
+mod key_profile;
+mod latency;
 mod lightweight_llm_call;
 mod linkage;
 mod llm_call;
 mod prompt;
+mod rating;
 mod row;
 mod various;
 
+pub use attribution::{
+    LlmCallAttribution, LlmCallAttributionRow, NewLlmCallAttribution,
+};
 pub use chat_message::ChatMessage;
+pub use deployment::{LlmCallDeployment, NewLlmCallDeployment};
+pub use embedding::{EmbeddingKind, LlmCallEmbedding};
 pub use entity_id::EntityId;
+pub use key_profile::{LlmCallKeyProfile, NewLlmCallKeyProfile};
+pub use latency::{LlmCallLatency, NewLlmCallLatency};
 pub use lightweight_llm_call::LightweightLlmCall;
 #[allow(unused_imports)]
 pub use linkage::{
     LlmCallFollowUp, LlmCallVariant, NewLlmCallFollowUp, NewLlmCallVariant,
 };
 pub use llm_call::{LlmCall, LlmCallLeftJoinResult};
-pub use prompt::{ChatPrompt, Prompt};
+pub use prompt::{ChatPrompt, FileAttachment, Prompt};
+#[allow(unused_imports)]
+pub use rating::{LlmCallRating, NewLlmCallRating, Rating};
 #[allow(unused_imports)]
 pub use row::{LlmCallRow, NewLlmCallRow};
 pub use various::TokenMetadata;
@@ -12,9 +12,19 @@ use serde::{Deserialize, Serialize};
 use serde_json;
 use std::ops::Deref;
 
+/// A local file whose contents were included in the prompt.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, specta::Type)]
+pub struct FileAttachment {
+    pub path: String,
+    /// Hex-encoded SHA-256 hash of the file contents at the time of the call
+    pub sha256: String,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, specta::Type)]
 pub struct ChatPrompt {
     pub messages: Vec<ChatMessage>,
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub attachments: Vec<FileAttachment>,
 }
 
 #[derive(
@@ -53,7 +63,10 @@ impl TryFrom<Vec<ChatCompletionRequestMessage>> for ChatPrompt {
             .into_iter()
             .map(|message| message.try_into())
             .collect::<Result<Vec<ChatMessage>, Self::Error>>()?;
-        Ok(ChatPrompt { messages })
+        Ok(ChatPrompt {
+            messages,
+            attachments: Vec::new(),
+        })
     }
 }
 
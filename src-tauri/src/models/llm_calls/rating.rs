@@ -0,0 +1,87 @@
+use crate::models::llm_calls::entity_id::EntityId;
+use crate::schema::llm_call_ratings;
+use diesel::backend::Backend;
+use diesel::deserialize::{self, FromSql, FromSqlRow};
+use diesel::expression::AsExpression;
+use diesel::prelude::*;
+use diesel::serialize::{self, IsNull, Output, ToSql};
+use diesel::sql_types::Text;
+use diesel::sqlite::Sqlite;
+use serde::{Deserialize, Serialize};
+
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Eq,
+    PartialEq,
+    Serialize,
+    Deserialize,
+    AsExpression,
+    FromSqlRow,
+    specta::Type,
+)]
+#[diesel(sql_type = Text)]
+pub enum Rating {
+    Good,
+    Bad,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = llm_call_ratings)]
+pub struct NewLlmCallRating<'a> {
+    pub llm_call_id: &'a EntityId,
+    pub rating: &'a Rating,
+    pub ideal_completion: Option<&'a str>,
+}
+
+#[derive(
+    Debug, Queryable, Selectable, Clone, PartialEq, Serialize, Deserialize, specta::Type,
+)]
+#[diesel(table_name = llm_call_ratings)]
+pub struct LlmCallRating {
+    pub llm_call_id: EntityId,
+    pub rating: Rating,
+    /// What the AI should have said instead, for use in training data
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub ideal_completion: Option<String>,
+}
+
+impl LlmCallRating {
+    pub fn as_insertable(&self) -> NewLlmCallRating {
+        NewLlmCallRating {
+            llm_call_id: &self.llm_call_id,
+            rating: &self.rating,
+            ideal_completion: self.ideal_completion.as_deref(),
+        }
+    }
+}
+
+impl ToSql<Text, Sqlite> for Rating
+where
+    String: ToSql<Text, Sqlite>,
+{
+    fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Sqlite>) -> serialize::Result {
+        let rating_str = match self {
+            Rating::Good => "good",
+            Rating::Bad => "bad",
+        };
+        out.set_value(rating_str);
+        Ok(IsNull::No)
+    }
+}
+
+impl<DB> FromSql<Text, DB> for Rating
+where
+    DB: Backend,
+    String: FromSql<Text, DB>,
+{
+    fn from_sql(bytes: DB::RawValue<'_>) -> deserialize::Result<Self> {
+        let rating_str = String::from_sql(bytes)?;
+        match rating_str.as_str() {
+            "good" => Ok(Rating::Good),
+            "bad" => Ok(Rating::Bad),
+            _ => Err("Invalid rating string".into()),
+        }
+    }
+}
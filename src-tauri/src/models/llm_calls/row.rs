@@ -15,7 +15,7 @@ pub struct LlmCallRow {
     pub provider: Service,
     pub llm_requested: String,
     pub llm: String,
-    pub temperature: f32,
+    pub temperature: Option<f32>,
     pub prompt_tokens: Option<i32>,
     pub response_tokens: Option<i32>,
     pub total_tokens: Option<i32>,
@@ -31,7 +31,7 @@ impl LlmCallRow {
             provider: &self.provider,
             llm_requested: &self.llm_requested,
             llm: &self.llm,
-            temperature: &self.temperature,
+            temperature: self.temperature.as_ref(),
             prompt_tokens: self.prompt_tokens.as_ref(),
             response_tokens: self.response_tokens.as_ref(),
             total_tokens: self.total_tokens.as_ref(),
@@ -49,7 +49,7 @@ pub struct NewLlmCallRow<'a> {
     pub provider: &'a Service,
     pub llm_requested: &'a str,
     pub llm: &'a str,
-    pub temperature: &'a f32,
+    pub temperature: Option<&'a f32>,
     pub prompt_tokens: Option<&'a i32>,
     pub response_tokens: Option<&'a i32>,
     pub total_tokens: Option<&'a i32>,
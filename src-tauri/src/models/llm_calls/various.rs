@@ -11,12 +11,19 @@ pub struct Llm {
     pub name: String,
     pub requested: String,
     pub provider: Service,
+    /// Azure deployment that served the call
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub deployment: Option<String>,
+    /// Saved key that the call was made with, if not the default one
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub key_profile: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
 pub struct Request {
     pub prompt: Prompt,
-    pub temperature: f32,
+    /// Unknown for calls imported from places that don't record it
+    pub temperature: Option<f32>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
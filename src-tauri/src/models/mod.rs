@@ -4,7 +4,11 @@ pub mod database_contents;
 pub mod llm_calls;
 pub mod os;
 pub mod shell;
+pub mod trash;
 
-pub use api_keys::{ApiKey, NewApiKey};
+pub use api_keys::{
+    ActiveApiKeyProfile, ApiKey, ApiKeyEncryption, ApiKeyOptionsRow, NewApiKey,
+    NewApiKeyOptions,
+};
 pub use database_contents::{DatabaseContents, LlmCallData};
 pub use llm_calls::EntityId;
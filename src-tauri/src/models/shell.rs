@@ -2,15 +2,112 @@ use serde::{Deserialize, Serialize};
 use specta::Type;
 
 use std::env;
+use std::path::Path;
+
+/// Trails every line that ZAMM writes, so that those lines can be told apart from
+/// ones that users wrote themselves.
+const ZAMM_MARKER: &str = "# zamm";
 
 #[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize, Type)]
 pub enum Shell {
     Bash,
     Zsh,
+    Fish,
     #[allow(clippy::enum_variant_names)]
     PowerShell,
 }
 
+impl Shell {
+    /// Goes by the name of the init file, and then by the shell ZAMM was started
+    /// from.
+    pub fn for_init_file(path: &Path) -> Shell {
+        let file_name = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .unwrap_or_default();
+        if file_name.ends_with(".fish") {
+            return Shell::Fish;
+        }
+        if file_name.ends_with(".ps1") {
+            return Shell::PowerShell;
+        }
+        if file_name.starts_with(".zsh") || file_name == ".zprofile" {
+            return Shell::Zsh;
+        }
+        if file_name.starts_with(".bash") || file_name == ".profile" {
+            return Shell::Bash;
+        }
+        get_shell().unwrap_or(Shell::Bash)
+    }
+
+    /// Line that sets the environment variable when the init file runs.
+    pub fn export_line(&self, name: &str, value: &str) -> String {
+        let assignment = match self {
+            Shell::Bash | Shell::Zsh => {
+                let escaped = escape(value, &['\\', '"', '$', '`'], '\\');
+                format!("export {}=\"{}\"", name, escaped)
+            }
+            Shell::Fish => {
+                let escaped = escape(value, &['\\', '"', '$'], '\\');
+                format!("set -gx {} \"{}\"", name, escaped)
+            }
+            Shell::PowerShell => {
+                let escaped = escape(value, &['`', '"', '$'], '`');
+                format!("$env:{} = \"{}\"", name, escaped)
+            }
+        };
+        format!("{} {}", assignment, ZAMM_MARKER)
+    }
+
+    /// Whether the line is one that `export_line` wrote for the environment
+    /// variable, or one that older versions of ZAMM wrote before lines got
+    /// marked. Lines that set the variable by hand are left alone.
+    pub fn is_export_line(&self, line: &str, name: &str) -> bool {
+        let line = line.trim();
+        if is_legacy_export_line(line, name) {
+            return true;
+        }
+        if !line.ends_with(ZAMM_MARKER) {
+            return false;
+        }
+        let rest = match self {
+            Shell::Bash | Shell::Zsh => line.strip_prefix("export "),
+            Shell::Fish => line.strip_prefix("set -gx "),
+            Shell::PowerShell => line.strip_prefix("$env:"),
+        };
+        let after_name = match rest.and_then(|r| r.strip_prefix(name)) {
+            Some(after_name) => after_name,
+            None => return false,
+        };
+        match self {
+            Shell::Bash | Shell::Zsh => after_name.starts_with('='),
+            Shell::Fish => after_name.starts_with(' '),
+            Shell::PowerShell => after_name.trim_start().starts_with('='),
+        }
+    }
+}
+
+/// Older versions of ZAMM appended `export NAME="value"` to the init file no
+/// matter which shell it was for, and without a marker.
+fn is_legacy_export_line(line: &str, name: &str) -> bool {
+    line.strip_prefix("export ")
+        .and_then(|rest| rest.strip_prefix(name))
+        .and_then(|rest| rest.strip_prefix("=\""))
+        .and_then(|rest| rest.strip_suffix('"'))
+        .is_some_and(|value| !value.contains('"'))
+}
+
+fn escape(value: &str, special_chars: &[char], escape_char: char) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        if special_chars.contains(&c) {
+            escaped.push(escape_char);
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
 pub fn get_shell() -> Option<Shell> {
     if let Ok(shell) = env::var("SHELL") {
         if shell.ends_with("/zsh") {
@@ -19,6 +116,9 @@ pub fn get_shell() -> Option<Shell> {
         if shell.ends_with("/bash") {
             return Some(Shell::Bash);
         }
+        if shell.ends_with("/fish") {
+            return Some(Shell::Fish);
+        }
     }
 
     if env::var("ZSH_NAME").is_ok() {
@@ -49,4 +149,48 @@ mod tests {
         );
         assert!(shell.is_some());
     }
+
+    #[test]
+    fn test_shell_for_init_file() {
+        let shell_for = |path: &str| Shell::for_init_file(Path::new(path));
+        assert_eq!(shell_for("/home/rando/.zshrc"), Shell::Zsh);
+        assert_eq!(shell_for("/home/rando/.bash_profile"), Shell::Bash);
+        assert_eq!(
+            shell_for("/home/rando/.config/fish/config.fish"),
+            Shell::Fish
+        );
+        assert_eq!(
+            shell_for("C:\\Users\\rando\\Microsoft.PowerShell_profile.ps1"),
+            Shell::PowerShell
+        );
+    }
+
+    #[test]
+    fn test_export_line_round_trip() {
+        for shell in [Shell::Bash, Shell::Zsh, Shell::Fish, Shell::PowerShell] {
+            let line = shell.export_line("OPENAI_API_KEY", "k3y-w1th-\"qu0t3s\"");
+            assert!(shell.is_export_line(&line, "OPENAI_API_KEY"));
+            assert!(!shell.is_export_line(&line, "OPENAI_API"));
+        }
+    }
+
+    #[test]
+    fn test_legacy_line_matched() {
+        let line = "export OPENAI_API_KEY=\"0p3n41-4p1-k3y\"";
+        for shell in [Shell::Bash, Shell::Zsh, Shell::Fish, Shell::PowerShell] {
+            assert!(shell.is_export_line(line, "OPENAI_API_KEY"));
+            assert!(!shell.is_export_line(line, "OPENAI_API"));
+        }
+    }
+
+    #[test]
+    fn test_hand_written_line_not_matched() {
+        for line in [
+            "export OPENAI_API_KEY='h4nd-wr1773n-k3y'",
+            "export OPENAI_API_KEY=\"$(cat ~/.openai-key)\" # from a file",
+            "export OPENAI_API_KEY=\"h4nd\" \"wr1773n\"",
+        ] {
+            assert!(!Shell::Bash.is_export_line(line, "OPENAI_API_KEY"));
+        }
+    }
 }
@@ -0,0 +1,56 @@
+use crate::models::llm_calls::{EntityId, LlmCallEmbedding, LlmCallVariant};
+use crate::models::LlmCallData;
+use crate::schema::llm_call_trash;
+use chrono::naive::NaiveDateTime;
+use diesel::backend::Backend;
+use diesel::deserialize::{self, FromSql, FromSqlRow};
+use diesel::expression::AsExpression;
+use diesel::prelude::*;
+use diesel::serialize::{self, IsNull, Output, ToSql};
+use diesel::sql_types::Text;
+use diesel::sqlite::Sqlite;
+use serde::{Deserialize, Serialize};
+
+/// Everything that was removed from the database when deleting some LLM calls,
+/// kept around so that the deletion can be undone.
+#[derive(Debug, Default, Serialize, Deserialize, AsExpression, FromSqlRow)]
+#[diesel(sql_type = Text)]
+pub struct TrashedLlmCalls {
+    pub llm_calls: LlmCallData,
+    /// Variant links that were created to replace a deleted canonical call
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub repaired_variants: Vec<LlmCallVariant>,
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub embeddings: Vec<LlmCallEmbedding>,
+}
+
+#[derive(Debug, Queryable, Selectable, Insertable)]
+#[diesel(table_name = llm_call_trash)]
+pub struct LlmCallTrashEntry {
+    pub id: EntityId,
+    pub deleted_at: NaiveDateTime,
+    pub contents: TrashedLlmCalls,
+}
+
+impl ToSql<Text, Sqlite> for TrashedLlmCalls
+where
+    String: ToSql<Text, Sqlite>,
+{
+    fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Sqlite>) -> serialize::Result {
+        let json_str = serde_json::to_string(&self)?;
+        out.set_value(json_str);
+        Ok(IsNull::No)
+    }
+}
+
+impl<DB> FromSql<Text, DB> for TrashedLlmCalls
+where
+    DB: Backend,
+    String: FromSql<Text, DB>,
+{
+    fn from_sql(bytes: DB::RawValue<'_>) -> deserialize::Result<Self> {
+        let json_str = String::from_sql(bytes)?;
+        let parsed_json: Self = serde_json::from_str(&json_str)?;
+        Ok(parsed_json)
+    }
+}
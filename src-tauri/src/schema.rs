@@ -1,8 +1,31 @@
 // @generated automatically by Diesel CLI.
 
 diesel::table! {
-    api_keys (service) {
+    active_api_key_profiles (service) {
         service -> Text,
+        profile -> Text,
+    }
+}
+
+diesel::table! {
+    api_key_encryption (salt) {
+        salt -> Text,
+        verifier -> Text,
+    }
+}
+
+diesel::table! {
+    api_key_options (service, profile) {
+        service -> Text,
+        profile -> Text,
+        options -> Text,
+    }
+}
+
+diesel::table! {
+    api_keys (service, profile) {
+        service -> Text,
+        profile -> Text,
         api_key -> Text,
     }
 }
@@ -17,6 +40,37 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    completed_upgrades (name) {
+        name -> Text,
+    }
+}
+
+diesel::table! {
+    llm_call_attributions (llm_call_id) {
+        llm_call_id -> Text,
+        organization -> Nullable<Text>,
+        project -> Nullable<Text>,
+        extra_header_names -> Nullable<Text>,
+    }
+}
+
+diesel::table! {
+    llm_call_deployments (llm_call_id) {
+        llm_call_id -> Text,
+        deployment -> Text,
+    }
+}
+
+diesel::table! {
+    llm_call_embeddings (llm_call_id, model, kind) {
+        llm_call_id -> Text,
+        model -> Text,
+        kind -> Text,
+        embedding -> Binary,
+    }
+}
+
 diesel::table! {
     llm_call_follow_ups (previous_call_id, next_call_id) {
         previous_call_id -> Text,
@@ -24,6 +78,36 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    llm_call_key_profiles (llm_call_id) {
+        llm_call_id -> Text,
+        profile -> Text,
+    }
+}
+
+diesel::table! {
+    llm_call_latencies (llm_call_id) {
+        llm_call_id -> Text,
+        latency_ms -> Integer,
+    }
+}
+
+diesel::table! {
+    llm_call_ratings (llm_call_id) {
+        llm_call_id -> Text,
+        rating -> Text,
+        ideal_completion -> Nullable<Text>,
+    }
+}
+
+diesel::table! {
+    llm_call_trash (id) {
+        id -> Text,
+        deleted_at -> Timestamp,
+        contents -> Text,
+    }
+}
+
 diesel::table! {
     llm_call_variants (canonical_id, variant_id) {
         canonical_id -> Text,
@@ -38,7 +122,7 @@ diesel::table! {
         provider -> Text,
         llm_requested -> Text,
         llm -> Text,
-        temperature -> Float,
+        temperature -> Nullable<Float>,
         prompt_tokens -> Nullable<Integer>,
         response_tokens -> Nullable<Integer>,
         total_tokens -> Nullable<Integer>,
@@ -47,10 +131,28 @@ diesel::table! {
     }
 }
 
+diesel::joinable!(llm_call_attributions -> llm_calls (llm_call_id));
+diesel::joinable!(llm_call_deployments -> llm_calls (llm_call_id));
+diesel::joinable!(llm_call_embeddings -> llm_calls (llm_call_id));
+diesel::joinable!(llm_call_key_profiles -> llm_calls (llm_call_id));
+diesel::joinable!(llm_call_latencies -> llm_calls (llm_call_id));
+diesel::joinable!(llm_call_ratings -> llm_calls (llm_call_id));
+
 diesel::allow_tables_to_appear_in_same_query!(
+    active_api_key_profiles,
+    api_key_encryption,
+    api_key_options,
     api_keys,
     asciicasts,
+    completed_upgrades,
+    llm_call_attributions,
+    llm_call_deployments,
+    llm_call_embeddings,
     llm_call_follow_ups,
+    llm_call_key_profiles,
+    llm_call_latencies,
+    llm_call_ratings,
+    llm_call_trash,
     llm_call_variants,
     llm_calls,
 );
@@ -1,14 +1,23 @@
-use crate::schema::api_keys;
-use crate::{commands::errors::ZammResult, models::ApiKey};
+use crate::schema::{active_api_key_profiles, api_key_options, api_keys};
+use crate::setup::encryption::{get_encryption, is_encrypted, KeyCipher};
+use crate::{
+    commands::errors::ZammResult,
+    commands::preferences::Preferences,
+    models::{ActiveApiKeyProfile, ApiKey, ApiKeyOptionsRow},
+};
 use anyhow::anyhow;
 use diesel;
 use diesel::deserialize::FromSqlRow;
 use diesel::expression::AsExpression;
 use diesel::prelude::*;
 use diesel::sql_types::Text;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
 use serde::{Deserialize, Serialize};
 use specta::Type;
+use std::collections::{BTreeMap, HashMap};
 use std::env;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
 use strum_macros::{Display, EnumString};
 
 #[derive(
@@ -29,23 +38,371 @@ use strum_macros::{Display, EnumString};
 pub enum Service {
     OpenAI,
     Ollama,
+    /// OpenAI models deployed on Azure
+    Azure,
+    /// Offline provider that responds locally, for demos and tests
+    Mock,
     #[strum(default)]
     Unknown(String),
 }
 
+impl Service {
+    /// Environment variables that the service's key can be set with, in order of
+    /// precedence.
+    pub fn key_env_vars(&self) -> &'static [&'static str] {
+        match self {
+            Service::OpenAI => &["OPENAI_API_KEY"],
+            Service::Azure => &["AZURE_OPENAI_API_KEY", "AZURE_API_KEY"],
+            _ => &[],
+        }
+    }
+}
+
+/// Settings that get sent along with every request made with a key, so that usage
+/// gets billed to the right place.
+#[derive(
+    Debug,
+    Default,
+    Clone,
+    Eq,
+    PartialEq,
+    Serialize,
+    Deserialize,
+    Type,
+    AsExpression,
+    FromSqlRow,
+)]
+#[diesel(sql_type = Text)]
+pub struct ApiKeyOptions {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub organization: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub project: Option<String>,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty", default)]
+    pub extra_headers: BTreeMap<String, String>,
+    /// Azure resource endpoint, such as `https://my-resource.openai.azure.com`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub endpoint: Option<String>,
+    /// Azure API version to request, or the default one if unset
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub api_version: Option<String>,
+    /// Azure deployment names, keyed by the model names that get requested in ZAMM
+    #[serde(skip_serializing_if = "BTreeMap::is_empty", default)]
+    pub deployments: BTreeMap<String, String>,
+}
+
+impl ApiKeyOptions {
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Deployments that aren't explicitly mapped are assumed to be named after
+    /// their models, as is the Azure default.
+    pub fn azure_deployment<'a>(&'a self, llm: &'a str) -> &'a str {
+        self.deployments.get(llm).map(|d| d.as_str()).unwrap_or(llm)
+    }
+
+    /// Headers to add to every OpenAI request.
+    pub fn openai_headers(&self) -> ZammResult<HeaderMap> {
+        let standard_headers = [
+            ("OpenAI-Organization", &self.organization),
+            ("OpenAI-Project", &self.project),
+        ];
+        let all_headers = standard_headers
+            .into_iter()
+            .filter_map(|(name, value)| value.as_ref().map(|v| (name, v.as_str())))
+            .chain(
+                self.extra_headers
+                    .iter()
+                    .map(|(name, value)| (name.as_str(), value.as_str())),
+            );
+
+        let mut headers = HeaderMap::new();
+        for (name, value) in all_headers {
+            let header_name = HeaderName::from_str(name)
+                .map_err(|e| anyhow!("Invalid header name \"{}\": {}", name, e))?;
+            let header_value = HeaderValue::from_str(value)
+                .map_err(|e| anyhow!("Invalid value for header {}: {}", name, e))?;
+            headers.insert(header_name, header_value);
+        }
+        Ok(headers)
+    }
+
+    fn map_header_values(
+        &self,
+        convert: impl Fn(&str) -> ZammResult<String>,
+    ) -> ZammResult<Self> {
+        let mut converted = self.clone();
+        for value in converted.extra_headers.values_mut() {
+            *value = convert(value)?;
+        }
+        Ok(converted)
+    }
+
+    /// Extra headers can carry credentials of their own, so their values get
+    /// stored encrypted whenever the keys are.
+    pub fn encrypt_headers(&self, cipher: &KeyCipher) -> ZammResult<Self> {
+        self.map_header_values(|value| cipher.encrypt(value))
+    }
+
+    /// Values saved before encryption got turned on are left as they are.
+    pub fn decrypt_headers(&self, cipher: &KeyCipher) -> ZammResult<Self> {
+        self.map_header_values(|value| {
+            if is_encrypted(value) {
+                cipher.decrypt(value)
+            } else {
+                Ok(value.to_string())
+            }
+        })
+    }
+
+    pub fn has_encrypted_headers(&self) -> bool {
+        self.extra_headers.values().any(|value| is_encrypted(value))
+    }
+}
+
+/// Name of the profile that a key gets saved under when none is specified.
+pub const DEFAULT_PROFILE: &str = "default";
+
+/// A key saved under a name, so that a service can have several keys on hand.
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize, Type)]
+pub struct ApiKeyProfile {
+    pub service: Service,
+    pub name: String,
+    pub api_key: String,
+    #[serde(skip_serializing_if = "ApiKeyOptions::is_default", default)]
+    pub options: ApiKeyOptions,
+}
+
+/// Where the active key of a service got loaded from.
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum ApiKeySource {
+    Database,
+    Environment,
+    DotEnv { path: String },
+}
+
+/// The `openai` and `azure` keys and options are the ones from the active profile
+/// of each service, which is the default profile unless `openai_profile` or
+/// `azure_profile` say otherwise.
 #[derive(Debug, Default, Clone, Eq, PartialEq, Serialize, Deserialize, Type)]
 pub struct ApiKeys {
     pub openai: Option<String>,
+    #[serde(skip_serializing_if = "ApiKeyOptions::is_default", default)]
+    pub openai_options: ApiKeyOptions,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub openai_profile: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub openai_source: Option<ApiKeySource>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub azure: Option<String>,
+    #[serde(skip_serializing_if = "ApiKeyOptions::is_default", default)]
+    pub azure_options: ApiKeyOptions,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub azure_profile: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub azure_source: Option<ApiKeySource>,
+    /// Keys saved under profiles that aren't currently active
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub profiles: Vec<ApiKeyProfile>,
+    /// Whether the stored keys are encrypted and still waiting on the passphrase
+    #[serde(skip_serializing_if = "std::ops::Not::not", default)]
+    pub locked: bool,
+    /// Encrypts keys on their way into the database, if encryption is on
+    #[serde(skip)]
+    pub cipher: Option<KeyCipher>,
+    /// `.env` files that the keys got loaded from, in case they need reloading
+    #[serde(skip)]
+    pub dotenv_files: Vec<PathBuf>,
 }
 
 impl ApiKeys {
+    fn active_profile_mut(
+        &mut self,
+        service: &Service,
+    ) -> ZammResult<&mut Option<String>> {
+        match service {
+            Service::OpenAI => Ok(&mut self.openai_profile),
+            Service::Azure => Ok(&mut self.azure_profile),
+            _ => Err(anyhow!("{:?} doesn't have key profiles", service).into()),
+        }
+    }
+
+    pub fn active_profile(&self, service: &Service) -> &str {
+        let active = match service {
+            Service::OpenAI => &self.openai_profile,
+            Service::Azure => &self.azure_profile,
+            _ => &None,
+        };
+        active.as_deref().unwrap_or(DEFAULT_PROFILE)
+    }
+
+    fn saved_key(&self, service: &Service, profile: &str) -> Option<&str> {
+        self.profiles
+            .iter()
+            .find(|p| &p.service == service && p.name == profile)
+            .map(|p| p.api_key.as_str())
+    }
+
+    /// Returns the key from the requested profile, or from the active one if no
+    /// profile is requested.
+    pub fn key_for(&self, service: &Service, profile: Option<&str>) -> Option<&str> {
+        match profile {
+            Some(name) if name != self.active_profile(service) => {
+                self.saved_key(service, name)
+            }
+            _ => match service {
+                Service::OpenAI => self.openai.as_deref(),
+                Service::Azure => self.azure.as_deref(),
+                _ => None,
+            },
+        }
+    }
+
+    /// The profile that the key returned by `key_for` was saved under, or `None` if
+    /// that key came from the environment or a `.env` file instead.
+    pub fn stored_key_profile<'a>(
+        &'a self,
+        service: &Service,
+        profile: Option<&'a str>,
+    ) -> Option<&'a str> {
+        match profile {
+            Some(name) if name != self.active_profile(service) => {
+                self.saved_key(service, name).map(|_| name)
+            }
+            _ => {
+                let source = match service {
+                    Service::OpenAI => &self.openai_source,
+                    Service::Azure => &self.azure_source,
+                    _ => &None,
+                };
+                match source {
+                    Some(ApiKeySource::Database) => Some(self.active_profile(service)),
+                    _ => None,
+                }
+            }
+        }
+    }
+
+    /// Returns the options of the requested profile, or of the active one if no
+    /// profile is requested.
+    pub fn options_for(
+        &self,
+        service: &Service,
+        profile: Option<&str>,
+    ) -> ApiKeyOptions {
+        match profile {
+            Some(name) if name != self.active_profile(service) => self
+                .profiles
+                .iter()
+                .find(|p| &p.service == service && p.name == name)
+                .map(|p| p.options.clone())
+                .unwrap_or_default(),
+            _ => match service {
+                Service::OpenAI => self.openai_options.clone(),
+                Service::Azure => self.azure_options.clone(),
+                _ => ApiKeyOptions::default(),
+            },
+        }
+    }
+
+    pub fn save_profile(
+        &mut self,
+        service: &Service,
+        profile: &str,
+        key: String,
+    ) -> ZammResult<()> {
+        if profile == self.active_profile(service) {
+            return self.update(service, key);
+        }
+
+        self.active_profile_mut(service)?;
+        // a new key for the profile shouldn't lose the options set up for it
+        let options = self.options_for(service, Some(profile));
+        self.profiles
+            .retain(|p| !(&p.service == service && p.name == profile));
+        self.profiles.push(ApiKeyProfile {
+            service: service.clone(),
+            name: profile.to_string(),
+            api_key: key,
+            options,
+        });
+        Ok(())
+    }
+
+    pub fn remove_profile(
+        &mut self,
+        service: &Service,
+        profile: &str,
+    ) -> ZammResult<()> {
+        if profile == self.active_profile(service) {
+            return self.remove(service);
+        }
+
+        self.active_profile_mut(service)?;
+        self.profiles
+            .retain(|p| !(&p.service == service && p.name == profile));
+        Ok(())
+    }
+
+    /// Switches the service over to the key saved under the profile. Only the
+    /// default profile can be switched to without having a key saved.
+    pub fn activate_profile(
+        &mut self,
+        service: &Service,
+        profile: &str,
+    ) -> ZammResult<()> {
+        let previous_profile = self.active_profile(service).to_string();
+        if profile == previous_profile {
+            return Ok(());
+        }
+        let key = self.saved_key(service, profile).map(|k| k.to_string());
+        let options = self.options_for(service, Some(profile));
+        if key.is_none() && profile != DEFAULT_PROFILE {
+            return Err(anyhow!(
+                "No {:?} key saved under profile \"{}\"",
+                service,
+                profile
+            )
+            .into());
+        }
+
+        // the previously active key goes back to being just another saved key
+        let previous_key = self.key_for(service, None).map(|k| k.to_string());
+        let previous_options = self.options_for(service, None);
+        *self.active_profile_mut(service)? = if profile == DEFAULT_PROFILE {
+            None
+        } else {
+            Some(profile.to_string())
+        };
+        if let Some(k) = previous_key {
+            self.save_profile(service, &previous_profile, k)?;
+            self.set_options(service, Some(&previous_profile), previous_options)?;
+        }
+        self.profiles
+            .retain(|p| !(&p.service == service && p.name == profile));
+        self.set_options(service, None, options)?;
+        match key {
+            Some(k) => self.update(service, k),
+            None => self.remove(service),
+        }
+    }
+
     pub fn update(&mut self, service: &Service, key: String) -> ZammResult<()> {
         match service {
             Service::OpenAI => {
                 self.openai = Some(key);
+                self.openai_source = Some(ApiKeySource::Database);
+                Ok(())
+            }
+            Service::Azure => {
+                self.azure = Some(key);
+                self.azure_source = Some(ApiKeySource::Database);
                 Ok(())
             }
             Service::Ollama => Err(anyhow!("Ollama doesn't take API keys").into()),
+            Service::Mock => Err(anyhow!("Mock provider doesn't take API keys").into()),
             Service::Unknown(_) => {
                 Err(anyhow!("Can't update API keys for unknown service").into())
             }
@@ -56,52 +413,252 @@ impl ApiKeys {
         match service {
             Service::OpenAI => {
                 self.openai = None;
+                self.openai_source = None;
+                Ok(())
+            }
+            Service::Azure => {
+                self.azure = None;
+                self.azure_source = None;
                 Ok(())
             }
             Service::Ollama => Err(anyhow!("Ollama doesn't take API keys").into()),
+            Service::Mock => Err(anyhow!("Mock provider doesn't take API keys").into()),
             Service::Unknown(_) => {
                 Err(anyhow!("Can't delete API keys for unknown service").into())
             }
         }
     }
+
+    /// Sets the options of the requested profile, or of the active one if no
+    /// profile is requested. Inactive profiles need to have a key saved first.
+    pub fn set_options(
+        &mut self,
+        service: &Service,
+        profile: Option<&str>,
+        options: ApiKeyOptions,
+    ) -> ZammResult<()> {
+        if let Some(name) = profile.filter(|p| *p != self.active_profile(service)) {
+            let saved_profile = self
+                .profiles
+                .iter_mut()
+                .find(|p| &p.service == service && p.name == name)
+                .ok_or_else(|| {
+                    anyhow!("No {:?} key saved under profile \"{}\"", service, name)
+                })?;
+            saved_profile.options = options;
+            return Ok(());
+        }
+
+        match service {
+            Service::OpenAI => {
+                self.openai_options = options;
+                Ok(())
+            }
+            Service::Azure => {
+                self.azure_options = options;
+                Ok(())
+            }
+            _ => Err(
+                anyhow!("Key options are only supported for OpenAI and Azure").into(),
+            ),
+        }
+    }
+
+    /// Database keys get overridden by keys from `.env` files, which in turn get
+    /// overridden by keys from the environment.
+    pub fn load_environment(&mut self, dotenv_files: Vec<PathBuf>) {
+        let environment = KeyEnvironment::load(&dotenv_files);
+        if let Some((key, source)) = environment.get(Service::OpenAI.key_env_vars()) {
+            self.openai = Some(key);
+            self.openai_source = Some(source);
+        }
+        if let Some(openai_org_id) = environment.value("OPENAI_ORG_ID") {
+            self.openai_options.organization = Some(openai_org_id);
+        }
+        if let Some(openai_project_id) = environment.value("OPENAI_PROJECT_ID") {
+            self.openai_options.project = Some(openai_project_id);
+        }
+        if let Some((key, source)) = environment.get(Service::Azure.key_env_vars()) {
+            self.azure = Some(key);
+            self.azure_source = Some(source);
+        }
+        if let Some(azure_endpoint) = environment.value("AZURE_OPENAI_ENDPOINT") {
+            self.azure_options.endpoint = Some(azure_endpoint);
+        }
+        if let Some(azure_api_version) = environment.value("OPENAI_API_VERSION") {
+            self.azure_options.api_version = Some(azure_api_version);
+        }
+        self.dotenv_files = dotenv_files;
+    }
+}
+
+/// Looks up variables in the process environment first, and then in each of the
+/// `.env` files in order.
+struct KeyEnvironment {
+    dotenv_values: Vec<(PathBuf, HashMap<String, String>)>,
+}
+
+impl KeyEnvironment {
+    fn load(dotenv_files: &[PathBuf]) -> Self {
+        let dotenv_values = dotenv_files
+            .iter()
+            .filter(|path| path.is_file())
+            .filter_map(|path| match read_dotenv(path) {
+                Ok(values) => Some((path.clone(), values)),
+                Err(e) => {
+                    eprintln!("Error reading {}: {}", path.display(), e);
+                    None
+                }
+            })
+            .collect();
+        Self { dotenv_values }
+    }
+
+    fn get(&self, names: &[&str]) -> Option<(String, ApiKeySource)> {
+        if let Some(value) = names.iter().find_map(|name| env::var(name).ok()) {
+            return Some((value, ApiKeySource::Environment));
+        }
+        self.dotenv_values.iter().find_map(|(path, values)| {
+            let value = names.iter().find_map(|name| values.get(*name))?;
+            let source = ApiKeySource::DotEnv {
+                path: path.display().to_string(),
+            };
+            Some((value.clone(), source))
+        })
+    }
+
+    fn value(&self, name: &str) -> Option<String> {
+        self.get(&[name]).map(|(value, _)| value)
+    }
+}
+
+fn read_dotenv(path: &Path) -> Result<HashMap<String, String>, dotenvy::Error> {
+    dotenvy::from_path_iter(path)?.collect()
+}
+
+/// The `.env` files listed in the preferences, or else the one in the current
+/// directory followed by the one in the config directory.
+pub fn dotenv_files(
+    config_dir: &Option<PathBuf>,
+    preferences: &Preferences,
+) -> Vec<PathBuf> {
+    if let Some(files) = &preferences.dotenv_files {
+        return files.iter().map(PathBuf::from).collect();
+    }
+    let current_dir = env::current_dir().ok();
+    [current_dir.as_ref(), config_dir.as_ref()]
+        .into_iter()
+        .flatten()
+        .map(|dir| dir.join(".env"))
+        .collect()
 }
 
-pub fn setup_api_keys(possible_db: &mut Option<SqliteConnection>) -> ApiKeys {
-    let mut api_keys = ApiKeys { openai: None };
+/// Encrypted keys only get loaded if the cipher from the unlock step is passed in.
+/// Otherwise, they get skipped and the keys are marked as locked.
+pub fn setup_api_keys(
+    possible_db: &mut Option<SqliteConnection>,
+    cipher: Option<KeyCipher>,
+    dotenv_files: Vec<PathBuf>,
+) -> ApiKeys {
+    let mut api_keys = ApiKeys::default();
 
     if let Some(conn) = possible_db.as_mut() {
+        match get_encryption(conn) {
+            Ok(encryption) => {
+                api_keys.locked = encryption.is_some() && cipher.is_none()
+            }
+            Err(e) => eprintln!("Error reading key encryption settings: {}", e),
+        }
+
         let load_result: Result<Vec<ApiKey>, diesel::result::Error> =
             api_keys::table.load(conn);
         if let Ok(api_keys_rows) = load_result {
             for api_key in api_keys_rows {
-                if let Err(e) = api_keys.update(&api_key.service, api_key.api_key) {
+                let key = if is_encrypted(&api_key.api_key) {
+                    match &cipher {
+                        Some(c) => c.decrypt(&api_key.api_key),
+                        None => {
+                            api_keys.locked = true;
+                            continue;
+                        }
+                    }
+                } else {
+                    Ok(api_key.api_key)
+                };
+                if let Err(e) = key.and_then(|k| {
+                    api_keys.save_profile(&api_key.service, &api_key.profile, k)
+                }) {
                     eprintln!("Error reading API key for {}: {}", api_key.service, e);
                 }
             }
         }
-    }
 
-    // database keys will get overridden by environment keys
-    if let Ok(openai_api_key) = env::var("OPENAI_API_KEY") {
-        api_keys.openai = Some(openai_api_key);
+        let active_result: Result<Vec<ActiveApiKeyProfile>, diesel::result::Error> =
+            active_api_key_profiles::table.load(conn);
+        if let Ok(active_rows) = active_result {
+            for row in active_rows {
+                if let Err(e) = api_keys.activate_profile(&row.service, &row.profile) {
+                    eprintln!(
+                        "Error activating key profile for {}: {}",
+                        row.service, e
+                    );
+                }
+            }
+        }
+
+        let options_result: Result<Vec<ApiKeyOptionsRow>, diesel::result::Error> =
+            api_key_options::table.load(conn);
+        if let Ok(options_rows) = options_result {
+            for row in options_rows {
+                let options = if row.options.has_encrypted_headers() {
+                    match &cipher {
+                        Some(c) => row.options.decrypt_headers(c),
+                        None => {
+                            api_keys.locked = true;
+                            continue;
+                        }
+                    }
+                } else {
+                    Ok(row.options)
+                };
+                if let Err(e) = options.and_then(|o| {
+                    api_keys.set_options(&row.service, Some(&row.profile), o)
+                }) {
+                    eprintln!("Error reading key options for {}: {}", row.service, e);
+                }
+            }
+        }
     }
 
+    api_keys.load_environment(dotenv_files);
+    api_keys.cipher = cipher;
     api_keys
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::models::NewApiKey;
+    use crate::models::{NewApiKey, NewApiKeyOptions};
+    use crate::schema::api_key_encryption;
+    use crate::setup::encryption::new_encryption;
     use crate::test_helpers::database::setup_database;
+    use crate::test_helpers::temp_files::get_temp_test_dir;
+    use std::fs;
     use temp_env;
 
     const DUMMY_API_KEY: &str = "0p3n41-4p1-k3y";
+    const DOTENV_API_KEY: &str = "d0t3nv-4p1-k3y";
+
+    fn write_dotenv(test_name: &str, contents: &str) -> PathBuf {
+        let dotenv_path = get_temp_test_dir(test_name).join(".env");
+        fs::write(&dotenv_path, contents).unwrap();
+        dotenv_path
+    }
 
     #[test]
     fn test_get_empty_api_keys_no_db() {
         temp_env::with_var("OPENAI_API_KEY", None::<String>, || {
-            let api_keys = setup_api_keys(&mut None);
+            let api_keys = setup_api_keys(&mut None, None, Vec::new());
             assert!(api_keys.openai.is_none());
         });
     }
@@ -109,7 +666,7 @@ mod tests {
     #[test]
     fn test_get_present_api_keys_no_db() {
         temp_env::with_var("OPENAI_API_KEY", Some(DUMMY_API_KEY), || {
-            let api_keys = setup_api_keys(&mut None);
+            let api_keys = setup_api_keys(&mut None, None, Vec::new());
             assert_eq!(api_keys.openai, Some(DUMMY_API_KEY.to_string()));
         });
     }
@@ -121,13 +678,15 @@ mod tests {
             diesel::insert_into(api_keys::table)
                 .values(&NewApiKey {
                     service: Service::OpenAI,
+                    profile: DEFAULT_PROFILE,
                     api_key: DUMMY_API_KEY,
                 })
                 .execute(&mut conn)
                 .unwrap();
 
-            let api_keys = setup_api_keys(&mut Some(conn));
+            let api_keys = setup_api_keys(&mut Some(conn), None, Vec::new());
             assert_eq!(api_keys.openai, Some(DUMMY_API_KEY.to_string()));
+            assert_eq!(api_keys.openai_source, Some(ApiKeySource::Database));
         });
     }
 
@@ -140,13 +699,217 @@ mod tests {
             diesel::insert_into(api_keys::table)
                 .values(&NewApiKey {
                     service: Service::OpenAI,
+                    profile: DEFAULT_PROFILE,
                     api_key: DUMMY_API_KEY,
                 })
                 .execute(&mut conn)
                 .unwrap();
 
-            let api_keys = setup_api_keys(&mut Some(conn));
+            let api_keys = setup_api_keys(&mut Some(conn), None, Vec::new());
             assert_eq!(api_keys.openai, Some(custom_api_key.to_string()));
+            assert_eq!(api_keys.openai_source, Some(ApiKeySource::Environment));
+            assert_eq!(api_keys.stored_key_profile(&Service::OpenAI, None), None);
+        });
+    }
+
+    #[test]
+    fn test_dotenv_key_overrides_db_key() {
+        temp_env::with_var("OPENAI_API_KEY", None::<String>, || {
+            let mut conn = setup_database(None);
+            diesel::insert_into(api_keys::table)
+                .values(&NewApiKey {
+                    service: Service::OpenAI,
+                    profile: DEFAULT_PROFILE,
+                    api_key: DUMMY_API_KEY,
+                })
+                .execute(&mut conn)
+                .unwrap();
+            let dotenv_path = write_dotenv(
+                "test_dotenv_key_overrides_db_key",
+                &format!("OPENAI_API_KEY={}\n", DOTENV_API_KEY),
+            );
+
+            let api_keys =
+                setup_api_keys(&mut Some(conn), None, vec![dotenv_path.clone()]);
+            assert_eq!(api_keys.openai, Some(DOTENV_API_KEY.to_string()));
+            assert_eq!(
+                api_keys.openai_source,
+                Some(ApiKeySource::DotEnv {
+                    path: dotenv_path.display().to_string()
+                })
+            );
+        });
+    }
+
+    #[test]
+    fn test_env_key_overrides_dotenv_key() {
+        temp_env::with_vars(
+            [
+                ("AZURE_OPENAI_API_KEY", None),
+                ("AZURE_API_KEY", Some(DUMMY_API_KEY)),
+            ],
+            || {
+                let dotenv_path = write_dotenv(
+                    "test_env_key_overrides_dotenv_key",
+                    &format!("export AZURE_OPENAI_API_KEY=\"{}\"\n", DOTENV_API_KEY),
+                );
+
+                let api_keys = setup_api_keys(&mut None, None, vec![dotenv_path]);
+                assert_eq!(api_keys.azure, Some(DUMMY_API_KEY.to_string()));
+                assert_eq!(api_keys.azure_source, Some(ApiKeySource::Environment));
+            },
+        );
+    }
+
+    #[test]
+    fn test_missing_dotenv_file_ignored() {
+        temp_env::with_var("OPENAI_API_KEY", None::<String>, || {
+            let dotenv_path =
+                get_temp_test_dir("test_missing_dotenv_file_ignored").join(".env");
+
+            let api_keys = setup_api_keys(&mut None, None, vec![dotenv_path]);
+            assert_eq!(api_keys.openai, None);
+            assert_eq!(api_keys.openai_source, None);
+        });
+    }
+
+    #[test]
+    fn test_get_azure_key_from_db() {
+        temp_env::with_var("AZURE_OPENAI_API_KEY", None::<String>, || {
+            let mut conn = setup_database(None);
+            diesel::insert_into(api_keys::table)
+                .values(&NewApiKey {
+                    service: Service::Azure,
+                    profile: DEFAULT_PROFILE,
+                    api_key: DUMMY_API_KEY,
+                })
+                .execute(&mut conn)
+                .unwrap();
+
+            let api_keys = setup_api_keys(&mut Some(conn), None, Vec::new());
+            assert_eq!(api_keys.azure, Some(DUMMY_API_KEY.to_string()));
+        });
+    }
+
+    #[test]
+    fn test_active_profile_from_db() {
+        let team_api_key = "t34m-4p1-k3y";
+
+        temp_env::with_var("OPENAI_API_KEY", None::<String>, || {
+            let mut conn = setup_database(None);
+            diesel::insert_into(api_keys::table)
+                .values(&vec![
+                    NewApiKey {
+                        service: Service::OpenAI,
+                        profile: DEFAULT_PROFILE,
+                        api_key: DUMMY_API_KEY,
+                    },
+                    NewApiKey {
+                        service: Service::OpenAI,
+                        profile: "team",
+                        api_key: team_api_key,
+                    },
+                ])
+                .execute(&mut conn)
+                .unwrap();
+            diesel::insert_into(active_api_key_profiles::table)
+                .values(&ActiveApiKeyProfile {
+                    service: Service::OpenAI,
+                    profile: "team".to_string(),
+                })
+                .execute(&mut conn)
+                .unwrap();
+
+            let api_keys = setup_api_keys(&mut Some(conn), None, Vec::new());
+            assert_eq!(api_keys.openai, Some(team_api_key.to_string()));
+            assert_eq!(api_keys.active_profile(&Service::OpenAI), "team");
+            assert_eq!(
+                api_keys.stored_key_profile(&Service::OpenAI, None),
+                Some("team")
+            );
+            assert_eq!(
+                api_keys.key_for(&Service::OpenAI, Some(DEFAULT_PROFILE)),
+                Some(DUMMY_API_KEY)
+            );
+        });
+    }
+
+    #[test]
+    fn test_options_follow_active_profile() {
+        let team_api_key = "t34m-4p1-k3y";
+        let team_options = ApiKeyOptions {
+            organization: Some("org-team".to_string()),
+            ..Default::default()
+        };
+
+        temp_env::with_var("OPENAI_API_KEY", None::<String>, || {
+            let mut conn = setup_database(None);
+            diesel::insert_into(api_keys::table)
+                .values(&vec![
+                    NewApiKey {
+                        service: Service::OpenAI,
+                        profile: DEFAULT_PROFILE,
+                        api_key: DUMMY_API_KEY,
+                    },
+                    NewApiKey {
+                        service: Service::OpenAI,
+                        profile: "team",
+                        api_key: team_api_key,
+                    },
+                ])
+                .execute(&mut conn)
+                .unwrap();
+            diesel::insert_into(api_key_options::table)
+                .values(&NewApiKeyOptions {
+                    service: Service::OpenAI,
+                    profile: "team",
+                    options: &team_options,
+                })
+                .execute(&mut conn)
+                .unwrap();
+
+            let mut api_keys = setup_api_keys(&mut Some(conn), None, Vec::new());
+            assert!(api_keys.openai_options.is_default());
+            assert_eq!(
+                api_keys.options_for(&Service::OpenAI, Some("team")),
+                team_options
+            );
+
+            api_keys.activate_profile(&Service::OpenAI, "team").unwrap();
+            assert_eq!(api_keys.openai_options, team_options);
+            assert!(api_keys
+                .options_for(&Service::OpenAI, Some(DEFAULT_PROFILE))
+                .is_default());
+        });
+    }
+
+    #[test]
+    fn test_encrypted_key_from_db() {
+        temp_env::with_var("OPENAI_API_KEY", None::<String>, || {
+            let mut conn = setup_database(None);
+            let (cipher, encryption) = new_encryption("correct horse").unwrap();
+            diesel::insert_into(api_key_encryption::table)
+                .values(&encryption)
+                .execute(&mut conn)
+                .unwrap();
+            diesel::insert_into(api_keys::table)
+                .values(&NewApiKey {
+                    service: Service::OpenAI,
+                    profile: DEFAULT_PROFILE,
+                    api_key: &cipher.encrypt(DUMMY_API_KEY).unwrap(),
+                })
+                .execute(&mut conn)
+                .unwrap();
+            let mut possible_db = Some(conn);
+
+            let locked_keys = setup_api_keys(&mut possible_db, None, Vec::new());
+            assert!(locked_keys.locked);
+            assert_eq!(locked_keys.openai, None);
+
+            let unlocked_keys =
+                setup_api_keys(&mut possible_db, Some(cipher), Vec::new());
+            assert!(!unlocked_keys.locked);
+            assert_eq!(unlocked_keys.openai, Some(DUMMY_API_KEY.to_string()));
         });
     }
 
@@ -155,7 +918,7 @@ mod tests {
         temp_env::with_var("OPENAI_API_KEY", None::<String>, || {
             let conn = setup_database(None);
 
-            let api_keys = setup_api_keys(&mut Some(conn));
+            let api_keys = setup_api_keys(&mut Some(conn), None, Vec::new());
             assert_eq!(api_keys.openai, None);
         });
     }
@@ -0,0 +1,170 @@
+use crate::commands::errors::ZammResult;
+use crate::models::ApiKeyEncryption;
+use crate::schema::api_key_encryption;
+use anyhow::anyhow;
+use argon2::Argon2;
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use diesel::prelude::*;
+use std::env;
+use std::fmt;
+
+/// Stored keys start with this, so that keys saved before encryption got turned on
+/// can still be told apart from encrypted ones.
+const ENCRYPTED_PREFIX: &str = "enc:v1:";
+const VERIFIER_PLAINTEXT: &str = "zamm";
+const SALT_LENGTH: usize = 16;
+const NONCE_LENGTH: usize = 12;
+
+pub fn is_encrypted(stored_key: &str) -> bool {
+    stored_key.starts_with(ENCRYPTED_PREFIX)
+}
+
+/// Encrypts stored API keys with a key derived from the user's passphrase.
+#[derive(Clone, PartialEq, Eq)]
+pub struct KeyCipher {
+    key: [u8; 32],
+}
+
+impl fmt::Debug for KeyCipher {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("KeyCipher(..)")
+    }
+}
+
+impl KeyCipher {
+    fn derive(passphrase: &str, salt: &str) -> ZammResult<Self> {
+        let salt_bytes = STANDARD
+            .decode(salt)
+            .map_err(|e| anyhow!("Invalid key encryption salt: {}", e))?;
+        let mut key = [0u8; 32];
+        Argon2::default()
+            .hash_password_into(passphrase.as_bytes(), &salt_bytes, &mut key)
+            .map_err(|e| anyhow!("Error deriving encryption key: {}", e))?;
+        Ok(Self { key })
+    }
+
+    fn aead(&self) -> ChaCha20Poly1305 {
+        ChaCha20Poly1305::new(Key::from_slice(&self.key))
+    }
+
+    pub fn encrypt(&self, plaintext: &str) -> ZammResult<String> {
+        let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let ciphertext = self
+            .aead()
+            .encrypt(&nonce, plaintext.as_bytes())
+            .map_err(|_| anyhow!("Error encrypting API key"))?;
+        let mut payload = nonce.to_vec();
+        payload.extend(ciphertext);
+        Ok(format!("{}{}", ENCRYPTED_PREFIX, STANDARD.encode(payload)))
+    }
+
+    pub fn decrypt(&self, stored_key: &str) -> ZammResult<String> {
+        let encoded = stored_key
+            .strip_prefix(ENCRYPTED_PREFIX)
+            .ok_or(anyhow!("API key isn't encrypted"))?;
+        let payload = STANDARD
+            .decode(encoded)
+            .map_err(|e| anyhow!("Invalid encrypted API key: {}", e))?;
+        if payload.len() < NONCE_LENGTH {
+            return Err(anyhow!("Invalid encrypted API key: too short").into());
+        }
+        let (nonce, ciphertext) = payload.split_at(NONCE_LENGTH);
+        let plaintext = self
+            .aead()
+            .decrypt(Nonce::from_slice(nonce), ciphertext)
+            .map_err(|_| anyhow!("API key can't be decrypted with this passphrase"))?;
+        let api_key = String::from_utf8(plaintext)
+            .map_err(|e| anyhow!("Decrypted API key isn't valid text: {}", e))?;
+        Ok(api_key)
+    }
+}
+
+/// Sets up encryption under a new passphrase, returning the cipher along with the
+/// settings that need to be stored for it to be unlocked later.
+pub fn new_encryption(passphrase: &str) -> ZammResult<(KeyCipher, ApiKeyEncryption)> {
+    if passphrase.is_empty() {
+        return Err(anyhow!("Passphrase can't be empty").into());
+    }
+    let mut salt_bytes = [0u8; SALT_LENGTH];
+    OsRng.fill_bytes(&mut salt_bytes);
+    let salt = STANDARD.encode(salt_bytes);
+    let cipher = KeyCipher::derive(passphrase, &salt)?;
+    let verifier = cipher.encrypt(VERIFIER_PLAINTEXT)?;
+    Ok((cipher, ApiKeyEncryption { salt, verifier }))
+}
+
+pub fn get_encryption(
+    conn: &mut SqliteConnection,
+) -> ZammResult<Option<ApiKeyEncryption>> {
+    let settings = api_key_encryption::table
+        .first::<ApiKeyEncryption>(conn)
+        .optional()?;
+    Ok(settings)
+}
+
+pub fn unlock(settings: &ApiKeyEncryption, passphrase: &str) -> ZammResult<KeyCipher> {
+    let cipher = KeyCipher::derive(passphrase, &settings.salt)?;
+    match cipher.decrypt(&settings.verifier) {
+        Ok(verifier) if verifier == VERIFIER_PLAINTEXT => Ok(cipher),
+        _ => Err(anyhow!("Incorrect passphrase").into()),
+    }
+}
+
+/// Runs before keys get loaded at startup. Unless `ZAMM_KEY_PASSPHRASE` is set,
+/// encrypted keys stay locked until they're unlocked from within the app.
+pub fn unlock_at_startup(
+    possible_db: &mut Option<SqliteConnection>,
+) -> Option<KeyCipher> {
+    let conn = possible_db.as_mut()?;
+    let settings = match get_encryption(conn) {
+        Ok(settings) => settings?,
+        Err(e) => {
+            eprintln!("Error reading key encryption settings: {}", e);
+            return None;
+        }
+    };
+    let passphrase = env::var("ZAMM_KEY_PASSPHRASE").ok()?;
+    match unlock(&settings, &passphrase) {
+        Ok(cipher) => Some(cipher),
+        Err(e) => {
+            eprintln!("Couldn't unlock API keys: {}", e);
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let (cipher, settings) = new_encryption("correct horse").unwrap();
+        let encrypted = cipher.encrypt("0p3n41-4p1-k3y").unwrap();
+        assert!(is_encrypted(&encrypted));
+        assert!(!encrypted.contains("0p3n41-4p1-k3y"));
+
+        let unlocked = unlock(&settings, "correct horse").unwrap();
+        assert_eq!(unlocked.decrypt(&encrypted).unwrap(), "0p3n41-4p1-k3y");
+    }
+
+    #[test]
+    fn test_wrong_passphrase() {
+        let (_, settings) = new_encryption("correct horse").unwrap();
+        let result = unlock(&settings, "battery staple");
+        assert_eq!(result.unwrap_err().to_string(), "Incorrect passphrase");
+    }
+
+    #[test]
+    fn test_tampered_key() {
+        let (cipher, _) = new_encryption("correct horse").unwrap();
+        let mut encrypted = cipher.encrypt("0p3n41-4p1-k3y").unwrap();
+        let start = ENCRYPTED_PREFIX.len();
+        encrypted.replace_range(start..start + 4, "AAAA");
+        assert!(cipher.decrypt(&encrypted).is_err());
+    }
+}
@@ -1,4 +1,5 @@
 pub mod api_keys;
 pub mod db;
+pub mod encryption;
 
 pub use db::get_db;
@@ -1,4 +1,4 @@
-use crate::commands::database::{read_database_contents, write_database_contents};
+use crate::commands::database::{get_database_contents, read_database_contents};
 use crate::commands::errors::ZammResult;
 use crate::commands::terminal::{ActualTerminal, Terminal};
 use crate::models::llm_calls::EntityId;
@@ -62,15 +62,21 @@ fn copy_missing_gold_file(expected_path_abs: &Path, actual_path_abs: &Path) {
     );
 }
 
+/// Latencies differ on every run, so they get left out of YAML dumps just like
+/// they get left out of SQL dumps.
+async fn dump_yaml(zamm_db: &ZammDatabase, yaml_dump_abs: &Path) {
+    let mut db_contents = get_database_contents(zamm_db, false, true).await.unwrap();
+    db_contents.llm_calls.latencies.clear();
+    fs::write(yaml_dump_abs, serde_yaml::to_string(&db_contents).unwrap()).unwrap();
+}
+
 async fn dump_sql_to_yaml(
     expected_sql_dump_abs: &PathBuf,
     expected_yaml_dump_abs: &Path,
 ) {
     let zamm_db = ZammDatabase(Mutex::new(Some(setup_database(None))));
     load_sqlite_database(&zamm_db, expected_sql_dump_abs).await;
-    write_database_contents(&zamm_db, expected_yaml_dump_abs.to_str().unwrap(), false)
-        .await
-        .unwrap();
+    dump_yaml(&zamm_db, expected_yaml_dump_abs).await;
 }
 
 async fn dump_yaml_to_sql(
@@ -434,7 +440,8 @@ where
                     .unwrap()
                     .with_mode(vcr_mode.clone())
                     .with_modify_request(|req| {
-                        req.headers = censor_headers(&req.headers, &["authorization"]);
+                        req.headers =
+                            censor_headers(&req.headers, &["authorization", "api-key"]);
                     })
                     .with_modify_response(|resp| {
                         resp.headers =
@@ -555,13 +562,7 @@ where
             let db_info = test_db_info.unwrap();
             let actual_db_yaml_dump = db_info.temp_db_dir.join("dump.yaml");
             let actual_db_sql_dump = db_info.temp_db_dir.join("dump.sql");
-            write_database_contents(
-                test_db,
-                actual_db_yaml_dump.to_str().unwrap(),
-                false,
-            )
-            .await
-            .unwrap();
+            dump_yaml(test_db, &actual_db_yaml_dump).await;
             dump_sqlite_database(&db_info.temp_db_file, &actual_db_sql_dump);
 
             setup_gold_db_files(
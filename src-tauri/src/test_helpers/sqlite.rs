@@ -14,10 +14,14 @@ pub async fn load_sqlite_database(zamm_db: &ZammDatabase, dump_path: &PathBuf) {
 pub fn dump_sqlite_database(db_path: &PathBuf, dump_path: &PathBuf) {
     let dump_output = std::process::Command::new("sqlite3")
         .arg(db_path)
-        // avoid the inserts into __diesel_schema_migrations
-        .arg(
-            ".dump api_keys llm_calls llm_call_follow_ups llm_call_variants asciicasts",
-        )
+        // avoid the inserts into __diesel_schema_migrations, as well as
+        // llm_call_latencies because those differ on every run
+        .arg(concat!(
+            ".dump api_keys api_key_options active_api_key_profiles ",
+            "api_key_encryption llm_calls llm_call_follow_ups llm_call_variants ",
+            "llm_call_ratings asciicasts llm_call_trash llm_call_embeddings ",
+            "llm_call_attributions llm_call_deployments llm_call_key_profiles"
+        ))
         .output()
         .expect("Error running sqlite3 .dump command");
     // filter output by lines starting with "INSERT"
@@ -1,12 +1,15 @@
 use crate::commands::errors::ZammResult;
 use crate::commands::terminal::{ActualTerminal, Terminal};
-use crate::models::asciicasts::AsciiCastData;
-use asciicast::EventType;
+use crate::models::asciicasts::{AsciiCastData, Entry, EventType, TerminalSize};
 
 use either::Either::{self, Left, Right};
 use std::thread::sleep;
 use std::time::Duration;
 
+/// Output that arrives without a pause this long in between gets treated as a
+/// single response, both when recording and when replaying sessions.
+const QUIET_PERIOD: Duration = Duration::from_millis(500);
+
 pub struct TestTerminal {
     recording_file: String,
     terminal: Either<AsciiCastData, ActualTerminal>,
@@ -26,11 +29,18 @@ impl TestTerminal {
         }
     }
 
+    pub fn read_updates(&mut self) -> ZammResult<String> {
+        match &mut self.terminal {
+            Left(_) => Ok(self.next_output()),
+            Right(actual_terminal) => read_until_quiet(actual_terminal),
+        }
+    }
+
     pub fn set_entry_index(&mut self, index: usize) {
         self.entry_index = index;
     }
 
-    fn next_entry(&mut self) -> &asciicast::Entry {
+    fn next_entry(&mut self) -> &Entry {
         match &self.terminal {
             Left(cast) => {
                 let entry = &cast.entries[self.entry_index];
@@ -40,6 +50,41 @@ impl TestTerminal {
             Right(_) => panic!("Expected recording"),
         }
     }
+
+    /// The real terminal records output in chunks as it arrives, so gather up
+    /// everything that got printed before the program went quiet.
+    fn next_output(&mut self) -> String {
+        let entry = self.next_entry();
+        assert_eq!(entry.event_type, EventType::Output);
+        let mut last_time = entry.time;
+        let mut output = entry.event_data.clone();
+        while let Left(cast) = &self.terminal {
+            match cast.entries.get(self.entry_index) {
+                Some(entry)
+                    if entry.event_type == EventType::Output
+                        && entry.time - last_time < QUIET_PERIOD.as_secs_f64() =>
+                {
+                    last_time = entry.time;
+                    output.push_str(&entry.event_data);
+                    self.entry_index += 1;
+                }
+                _ => break,
+            }
+        }
+        output
+    }
+}
+
+fn read_until_quiet(terminal: &mut ActualTerminal) -> ZammResult<String> {
+    let mut output = String::new();
+    loop {
+        sleep(QUIET_PERIOD);
+        let partial = terminal.read_updates()?;
+        if partial.is_empty() {
+            return Ok(output);
+        }
+        output.push_str(&partial);
+    }
 }
 
 impl Drop for TestTerminal {
@@ -55,28 +100,20 @@ impl Drop for TestTerminal {
 }
 
 impl Terminal for TestTerminal {
-    fn run_command(&mut self, command: &str) -> ZammResult<String> {
+    fn run_command(&mut self, command: &str, size: TerminalSize) -> ZammResult<String> {
         match &mut self.terminal {
             Left(cast) => {
                 let expected_command = cast.header.command.as_ref().unwrap();
                 assert_eq!(command, expected_command);
+                assert_eq!(cast.size(), size);
 
-                let entry = self.next_entry();
-                assert_eq!(entry.event_type, EventType::Output);
-                Ok(entry.event_data.clone())
+                Ok(self.next_output())
             }
-            Right(actual_terminal) => actual_terminal.run_command(command),
-        }
-    }
-
-    fn read_updates(&mut self) -> ZammResult<String> {
-        match &mut self.terminal {
-            Left(_) => {
-                let entry = self.next_entry();
-                assert_eq!(entry.event_type, EventType::Output);
-                Ok(entry.event_data.clone())
+            Right(actual_terminal) => {
+                let mut output = actual_terminal.run_command(command, size)?;
+                output.push_str(&read_until_quiet(actual_terminal)?);
+                Ok(output)
             }
-            Right(actual_terminal) => actual_terminal.read_updates(),
         }
     }
 
@@ -87,11 +124,25 @@ impl Terminal for TestTerminal {
                 assert_eq!(input_entry.event_type, EventType::Input);
                 assert_eq!(input_entry.event_data, input);
 
-                let output_entry = self.next_entry();
-                assert_eq!(output_entry.event_type, EventType::Output);
-                Ok(output_entry.event_data.clone())
+                Ok(self.next_output())
+            }
+            Right(actual_terminal) => {
+                let mut output = actual_terminal.send_input(input)?;
+                output.push_str(&read_until_quiet(actual_terminal)?);
+                Ok(output)
             }
-            Right(actual_terminal) => actual_terminal.send_input(input),
+        }
+    }
+
+    fn resize(&mut self, size: TerminalSize) -> ZammResult<()> {
+        match &mut self.terminal {
+            Left(_) => {
+                let entry = self.next_entry();
+                assert_eq!(entry.event_type, EventType::Resize);
+                assert_eq!(entry.event_data, size.to_string());
+                Ok(())
+            }
+            Right(actual_terminal) => actual_terminal.resize(size),
         }
     }
 
@@ -104,6 +155,14 @@ impl Terminal for TestTerminal {
             Right(actual_terminal) => actual_terminal.get_cast(),
         }
     }
+
+    fn exit_code(&self) -> ZammResult<Option<u32>> {
+        match &self.terminal {
+            // recordings don't say when the program exited
+            Left(_) => Ok(None),
+            Right(actual_terminal) => actual_terminal.exit_code(),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -114,16 +173,32 @@ mod tests {
     async fn test_terminal_replay() {
         let mut terminal = TestTerminal::new("api/sample-terminal-sessions/date.cast");
         let output = terminal
-            .run_command("date \"+%A %B %e, %Y %R %z\"")
+            .run_command("date \"+%A %B %e, %Y %R %z\"", TerminalSize::default())
             .unwrap();
         assert_eq!(output, "Friday September 20, 2024 18:23 +0700\r\n");
     }
 
+    #[tokio::test]
+    async fn test_unicode_replay() {
+        let mut terminal =
+            TestTerminal::new("api/sample-terminal-sessions/unicode.cast");
+        let output = terminal
+            .run_command(
+                "python api/sample-terminal-sessions/unicode.py",
+                TerminalSize::default(),
+            )
+            .unwrap();
+        assert_eq!(output, "សួស្តី ពិភពលោក\r\n┌────┐\r\n│ ok │\r\n└────┘\r\n👋\r\n");
+    }
+
     #[tokio::test]
     async fn test_terminal_pause() {
         let mut terminal = TestTerminal::new("api/sample-terminal-sessions/pause.cast");
         terminal
-            .run_command("python api/sample-terminal-sessions/pause.py")
+            .run_command(
+                "python api/sample-terminal-sessions/pause.py",
+                TerminalSize::default(),
+            )
             .unwrap();
 
         sleep(Duration::from_millis(1_000));
@@ -134,7 +209,9 @@ mod tests {
     #[tokio::test]
     async fn test_interactivity() {
         let mut terminal = TestTerminal::new("api/sample-terminal-sessions/bash.cast");
-        terminal.run_command("bash").unwrap();
+        terminal
+            .run_command("bash", TerminalSize::default())
+            .unwrap();
         let output = terminal
             .send_input("python api/sample-terminal-sessions/interleaved.py\n")
             .unwrap();
@@ -144,11 +221,35 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_resize_replay() {
+        let mut terminal =
+            TestTerminal::new("api/sample-terminal-sessions/resize.cast");
+        terminal
+            .run_command("bash --norc --noprofile", TerminalSize::default())
+            .unwrap();
+        assert_eq!(terminal.get_cast().unwrap().header.width, 80);
+
+        terminal
+            .resize(TerminalSize {
+                rows: 40,
+                cols: 120,
+            })
+            .unwrap();
+        let cast = terminal.get_cast().unwrap();
+        assert_eq!(cast.header.width, 80);
+        assert_eq!(cast.entries.last().unwrap().event_data, "120x40");
+        let output = terminal.send_input("stty size\n").unwrap();
+        assert!(output.contains("40 120\r\n"), "Output: {:?}", output);
+    }
+
     #[tokio::test]
     async fn test_windows_interactivity() {
         let mut terminal =
             TestTerminal::new("api/sample-terminal-sessions/windows.cast");
-        terminal.run_command("cmd").unwrap();
+        terminal
+            .run_command("cmd", TerminalSize::default())
+            .unwrap();
         terminal.send_input("dir\r\n").unwrap();
         let output = terminal.send_input("echo %cd%\r\n").unwrap();
         assert!(output.contains("src-tauri"));
@@ -2,8 +2,9 @@ use std::env;
 use std::path::PathBuf;
 
 use crate::commands::{
-    errors::ZammResult,
+    errors::{Error, ZammResult},
     preferences::{get_preferences_file_contents, set_preferences_helper},
+    search::index_unindexed_terminal_sessions,
 };
 use anyhow::anyhow;
 use chrono::NaiveDateTime;
@@ -13,7 +14,7 @@ use diesel::prelude::*;
 use crate::models::llm_calls::ChatPrompt;
 use crate::models::llm_calls::EntityId;
 use crate::models::llm_calls::Prompt;
-use crate::schema::{llm_call_follow_ups, llm_calls};
+use crate::schema::{completed_upgrades, llm_call_follow_ups, llm_calls};
 use crate::ZammDatabase;
 
 async fn upgrade_to_v_0_1_4(zamm_db: &ZammDatabase) -> ZammResult<()> {
@@ -51,6 +52,7 @@ async fn upgrade_to_v_0_1_4(zamm_db: &ZammDatabase) -> ZammResult<()> {
                 let previous_messages = chat_prompt.messages[..length - 2].to_vec();
                 let previous_prompt = Prompt::Chat(ChatPrompt {
                     messages: previous_messages,
+                    attachments: Vec::new(),
                 });
                 let previous_completion = &chat_prompt.messages[length - 2];
                 (previous_prompt, previous_completion)
@@ -87,6 +89,42 @@ async fn upgrade_to_v_0_1_4(zamm_db: &ZammDatabase) -> ZammResult<()> {
     Ok(())
 }
 
+const INDEX_TERMINAL_SESSIONS_UPGRADE: &str = "index_terminal_sessions";
+
+/// Indexes terminal sessions that were recorded before search was available. This
+/// gets recorded in the database once done, instead of being tied to whichever
+/// release it first ships in.
+async fn index_existing_terminal_sessions(zamm_db: &ZammDatabase) -> ZammResult<()> {
+    let mut db = zamm_db.0.lock().await;
+    let conn = db.as_mut().ok_or(anyhow!("Failed to lock database"))?;
+
+    let already_done = completed_upgrades::table
+        .find(INDEX_TERMINAL_SESSIONS_UPGRADE)
+        .select(completed_upgrades::name)
+        .first::<String>(conn)
+        .optional()?
+        .is_some();
+    if already_done {
+        return Ok(());
+    }
+
+    let num_sessions_indexed = conn.transaction::<usize, Error, _>(|conn| {
+        let num_sessions_indexed = index_unindexed_terminal_sessions(conn)?;
+        diesel::insert_into(completed_upgrades::table)
+            .values(completed_upgrades::name.eq(INDEX_TERMINAL_SESSIONS_UPGRADE))
+            .execute(conn)?;
+        Ok(num_sessions_indexed)
+    })?;
+    if num_sessions_indexed > 0 {
+        println!(
+            "Data migration: Indexed {} terminal sessions for search",
+            num_sessions_indexed
+        );
+    }
+
+    Ok(())
+}
+
 fn version_before(a: &Option<String>, b: &str) -> bool {
     match a {
         None => true,
@@ -108,6 +146,8 @@ pub async fn handle_app_upgrades(
         upgrade_to_v_0_1_4(zamm_db).await?;
     }
 
+    index_existing_terminal_sessions(zamm_db).await?;
+
     if version_before(&preferences.version, current_version) {
         preferences.version = Some(current_version.to_string());
         set_preferences_helper(preferences_dir, &preferences)?;